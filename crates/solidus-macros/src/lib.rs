@@ -6,6 +6,7 @@
 //! - `#[method]` - Generate wrappers for Ruby instance methods
 //! - `#[function]` - Generate wrappers for Ruby global/module functions
 //! - `#[wrap]` - Derive TypedData implementation for Rust types (planned)
+//! - `#[methods]` - Define a Ruby class from an `impl` block
 //!
 //! These macros are re-exported by the main `solidus` crate and should not be
 //! used directly.
@@ -52,6 +53,7 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::{
     Expr, FnArg, GenericArgument, ItemFn, Lit, Meta, Pat, PathArguments, Token, Type,
     parse_macro_input, punctuated::Punctuated,
@@ -62,12 +64,66 @@ type MacroResult<T> = Result<T, syn::Error>;
 
 /// Information about a parsed parameter.
 struct ParamInfo {
+    /// The parameter name
+    name: syn::Ident,
+    /// Whether the parameter is marked `#[kwarg]`
+    kwarg: bool,
+    /// Whether the type is `Option<T>`
+    is_option: bool,
+    /// Whether the type is `Block` (the method's block, not a Ruby argument)
+    is_block: bool,
+    /// Whether the type is `&Context` (the wrapper's Context, not a Ruby argument)
+    is_context: bool,
+    /// The `#[default = expr]` value, if any
+    default: Option<Expr>,
     /// Whether the type is already `Pin<&StackPinned<T>>`
     is_explicit_pinned: bool,
     /// Whether this type needs pinning (false for Rust primitives, true for Ruby VALUE types)
     needs_pinning: bool,
-    /// The inner type (T if `Pin<&StackPinned<T>>`, or the original type)
+    /// The inner type (T if `Pin<&StackPinned<T>>` or `&T`, or the original type)
     inner_type: Type,
+    /// How a `&T`/`&mut T` parameter borrows the wrapped data, if it is one
+    borrow: Option<Borrow>,
+}
+
+/// How a typed self parameter borrows the wrapped `TypedData`.
+#[derive(Clone, Copy)]
+enum Borrow {
+    /// `&T`, unwrapped with `typed_data::get`
+    Shared,
+    /// `&mut T`, unwrapped with `typed_data::get_mut`
+    Mut,
+}
+
+/// The number of parameters passed by Ruby, i.e. excluding any `Block` or `&Context`.
+fn ruby_arg_count(params: &[ParamInfo]) -> usize {
+    params
+        .iter()
+        .filter(|param| param.implicit_arg().is_none())
+        .count()
+}
+
+impl ParamInfo {
+    /// Whether the argument may be omitted by the Ruby caller.
+    fn is_optional(&self) -> bool {
+        self.is_option || self.default.is_some()
+    }
+
+    /// Whether the wrapper has to parse `argc`/`argv` itself.
+    fn needs_variadic(&self) -> bool {
+        self.kwarg || self.is_optional()
+    }
+
+    /// The argument passed for a parameter that Ruby doesn't supply.
+    fn implicit_arg(&self) -> Option<proc_macro2::TokenStream> {
+        if self.is_block {
+            Some(quote! { solidus::method::Block::new() })
+        } else if self.is_context {
+            Some(quote! { &ctx })
+        } else {
+            None
+        }
+    }
 }
 
 /// Check if a type is `Pin<&StackPinned<T>>` and extract the inner type T.
@@ -124,6 +180,58 @@ fn extract_pinned_inner_type(ty: &Type) -> Option<Type> {
     Some(inner_type.clone())
 }
 
+/// Check if a type is `Option<T>` and extract the inner type T.
+fn extract_option_inner_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let last_seg = type_path.path.segments.last()?;
+    if last_seg.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &last_seg.arguments else {
+        return None;
+    };
+
+    let GenericArgument::Type(inner_type) = args.args.first()? else {
+        return None;
+    };
+
+    Some(inner_type.clone())
+}
+
+/// Check if a type is `Block` (or `Block<'_>`), the block passed to the method.
+fn is_block_type(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "Block")
+}
+
+/// Check if a type is a reference to `Context`, returning whether it is `&mut`.
+fn context_reference(ty: &Type) -> Option<bool> {
+    let Type::Reference(reference) = ty else {
+        return None;
+    };
+    let Type::Path(type_path) = reference.elem.as_ref() else {
+        return None;
+    };
+
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "Context")
+        .then_some(reference.mutability.is_some())
+}
+
 /// Check if a type is a Rust primitive type that doesn't need pinning.
 ///
 /// These types create new Rust data via `TryConvert` rather than wrapping a Ruby VALUE,
@@ -164,13 +272,29 @@ fn parse_param(param: &FnArg) -> MacroResult<ParamInfo> {
         ));
     };
 
-    let Pat::Ident(_pat_ident) = pat_type.pat.as_ref() else {
+    let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
         return Err(syn::Error::new_spanned(
             &pat_type.pat,
             "expected identifier pattern",
         ));
     };
 
+    let kwarg = pat_type
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("kwarg"));
+
+    let mut default = None;
+    for attr in &pat_type.attrs {
+        if !attr.path().is_ident("default") {
+            continue;
+        }
+        let Meta::NameValue(name_value) = &attr.meta else {
+            return Err(syn::Error::new_spanned(attr, "expected #[default = expr]"));
+        };
+        default = Some(name_value.value.clone());
+    }
+
     let ty = (*pat_type.ty).clone();
     let (is_explicit_pinned, inner_type) = if let Some(inner) = extract_pinned_inner_type(&ty) {
         (true, inner)
@@ -178,6 +302,69 @@ fn parse_param(param: &FnArg) -> MacroResult<ParamInfo> {
         (false, ty.clone())
     };
 
+    // `&Context` receives the Context the wrapper creates
+    if let Some(mutable) = context_reference(&ty) {
+        if mutable || kwarg || default.is_some() {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "Context parameters must be a plain &Context",
+            ));
+        }
+        return Ok(ParamInfo {
+            name: pat_ident.ident.clone(),
+            kwarg,
+            is_option: false,
+            is_block: false,
+            is_context: true,
+            default,
+            is_explicit_pinned: false,
+            needs_pinning: false,
+            inner_type: ty,
+            borrow: None,
+        });
+    }
+
+    // `&T` and `&mut T` borrow wrapped data from the receiver
+    if let Type::Reference(reference) = &ty {
+        if kwarg || default.is_some() {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "reference parameters cannot be #[kwarg] or have a #[default]",
+            ));
+        }
+        let borrow = if reference.mutability.is_some() {
+            Borrow::Mut
+        } else {
+            Borrow::Shared
+        };
+        return Ok(ParamInfo {
+            name: pat_ident.ident.clone(),
+            kwarg,
+            is_option: false,
+            is_block: false,
+            is_context: false,
+            default,
+            is_explicit_pinned: false,
+            needs_pinning: false,
+            inner_type: (*reference.elem).clone(),
+            borrow: Some(borrow),
+        });
+    }
+
+    if (kwarg || default.is_some()) && is_block_type(&inner_type) {
+        return Err(syn::Error::new_spanned(
+            &pat_type.ty,
+            "Block parameters cannot be #[kwarg] or have a #[default]",
+        ));
+    }
+
+    if kwarg && is_explicit_pinned {
+        return Err(syn::Error::new_spanned(
+            &pat_type.ty,
+            "#[kwarg] parameters cannot be Pin<&StackPinned<T>>; use the type directly",
+        ));
+    }
+
     // Determine if this type needs pinning:
     // - Explicit Pin<&StackPinned<T>> always needs pinning (user requested it)
     // - Rust primitives (i64, f64, bool, String, etc.) don't need pinning
@@ -189,12 +376,51 @@ fn parse_param(param: &FnArg) -> MacroResult<ParamInfo> {
     };
 
     Ok(ParamInfo {
+        name: pat_ident.ident.clone(),
+        kwarg,
+        is_option: extract_option_inner_type(&inner_type).is_some(),
+        is_block: is_block_type(&inner_type),
+        is_context: false,
+        default,
         is_explicit_pinned,
         needs_pinning,
         inner_type,
+        borrow: None,
     })
 }
 
+/// Reject `&T`/`&mut T` parameters other than a method's self.
+///
+/// Only the receiver can be borrowed: a guard for an argument could conflict
+/// with the receiver's (`a.merge!(a)`), which is better handled explicitly
+/// with `typed_data::get` in the function body.
+fn reject_borrowed_args(params: &[&FnArg], parsed: &[ParamInfo]) -> MacroResult<()> {
+    for (param, info) in params.iter().zip(parsed) {
+        if info.borrow.is_some() {
+            return Err(syn::Error::new_spanned(
+                param,
+                "only the self parameter of a #[solidus::method] can be a reference; \
+                 take a Value and use solidus::typed_data::get instead",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Remove the parameter attributes understood by `#[method]`/`#[function]`.
+///
+/// The original function is emitted unchanged otherwise, and Rust rejects
+/// unknown attributes on function parameters.
+fn strip_param_attrs(sig: &mut syn::Signature) {
+    for param in sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = param {
+            pat_type
+                .attrs
+                .retain(|attr| !attr.path().is_ident("kwarg") && !attr.path().is_ident("default"));
+        }
+    }
+}
+
 /// Marks a function as the Ruby extension entry point.
 ///
 /// The function must have the signature `fn(&Ruby) -> Result<(), Error>`.
@@ -633,7 +859,7 @@ pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - Panic catching via `std::panic::catch_unwind`
 /// - Type conversion of `self` via `TryConvert`
 /// - Type conversion and stack pinning of arguments
-/// - Error propagation (converts `Err` to Ruby exceptions)
+/// - Error propagation (converts `Err` to Ruby exceptions, raised after all locals are dropped)
 /// - Return value conversion via `ReturnValue`
 ///
 /// # Arguments
@@ -705,7 +931,7 @@ pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     
 ///     pub const ARITY: i32 = 0;
 ///     
-///     pub fn wrapper() -> unsafe extern "C" fn() -> solidus::rb_sys::VALUE {
+///     pub fn wrapper() -> solidus::method::MethodFn<ARITY> {
 ///         // ... wrapper implementation
 ///     }
 /// }
@@ -732,22 +958,105 @@ pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 ///
 /// // Register with Ruby using the generated module:
-/// // class.define_method("concat", __solidus_method_concat::wrapper(), __solidus_method_concat::ARITY)?;
+/// // class.define_method("concat", __solidus_method_concat::wrapper(),)?;
+/// ```
+///
+/// # Keyword Arguments
+///
+/// Parameters marked `#[kwarg]` are taken from Ruby keyword arguments, using the
+/// parameter name as the keyword. An `Option<T>` keyword is optional; any other
+/// type is required. Missing or unknown keywords raise `ArgumentError` with
+/// Ruby's own messages.
+///
+/// ```ignore
+/// // def connect(path, host:, port: nil)
+/// #[solidus::method]
+/// fn connect(rb_self: Value, path: String, #[kwarg] host: String, #[kwarg] port: Option<i64>) -> Result<bool, Error> {
+///     // ...
+/// }
+/// ```
+///
+/// A method with keywords is registered with arity -1 (`ARITY` is `-1`), and the
+/// wrapper checks the positional argument count itself.
+///
+/// # Optional Arguments
+///
+/// `Option<T>` parameters are optional: omitting the argument or passing `nil`
+/// gives `None`. Any parameter can instead be given a default with
+/// `#[default = expr]`, used when the argument is omitted. Optional parameters
+/// must come after the required ones, and make the method variadic (`ARITY` is
+/// `-1`) with Ruby-style arity errors such as
+/// `wrong number of arguments (given 3, expected 1..2)`.
+///
+/// ```ignore
+/// // def scale(value, factor = 2, offset = nil)
+/// #[solidus::method]
+/// fn scale(rb_self: Value, value: i64, #[default = 2] factor: i64, offset: Option<i64>) -> Result<i64, Error> {
+///     Ok(value * factor + offset.unwrap_or(0))
+/// }
+/// ```
+///
+/// `#[default = expr]` also works on `#[kwarg]` parameters.
+///
+/// # Blocks
+///
+/// A parameter of type `Block` receives the block passed to the method, for
+/// `is_given()`, `yield_value` and `yield_values`. It is not a Ruby argument
+/// and doesn't count towards the arity.
+///
+/// # Wrapped Data
+///
+/// The self parameter can be `&T` or `&mut T` for a `TypedData` type `T`, so
+/// methods on `#[solidus::wrap]` structs don't have to unwrap self by hand.
+/// The wrapper borrows the wrapped value with `typed_data::get`/`get_mut` for
+/// the duration of the call. A receiver that doesn't wrap a `T` raises
+/// `TypeError` (`wrong argument type Integer (expected Counter)`), and a
+/// conflicting borrow (for example Ruby code re-entering a `&mut self` method
+/// on the same object) raises `RuntimeError`.
+///
+/// ```ignore
+/// #[solidus::method]
+/// fn increment(counter: &mut Counter, by: i64) -> Result<i64, Error> {
+///     counter.count += by;
+///     Ok(counter.count)
+/// }
+/// ```
+///
+/// Other parameters can't be references.
+///
+/// # Context
+///
+/// A `&Context` parameter, usually the first one as with `method!`, receives a
+/// `Context` created by the wrapper. It is not a Ruby argument and doesn't
+/// count towards the arity. The wrapper creates the Context the signature
+/// asks for, so `ctx: &'ctx Context<'ctx, 32>` gets 32 slots instead of the
+/// default 8. A function generic over the capacity gets it from the attribute:
+///
+/// ```ignore
+/// #[solidus::method(slots = 32)]
+/// fn build<'ctx, const N: usize>(ctx: &'ctx Context<'ctx, N>, rb_self: Value) -> Result<Value, Error> {
+///     // ...
+/// }
 /// ```
 ///
 /// # Supported Arities
 ///
-/// Currently supports arities 0-2 (self + 0-2 arguments).
+/// Currently supports arities 0-2 (self + 0-2 arguments), plus any number of
+/// positional arguments when optional or `#[kwarg]` parameters are present.
 ///
 /// # Safety
 ///
 /// The generated wrapper function is marked `unsafe extern "C"` because it interfaces
 /// directly with Ruby's C API. The wrapper handles all safety concerns internally.
 #[proc_macro_attribute]
-pub fn method(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn method(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match parse_item_args(attr.into(), &["slots"]) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let input_fn = parse_macro_input!(item as ItemFn);
 
-    match method_impl(input_fn) {
+    match method_impl(args, input_fn) {
         Ok(tokens) => tokens,
         Err(e) => e.to_compile_error().into(),
     }
@@ -823,7 +1132,7 @@ pub fn method(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     
 ///     pub const ARITY: i32 = 2;
 ///     
-///     pub fn wrapper() -> unsafe extern "C" fn() -> solidus::rb_sys::VALUE {
+///     pub fn wrapper() -> solidus::method::MethodFn<ARITY> {
 ///         // ... wrapper implementation
 ///     }
 /// }
@@ -848,30 +1157,55 @@ pub fn method(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 ///
 /// // Register with Ruby using the generated module:
-/// // ruby.define_global_function("greet", __solidus_function_greet::wrapper(), __solidus_function_greet::ARITY)?;
+/// // ruby.define_global_function("greet", __solidus_function_greet::wrapper(),)?;
 /// ```
 ///
+/// # Keyword Arguments
+///
+/// As with `#[solidus::method]`, parameters marked `#[kwarg]` are taken from Ruby
+/// keyword arguments and make the function variadic (`ARITY` is `-1`).
+///
+/// # Optional Arguments
+///
+/// As with `#[solidus::method]`, `Option<T>` and `#[default = expr]` parameters
+/// are optional and make the function variadic (`ARITY` is `-1`).
+///
+/// # Blocks
+///
+/// As with `#[solidus::method]`, a `Block` parameter receives the block passed to
+/// the function and doesn't count towards the arity.
+///
+/// # Context
+///
+/// As with `#[solidus::method]`, a `&Context` parameter receives a `Context`
+/// with the capacity of its type, or of `#[solidus::function(slots = N)]`.
+///
 /// # Supported Arities
 ///
-/// Currently supports arities 0-2.
+/// Currently supports arities 0-2, plus any number of positional arguments when
+/// optional or `#[kwarg]` parameters are present.
 ///
 /// # Safety
 ///
 /// The generated wrapper function is marked `unsafe extern "C"` because it interfaces
 /// directly with Ruby's C API. The wrapper handles all safety concerns internally.
 #[proc_macro_attribute]
-pub fn function(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match parse_item_args(attr.into(), &["slots"]) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let input_fn = parse_macro_input!(item as ItemFn);
 
-    match function_impl(input_fn) {
+    match function_impl(args, input_fn) {
         Ok(tokens) => tokens,
         Err(e) => e.to_compile_error().into(),
     }
 }
 
 /// Implementation of the #[method] attribute macro.
-fn method_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
-    let fn_name = &input_fn.sig.ident;
+fn method_impl(args: ItemArgs, mut input_fn: ItemFn) -> MacroResult<TokenStream> {
+    let fn_name = &input_fn.sig.ident.clone();
     let module_name = syn::Ident::new(
         &format!("__solidus_method_{}", fn_name),
         proc_macro2::Span::call_site(),
@@ -880,25 +1214,44 @@ fn method_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
     // Extract parameters
     let params: Vec<_> = input_fn.sig.inputs.iter().collect();
 
-    // First parameter must be self (the Ruby receiver)
-    if params.is_empty() {
+    // Parse all parameters
+    let mut parsed_params = Vec::new();
+    for param in &params {
+        parsed_params.push(parse_param(param)?);
+    }
+
+    // The first parameter after any &Context must be self (the Ruby receiver)
+    let self_index = parsed_params
+        .iter()
+        .take_while(|param| param.is_context)
+        .count();
+    if self_index == params.len() {
         return Err(syn::Error::new_spanned(
             &input_fn.sig,
             "method must have at least one parameter (self)",
         ));
     }
 
-    // Parse all parameters
-    let mut parsed_params = Vec::new();
-    for param in &params {
-        parsed_params.push(parse_param(param)?);
+    if parsed_params[self_index].needs_variadic() || parsed_params[self_index].is_block {
+        return Err(syn::Error::new_spanned(
+            params[self_index],
+            "the self parameter cannot be optional, a #[kwarg] or a Block",
+        ));
     }
+    reject_borrowed_args(&params[self_index + 1..], &parsed_params[self_index + 1..])?;
 
-    // Arity is number of parameters minus self
-    let arity = (params.len() - 1) as i32;
+    // Arity is number of parameters minus self, or -1 when arguments are
+    // optional or keywords are accepted
+    let arity = if parsed_params.iter().any(ParamInfo::needs_variadic) {
+        -1
+    } else {
+        ruby_arg_count(&parsed_params[self_index + 1..]) as i32
+    };
 
     // Generate the wrapper based on parsed parameters
-    let wrapper_fn = generate_method_wrapper_dynamic(fn_name, &parsed_params)?;
+    let wrapper_fn =
+        generate_method_wrapper_dynamic(&quote! { #fn_name }, &parsed_params, args.slots.as_ref())?;
+    strip_param_attrs(&mut input_fn.sig);
 
     let expanded = quote! {
         // Keep the original function
@@ -915,7 +1268,7 @@ fn method_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
             /// Returns the extern "C" wrapper function pointer for this method.
             ///
             /// This can be passed to `define_method` for Ruby method registration.
-            pub fn wrapper() -> unsafe extern "C" fn() -> solidus::rb_sys::VALUE {
+            pub fn wrapper() -> solidus::method::MethodFn<ARITY> {
                 #wrapper_fn
             }
         }
@@ -925,8 +1278,8 @@ fn method_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
 }
 
 /// Implementation of the #[function] attribute macro.
-fn function_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
-    let fn_name = &input_fn.sig.ident;
+fn function_impl(args: ItemArgs, mut input_fn: ItemFn) -> MacroResult<TokenStream> {
+    let fn_name = &input_fn.sig.ident.clone();
     let module_name = syn::Ident::new(
         &format!("__solidus_function_{}", fn_name),
         proc_macro2::Span::call_site(),
@@ -940,12 +1293,23 @@ fn function_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
     for param in &params {
         parsed_params.push(parse_param(param)?);
     }
+    reject_borrowed_args(&params, &parsed_params)?;
 
-    // Arity is number of parameters (no self for functions)
-    let arity = params.len() as i32;
+    // Arity is number of parameters (no self for functions), or -1 when
+    // arguments are optional or keywords are accepted
+    let arity = if parsed_params.iter().any(ParamInfo::needs_variadic) {
+        -1
+    } else {
+        ruby_arg_count(&parsed_params) as i32
+    };
 
     // Generate the wrapper based on parsed parameters
-    let wrapper_fn = generate_function_wrapper_dynamic(fn_name, &parsed_params)?;
+    let wrapper_fn = generate_function_wrapper_dynamic(
+        &quote! { #fn_name },
+        &parsed_params,
+        args.slots.as_ref(),
+    )?;
+    strip_param_attrs(&mut input_fn.sig);
 
     let expanded = quote! {
         // Keep the original function
@@ -963,7 +1327,7 @@ fn function_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
             ///
             /// This can be passed to `define_global_function` or `define_module_function`
             /// for Ruby function registration.
-            pub fn wrapper() -> unsafe extern "C" fn() -> solidus::rb_sys::VALUE {
+            pub fn wrapper() -> solidus::method::MethodFn<ARITY> {
                 #wrapper_fn
             }
         }
@@ -972,135 +1336,614 @@ fn function_impl(input_fn: ItemFn) -> MacroResult<TokenStream> {
     Ok(TokenStream::from(expanded))
 }
 
-/// Generate the extern "C" wrapper for a method dynamically based on parsed parameters.
-///
-/// This function generates a wrapper that handles both explicit `Pin<&StackPinned<T>>`
-/// parameters and implicit pinning for simple types.
-fn generate_method_wrapper_dynamic(
-    fn_name: &syn::Ident,
-    params: &[ParamInfo],
-) -> MacroResult<proc_macro2::TokenStream> {
-    let arity = params.len().saturating_sub(1);
-    if arity > 2 {
-        return Err(syn::Error::new(
+/// Helper struct to hold parsed methods attributes.
+struct MethodsArgs {
+    class_name: String,
+    alloc: bool,
+}
+
+/// Parse the arguments to the #[methods] attribute.
+fn parse_methods_args(attr: TokenStream) -> MacroResult<MethodsArgs> {
+    let mut class_name = None;
+    let mut alloc = false;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("class") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            if value.value().contains('\0') {
+                return Err(syn::Error::new_spanned(
+                    value,
+                    "class name contains null byte",
+                ));
+            }
+            class_name = Some(value.value());
+            Ok(())
+        } else if meta.path.is_ident("alloc") {
+            alloc = true;
+            Ok(())
+        } else {
+            Err(meta.error("unknown methods attribute, expected `class` or `alloc`"))
+        }
+    });
+
+    syn::parse::Parser::parse(parser, attr)?;
+
+    let class_name = class_name.ok_or_else(|| {
+        syn::Error::new(
             proc_macro2::Span::call_site(),
-            format!(
-                "#[solidus::method] currently supports arities 0-2, got {}. \
-                 For higher arities, use the method! macro directly.",
-                arity
-            ),
+            "missing required `class` attribute",
+        )
+    })?;
+
+    Ok(MethodsArgs { class_name, alloc })
+}
+
+/// Defines a Ruby class from an `impl` block.
+///
+/// Functions in the block marked `#[method]` become Ruby methods and associated
+/// constants marked `#[constant]` become Ruby constants. The macro adds a
+/// `register(ruby)` associated function that defines the class (as a subclass
+/// of `Object`) with all of them, to be called from the `#[solidus::init]`
+/// function. Unmarked items are left alone.
+///
+/// # Arguments
+///
+/// * `class = "Name"` - (Required) The Ruby class name
+/// * `alloc` - Register an allocator that wraps `Self::default()`, so
+///   `Name.new` creates wrapped objects and calls the `initialize` method
+///
+/// # Methods
+///
+/// Functions taking `&self` or `&mut self` become instance methods, which
+/// borrow the wrapped data like a `&T`/`&mut T` receiver of
+/// `#[solidus::method]`. Associated functions without a receiver become
+/// singleton methods (`Point.origin`). The Ruby name is the function name,
+/// or `#[method(name = "x=")]` to choose another, and the arity is derived from
+/// the parameters. Parameters follow the rules of `#[solidus::method]`,
+/// including `#[kwarg]`, `#[default = expr]`, `Option<T>`, `Block` and
+/// `&Context`, whose capacity can be set with `#[method(slots = N)]`.
+///
+/// # Constants
+///
+/// `#[constant]` associated constants are defined on the class under their
+/// own name, or `#[constant(name = "...")]`. Their type must implement
+/// `IntoValue`.
+///
+/// # Example
+///
+/// ```ignore
+/// use solidus::prelude::*;
+///
+/// #[solidus::wrap(class = "Point")]
+/// #[derive(Default)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// #[solidus::methods(class = "Point", alloc)]
+/// impl Point {
+///     #[constant]
+///     const DIMENSIONS: i64 = 2;
+///
+///     // Point.new(x, y)
+///     #[method]
+///     fn initialize(&mut self, x: f64, y: f64) -> Result<(), Error> {
+///         (self.x, self.y) = (x, y);
+///         Ok(())
+///     }
+///
+///     #[method]
+///     fn x(&self) -> Result<f64, Error> {
+///         Ok(self.x)
+///     }
+///
+///     #[method(name = "x=")]
+///     fn set_x(&mut self, x: f64) -> Result<(), Error> {
+///         self.x = x;
+///         Ok(())
+///     }
+///
+///     // Point.dimensions
+///     #[method]
+///     fn dimensions() -> Result<i64, Error> {
+///         Ok(Self::DIMENSIONS)
+///     }
+/// }
+///
+/// #[solidus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     Point::register(ruby)?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn methods(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match parse_methods_args(attr) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let input = parse_macro_input!(item as syn::ItemImpl);
+    match methods_impl(args, input) {
+        Ok(ts) => ts,
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Implementation of the #[methods] attribute macro.
+fn methods_impl(args: MethodsArgs, mut input: syn::ItemImpl) -> MacroResult<TokenStream> {
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[solidus::methods] must be used on an inherent impl block",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[solidus::methods] does not support generic impl blocks",
+        ));
+    }
+
+    let self_ty = input.self_ty.clone();
+    let mut registrations = Vec::new();
+
+    for item in input.items.iter_mut() {
+        match item {
+            syn::ImplItem::Fn(item_fn) => {
+                let Some(args) = take_item_attr(&mut item_fn.attrs, "method", &["name", "slots"])?
+                else {
+                    continue;
+                };
+                let name = args
+                    .name
+                    .unwrap_or_else(|| item_fn.sig.ident.unraw().to_string());
+                let slots = args.slots.as_ref();
+                let fn_name = &item_fn.sig.ident;
+                let callee = quote! { <#self_ty>::#fn_name };
+
+                let params: Vec<_> = item_fn.sig.inputs.iter().collect();
+                let mut parsed_params = Vec::new();
+                for param in &params {
+                    parsed_params.push(match param {
+                        FnArg::Receiver(receiver) => parse_receiver(receiver, &self_ty)?,
+                        FnArg::Typed(_) => parse_param(param)?,
+                    });
+                }
+
+                let is_instance = matches!(params.first(), Some(FnArg::Receiver(_)));
+                let (wrapper_fn, arity, define) = if is_instance {
+                    reject_borrowed_args(&params[1..], &parsed_params[1..])?;
+                    let arity = if parsed_params.iter().any(ParamInfo::needs_variadic) {
+                        -1
+                    } else {
+                        ruby_arg_count(&parsed_params[1..]) as i32
+                    };
+                    (
+                        generate_method_wrapper_dynamic(&callee, &parsed_params, slots)?,
+                        arity,
+                        quote! { define_method },
+                    )
+                } else {
+                    reject_borrowed_args(&params, &parsed_params)?;
+                    let arity = if parsed_params.iter().any(ParamInfo::needs_variadic) {
+                        -1
+                    } else {
+                        ruby_arg_count(&parsed_params) as i32
+                    };
+                    (
+                        generate_function_wrapper_dynamic(&callee, &parsed_params, slots)?,
+                        arity,
+                        quote! { define_singleton_method },
+                    )
+                };
+                strip_param_attrs(&mut item_fn.sig);
+
+                registrations.push(quote! {
+                    {
+                        let wrapper: solidus::method::MethodFn<{ #arity }> = {
+                            #wrapper_fn
+                        };
+                        solidus::types::Module::#define(class.clone(), #name, wrapper)?;
+                    }
+                });
+            }
+            syn::ImplItem::Const(item_const) => {
+                let Some(args) = take_item_attr(&mut item_const.attrs, "constant", &["name"])?
+                else {
+                    continue;
+                };
+                let name = args.name.unwrap_or_else(|| item_const.ident.to_string());
+                let ident = &item_const.ident;
+                registrations.push(quote! {
+                    solidus::types::Module::define_const(&class, #name, <#self_ty>::#ident)?;
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let class_name = &args.class_name;
+    let alloc = if args.alloc {
+        quote! { solidus::typed_data::define_alloc_func::<#self_ty>(&class)?; }
+    } else {
+        quote! {}
+    };
+    let register_doc = format!(
+        "Define the Ruby class `{}` with the methods and constants of this impl block.",
+        class_name
+    );
+
+    let expanded = quote! {
+        #input
+
+        impl #self_ty {
+            #[doc = #register_doc]
+            ///
+            /// Call this from the extension's init function. Returns the class.
+            pub fn register(
+                ruby: &solidus::Ruby,
+            ) -> Result<solidus::types::RClass, solidus::Error> {
+                let class: solidus::types::RClass = solidus::convert::TryConvert::try_convert(
+                    ruby.define_class(#class_name, ruby.class_object()),
+                )?;
+                #alloc
+                #(#registrations)*
+                Ok(class)
+            }
+        }
+    };
+
+    Ok(TokenStream::from(expanded))
+}
+
+/// Remove the `#[marker]` or `#[marker(...)]` attribute of an impl item.
+///
+/// `allowed` are the accepted arguments. Returns `None` if the item isn't
+/// marked.
+fn take_item_attr(
+    attrs: &mut Vec<syn::Attribute>,
+    marker: &str,
+    allowed: &[&str],
+) -> MacroResult<Option<ItemArgs>> {
+    let Some(index) = attrs.iter().position(|attr| attr.path().is_ident(marker)) else {
+        return Ok(None);
+    };
+    let attr = attrs.remove(index);
+
+    match &attr.meta {
+        Meta::Path(_) => Ok(Some(ItemArgs::default())),
+        Meta::List(list) => parse_item_args(list.tokens.clone(), allowed).map(Some),
+        Meta::NameValue(_) => Err(syn::Error::new_spanned(
+            &attr,
+            format!("expected #[{}] or #[{}(name = \"...\")]", marker, marker),
+        )),
+    }
+}
+
+/// The arguments of a `#[method]`, `#[function]` or `#[constant]` attribute.
+#[derive(Default)]
+struct ItemArgs {
+    /// `name = "..."`, the Ruby name
+    name: Option<String>,
+    /// `slots = N`, the capacity of the wrapper's `Context`
+    slots: Option<Expr>,
+}
+
+/// Parse the arguments of a `#[method]`, `#[function]` or `#[constant]` attribute.
+///
+/// `allowed` are the accepted arguments.
+fn parse_item_args(tokens: proc_macro2::TokenStream, allowed: &[&str]) -> MacroResult<ItemArgs> {
+    let mut args = ItemArgs::default();
+
+    let parser = syn::meta::parser(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        if !allowed.contains(&key.as_str()) {
+            let expected: Vec<_> = allowed.iter().map(|arg| format!("`{}`", arg)).collect();
+            return Err(meta.error(format!(
+                "unknown attribute '{}', expected {}",
+                key,
+                expected.join(" or ")
+            )));
+        }
+
+        if key == "name" {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            if value.value().contains('\0') {
+                return Err(syn::Error::new_spanned(value, "name contains null byte"));
+            }
+            args.name = Some(value.value());
+        } else {
+            args.slots = Some(meta.value()?.parse()?);
+        }
+        Ok(())
+    });
+
+    syn::parse::Parser::parse2(parser, tokens)?;
+    Ok(args)
+}
+
+/// Parse the `&self`/`&mut self` receiver of a function in a #[methods] block.
+fn parse_receiver(receiver: &syn::Receiver, self_ty: &Type) -> MacroResult<ParamInfo> {
+    if receiver.reference.is_none() || receiver.colon_token.is_some() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "methods must take &self or &mut self",
         ));
     }
+    let borrow = if receiver.mutability.is_some() {
+        Borrow::Mut
+    } else {
+        Borrow::Shared
+    };
 
-    // First param is self. It needs the same pinning rules as other Ruby VALUE types.
+    Ok(ParamInfo {
+        name: syn::Ident::from(receiver.self_token),
+        kwarg: false,
+        is_option: false,
+        is_block: false,
+        is_context: false,
+        default: None,
+        is_explicit_pinned: false,
+        needs_pinning: false,
+        inner_type: self_ty.clone(),
+        borrow: Some(borrow),
+    })
+}
+
+/// Generate the conversion of the self parameter.
+///
+/// Returns the conversion statements and the expression passed to the user function.
+fn generate_self_conversion(
+    self_param: &ParamInfo,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     // DESIGN: The self parameter needs pinning when it's a Ruby VALUE type because:
     // 1. If user stores the VALUE in a Vec or on the heap and loses the Ruby stack reference,
     //    it could be garbage collected.
     // 2. The pinning requirement ensures users must use BoxValue for heap storage.
     // 3. Users can opt-out of pinning by using non-VALUE types (like primitive conversions).
-    let self_param = &params[0];
     let self_type = &self_param.inner_type;
 
-    // Generate extern "C" parameter declarations
-    let mut extern_params = vec![quote! { rb_self: solidus::rb_sys::VALUE }];
-    for i in 0..arity {
-        let arg_name = syn::Ident::new(&format!("arg{}", i), proc_macro2::Span::call_site());
-        extern_params.push(quote! { #arg_name: solidus::rb_sys::VALUE });
-    }
-
-    // Generate conversion code for each argument
-    let mut conversion_stmts = Vec::new();
-    let mut call_args = Vec::new();
-
-    // Self conversion - check if it needs pinning
-    if self_param.needs_pinning {
+    if let Some(borrow) = self_param.borrow {
+        // Wrapped data - borrow it through the runtime-checked guard, which is
+        // released when the wrapper's closure returns
+        let (stmts, call_arg) = match borrow {
+            Borrow::Shared => (
+                quote! {
+                    let self_ref = solidus::typed_data::get::<#self_type>(&self_value)?;
+                },
+                quote! { &*self_ref },
+            ),
+            Borrow::Mut => (
+                quote! {
+                    let mut self_ref = solidus::typed_data::get_mut::<#self_type>(&self_value)?;
+                },
+                quote! { &mut *self_ref },
+            ),
+        };
+        let stmts = quote! {
+            let self_value = unsafe { solidus::Value::from_raw(rb_self) };
+            #stmts
+        };
+        (stmts, call_arg)
+    } else if self_param.needs_pinning {
         // Ruby VALUE type - needs pinning for GC safety
-        conversion_stmts.push(quote! {
+        let stmts = quote! {
             let self_value = unsafe { solidus::Value::from_raw(rb_self) };
             let self_converted: #self_type = solidus::convert::TryConvert::try_convert(self_value)?;
             solidus::pin_on_stack!(self_pinned = solidus::value::NewValue::new(self_converted));
-        });
+        };
         // Determine how to pass the argument to the user function
-        if self_param.is_explicit_pinned {
+        let call_arg = if self_param.is_explicit_pinned {
             // User wants Pin<&StackPinned<T>>, pass the pinned reference directly
-            call_args.push(quote! { self_pinned });
+            quote! { self_pinned }
         } else {
             // User wants T directly - pass .get().clone()
-            call_args.push(quote! { self_pinned.get().clone() });
-        }
+            quote! { self_pinned.get().clone() }
+        };
+        (stmts, call_arg)
     } else {
         // Rust primitive - direct conversion, no pinning
-        conversion_stmts.push(quote! {
+        let stmts = quote! {
             let self_value = unsafe { solidus::Value::from_raw(rb_self) };
             let self_converted: #self_type = solidus::convert::TryConvert::try_convert(self_value)?;
-        });
-        call_args.push(quote! { self_converted });
+        };
+        (stmts, quote! { self_converted })
     }
+}
 
-    // Argument conversions - conditionally pin based on type
-    for (i, param) in params.iter().skip(1).enumerate() {
-        let arg_value = syn::Ident::new(&format!("arg{}", i), proc_macro2::Span::call_site());
-        let inner_type = &param.inner_type;
-
-        if param.needs_pinning {
-            // Ruby VALUE type - needs pinning for GC safety
-            let arg_converted = syn::Ident::new(
-                &format!("arg{}_converted", i),
-                proc_macro2::Span::call_site(),
-            );
-            let arg_pinned =
-                syn::Ident::new(&format!("arg{}_pinned", i), proc_macro2::Span::call_site());
+/// Generate the conversion of the positional argument `arg{index}`.
+///
+/// Expects a raw `VALUE` named `arg{index}` to be in scope. Returns the conversion
+/// statements and the expression passed to the user function.
+fn generate_arg_conversion(
+    index: usize,
+    param: &ParamInfo,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let arg_value = syn::Ident::new(&format!("arg{}", index), proc_macro2::Span::call_site());
+    let inner_type = &param.inner_type;
 
-            conversion_stmts.push(quote! {
-                let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
-                let #arg_converted: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
-                solidus::pin_on_stack!(#arg_pinned = solidus::value::NewValue::new(#arg_converted));
-            });
+    if param.needs_pinning {
+        // Ruby VALUE type - needs pinning for GC safety
+        let arg_converted = syn::Ident::new(
+            &format!("arg{}_converted", index),
+            proc_macro2::Span::call_site(),
+        );
+        let arg_pinned = syn::Ident::new(
+            &format!("arg{}_pinned", index),
+            proc_macro2::Span::call_site(),
+        );
 
-            // Determine how to pass the argument to the user function
-            if param.is_explicit_pinned {
-                // User wants Pin<&StackPinned<T>>, pass the pinned reference directly
-                call_args.push(quote! { #arg_pinned });
-            } else {
-                // User wants T directly - pass .get().clone()
-                call_args.push(quote! { #arg_pinned.get().clone() });
-            }
+        let stmts = quote! {
+            let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
+            let #arg_converted: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
+            solidus::pin_on_stack!(#arg_pinned = solidus::value::NewValue::new(#arg_converted));
+        };
+
+        // Determine how to pass the argument to the user function
+        let call_arg = if param.is_explicit_pinned {
+            // User wants Pin<&StackPinned<T>>, pass the pinned reference directly
+            quote! { #arg_pinned }
         } else {
-            // Rust primitive - direct conversion, no pinning needed
-            let arg_direct =
-                syn::Ident::new(&format!("arg{}_direct", i), proc_macro2::Span::call_site());
+            // User wants T directly - pass .get().clone()
+            quote! { #arg_pinned.get().clone() }
+        };
+        (stmts, call_arg)
+    } else {
+        // Rust primitive - direct conversion, no pinning needed
+        let arg_direct = syn::Ident::new(
+            &format!("arg{}_direct", index),
+            proc_macro2::Span::call_site(),
+        );
 
-            conversion_stmts.push(quote! {
-                let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
-                let #arg_direct: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
-            });
-            call_args.push(quote! { #arg_direct });
-        }
+        let stmts = quote! {
+            let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
+            let #arg_direct: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
+        };
+        (stmts, quote! { #arg_direct })
     }
+}
 
-    Ok(quote! {
+/// Wrap the body of a generated wrapper in `catch_unwind` and the raise handling.
+///
+/// `extern_params` are the names and types of the C parameters. The result is a
+/// `MethodFn`, whose arity is inferred from where it's used and has to match
+/// the number of parameters.
+fn generate_wrapper_fn(
+    extern_params: &[(proc_macro2::TokenStream, proc_macro2::TokenStream)],
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let names = extern_params.iter().map(|(name, _)| name);
+    let types: Vec<_> = extern_params.iter().map(|(_, ty)| ty).collect();
+    quote! {
         #[allow(unused_unsafe)]
         unsafe extern "C" fn __wrapper(
-            #(#extern_params),*
+            #(#names: #types),*
         ) -> solidus::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                #(#conversion_stmts)*
-
-                let result = #fn_name(#(#call_args),*);
+                #body
 
                 use solidus::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => solidus::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match solidus::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(__wrapper as usize) }
-    })
+        solidus::method::MethodFn::new(
+            __wrapper as unsafe extern "C" fn(#(#types),*) -> solidus::rb_sys::VALUE,
+        )
+    }
+}
+
+/// Generate the `Context` passed to `&Context` parameters.
+///
+/// Returns the statement creating it, run before the arguments are converted,
+/// and the one recording its slot usage after the call. The capacity is
+/// `slots` if given, or else inferred from the parameter's type.
+fn generate_context(
+    callee: &proc_macro2::TokenStream,
+    params: &[ParamInfo],
+    slots: Option<&Expr>,
+) -> MacroResult<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    if !params.iter().any(|param| param.is_context) {
+        if let Some(slots) = slots {
+            return Err(syn::Error::new_spanned(
+                slots,
+                "`slots` sets the capacity of the Context, but there is no &Context parameter",
+            ));
+        }
+        return Ok((quote! {}, quote! {}));
+    }
+
+    let label = callee.to_string().replace(' ', "");
+    Ok((
+        quote! { let ctx = solidus::__wrapper_context!(#slots); },
+        quote! { solidus::__record_slot_usage!(#label, &ctx); },
+    ))
+}
+
+/// Generate the extern "C" wrapper for a method dynamically based on parsed parameters.
+///
+/// This function generates a wrapper that handles both explicit `Pin<&StackPinned<T>>`
+/// parameters and implicit pinning for simple types. `callee` is the path of the
+/// user function, e.g. `concat` or `<Point>::x`.
+fn generate_method_wrapper_dynamic(
+    callee: &proc_macro2::TokenStream,
+    params: &[ParamInfo],
+    slots: Option<&Expr>,
+) -> MacroResult<proc_macro2::TokenStream> {
+    // Self is the first param after any &Context. It needs the same pinning
+    // rules as other Ruby VALUE types.
+    let self_index = params.iter().take_while(|param| param.is_context).count();
+    let self_param = &params[self_index];
+
+    if params.iter().any(ParamInfo::needs_variadic) {
+        return generate_variadic_wrapper(callee, Some(self_index), params, slots);
+    }
+
+    let arity = ruby_arg_count(&params[self_index + 1..]);
+    if arity > 2 {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "#[solidus::method] currently supports arities 0-2, got {}. \
+                 For higher arities, use the method! macro directly.",
+                arity
+            ),
+        ));
+    }
+
+    // Generate extern "C" parameter declarations
+    let mut extern_params = vec![(quote! { rb_self }, quote! { solidus::rb_sys::VALUE })];
+    for i in 0..arity {
+        let arg_name = syn::Ident::new(&format!("arg{}", i), proc_macro2::Span::call_site());
+        extern_params.push((quote! { #arg_name }, quote! { solidus::rb_sys::VALUE }));
+    }
+
+    let (ctx_stmts, record_stmts) = generate_context(callee, params, slots)?;
+
+    // Self conversion - pinned if it's a Ruby VALUE type
+    let (self_stmts, self_arg) = generate_self_conversion(self_param);
+    let mut conversion_stmts = vec![self_stmts];
+    let mut call_args = Vec::new();
+
+    // Argument conversions - conditionally pin based on type
+    let mut positional = 0;
+    for (index, param) in params.iter().enumerate() {
+        if index == self_index {
+            call_args.push(self_arg.clone());
+            continue;
+        }
+        if let Some(call_arg) = param.implicit_arg() {
+            call_args.push(call_arg);
+            continue;
+        }
+        let (stmts, call_arg) = generate_arg_conversion(positional, param);
+        conversion_stmts.push(stmts);
+        call_args.push(call_arg);
+        positional += 1;
+    }
+
+    Ok(generate_wrapper_fn(
+        &extern_params,
+        quote! {
+            #ctx_stmts
+            #(#conversion_stmts)*
+
+            let result = #callee(#(#call_args),*);
+            #record_stmts
+        },
+    ))
 }
 
 /// Generate the extern "C" wrapper for a function dynamically based on parsed parameters.
@@ -1108,10 +1951,15 @@ fn generate_method_wrapper_dynamic(
 /// This function generates a wrapper that handles both explicit `Pin<&StackPinned<T>>`
 /// parameters and implicit pinning for simple types.
 fn generate_function_wrapper_dynamic(
-    fn_name: &syn::Ident,
+    callee: &proc_macro2::TokenStream,
     params: &[ParamInfo],
+    slots: Option<&Expr>,
 ) -> MacroResult<proc_macro2::TokenStream> {
-    let arity = params.len();
+    if params.iter().any(ParamInfo::needs_variadic) {
+        return generate_variadic_wrapper(callee, None, params, slots);
+    }
+
+    let arity = ruby_arg_count(params);
     if arity > 2 {
         return Err(syn::Error::new(
             proc_macro2::Span::call_site(),
@@ -1124,88 +1972,214 @@ fn generate_function_wrapper_dynamic(
     }
 
     // Generate extern "C" parameter declarations (always has _rb_self for Ruby)
-    let mut extern_params = vec![quote! { _rb_self: solidus::rb_sys::VALUE }];
+    let mut extern_params = vec![(quote! { _rb_self }, quote! { solidus::rb_sys::VALUE })];
     for i in 0..arity {
         let arg_name = syn::Ident::new(&format!("arg{}", i), proc_macro2::Span::call_site());
-        extern_params.push(quote! { #arg_name: solidus::rb_sys::VALUE });
+        extern_params.push((quote! { #arg_name }, quote! { solidus::rb_sys::VALUE }));
     }
 
-    // Generate conversion code for each argument
+    let (ctx_stmts, record_stmts) = generate_context(callee, params, slots)?;
+
+    // Argument conversions - conditionally pin based on type
     let mut conversion_stmts = Vec::new();
     let mut call_args = Vec::new();
+    let mut positional = 0;
+    for param in params {
+        if let Some(call_arg) = param.implicit_arg() {
+            call_args.push(call_arg);
+            continue;
+        }
+        let (stmts, call_arg) = generate_arg_conversion(positional, param);
+        conversion_stmts.push(stmts);
+        call_args.push(call_arg);
+        positional += 1;
+    }
 
-    // Argument conversions - conditionally pin based on type
-    for (i, param) in params.iter().enumerate() {
-        let arg_value = syn::Ident::new(&format!("arg{}", i), proc_macro2::Span::call_site());
-        let inner_type = &param.inner_type;
-
-        if param.needs_pinning {
-            // Ruby VALUE type - needs pinning for GC safety
-            let arg_converted = syn::Ident::new(
-                &format!("arg{}_converted", i),
-                proc_macro2::Span::call_site(),
-            );
-            let arg_pinned =
-                syn::Ident::new(&format!("arg{}_pinned", i), proc_macro2::Span::call_site());
+    Ok(generate_wrapper_fn(
+        &extern_params,
+        quote! {
+            #ctx_stmts
+            #(#conversion_stmts)*
 
-            conversion_stmts.push(quote! {
-                let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
-                let #arg_converted: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
-                solidus::pin_on_stack!(#arg_pinned = solidus::value::NewValue::new(#arg_converted));
-            });
+            let result = #callee(#(#call_args),*);
+            #record_stmts
+        },
+    ))
+}
 
-            // Determine how to pass the argument to the user function
-            if param.is_explicit_pinned {
-                // User wants Pin<&StackPinned<T>>, pass the pinned reference directly
-                call_args.push(quote! { #arg_pinned });
+/// Generate a variadic (arity -1) wrapper for a method or function.
+///
+/// Used when a parameter is optional (`Option<T>` or `#[default = expr]`) or a
+/// `#[kwarg]`, since Ruby's C API can't express either with a fixed arity. The
+/// wrapper receives `argc`/`argv`, splits off the keyword hash, checks the
+/// positional count and extracts the keywords with `get_kwargs`. `self_index`
+/// is the position of a method's self parameter in `params`.
+fn generate_variadic_wrapper(
+    callee: &proc_macro2::TokenStream,
+    self_index: Option<usize>,
+    params: &[ParamInfo],
+    slots: Option<&Expr>,
+) -> MacroResult<proc_macro2::TokenStream> {
+    let self_ident = if self_index.is_some() {
+        quote! { rb_self }
+    } else {
+        quote! { _rb_self }
+    };
+    let extern_params = vec![
+        (quote! { argc }, quote! { ::std::os::raw::c_int }),
+        (quote! { argv }, quote! { *const solidus::rb_sys::VALUE }),
+        (self_ident, quote! { solidus::rb_sys::VALUE }),
+    ];
+
+    let (ctx_stmts, record_stmts) = generate_context(callee, params, slots)?;
+
+    let mut self_stmts = Vec::new();
+    let mut self_arg = None;
+    if let Some(self_index) = self_index {
+        let (stmts, call_arg) = generate_self_conversion(&params[self_index]);
+        self_stmts.push(stmts);
+        self_arg = Some(call_arg);
+    }
+    let mut call_args = Vec::new();
+
+    let mut required_idents = Vec::new();
+    let mut required_types = Vec::new();
+    let mut required_names = Vec::new();
+    let mut optional_idents = Vec::new();
+    let mut optional_types = Vec::new();
+    let mut optional_names = Vec::new();
+    let mut default_stmts = Vec::new();
+    let mut conversion_stmts = Vec::new();
+    let mut required_count = 0usize;
+    let mut positional = 0usize;
+
+    for (index, param) in params.iter().enumerate() {
+        let ty = &param.inner_type;
+
+        if Some(index) == self_index {
+            call_args.extend(self_arg.take());
+            continue;
+        }
+        if let Some(call_arg) = param.implicit_arg() {
+            call_args.push(call_arg);
+            continue;
+        }
+
+        if param.kwarg {
+            let name = param.name.unraw().to_string();
+            let ident = syn::Ident::new(&format!("kwarg_{}", name), proc_macro2::Span::call_site());
+            if param.is_optional() {
+                // Defaults apply to omitted keywords; get_kwargs yields None for them
+                match (&param.default, param.is_option) {
+                    (Some(default), true) => {
+                        optional_types.push(quote! { #ty });
+                        default_stmts.push(quote! {
+                            let #ident: #ty = match #ident {
+                                None => #default,
+                                value => value,
+                            };
+                        });
+                    }
+                    (Some(default), false) => {
+                        optional_types.push(quote! { Option<#ty> });
+                        default_stmts.push(quote! {
+                            let #ident: #ty = match #ident {
+                                Some(value) => value,
+                                None => #default,
+                            };
+                        });
+                    }
+                    (None, _) => optional_types.push(quote! { #ty }),
+                }
+                optional_idents.push(ident.clone());
+                optional_names.push(name);
             } else {
-                // User wants T directly - pass .get().clone()
-                call_args.push(quote! { #arg_pinned.get().clone() });
+                required_idents.push(ident.clone());
+                required_types.push(ty);
+                required_names.push(name);
             }
-        } else {
-            // Rust primitive - direct conversion, no pinning needed
-            let arg_direct =
-                syn::Ident::new(&format!("arg{}_direct", i), proc_macro2::Span::call_site());
+            call_args.push(quote! { #ident });
+            continue;
+        }
 
+        if param.is_optional() {
+            // The value stays reachable through Ruby's argument list, so it
+            // is converted without pinning
+            let arg_direct = syn::Ident::new(
+                &format!("arg{}_direct", positional),
+                proc_macro2::Span::call_site(),
+            );
+            let missing = match &param.default {
+                Some(default) => quote! { #default },
+                None => quote! { None },
+            };
             conversion_stmts.push(quote! {
-                let #arg_value = unsafe { solidus::Value::from_raw(#arg_value) };
-                let #arg_direct: #inner_type = solidus::convert::TryConvert::try_convert(#arg_value)?;
+                let #arg_direct: #ty = match args.get(#positional) {
+                    Some(value) => solidus::convert::TryConvert::try_convert(value.clone())?,
+                    None => #missing,
+                };
             });
             call_args.push(quote! { #arg_direct });
+        } else {
+            if positional > required_count {
+                return Err(syn::Error::new_spanned(
+                    &param.name,
+                    "required parameters must come before optional ones",
+                ));
+            }
+            let arg_value = syn::Ident::new(
+                &format!("arg{}", positional),
+                proc_macro2::Span::call_site(),
+            );
+            let (stmts, call_arg) = generate_arg_conversion(positional, param);
+            conversion_stmts.push(quote! {
+                let #arg_value = args[#positional].as_raw();
+                #stmts
+            });
+            call_args.push(call_arg);
+            required_count += 1;
         }
+        positional += 1;
     }
 
-    // Handle arity 0 case (no arguments to convert)
-    let body = if params.is_empty() {
+    // Without #[kwarg] parameters, keywords are an ordinary trailing hash
+    // argument, as in Ruby
+    let kwargs_stmts = if params.iter().any(|param| param.kwarg) {
         quote! {
-            let result = #fn_name();
+            let (args, kwargs) = solidus::method::split_kwargs(args);
+            solidus::method::check_arity(args.len(), #required_count, Some(#positional))?;
+
+            let solidus::method::KwArgs {
+                required: (#(#required_idents,)*),
+                optional: (#(#optional_idents,)*),
+            } = solidus::method::get_kwargs::<(#(#required_types,)*), (#(#optional_types,)*)>(
+                kwargs.as_ref(),
+                &[#(#required_names),*],
+                &[#(#optional_names),*],
+            )?;
+            #(#default_stmts)*
         }
     } else {
         quote! {
-            #(#conversion_stmts)*
-            let result = #fn_name(#(#call_args),*);
+            solidus::method::check_arity(args.len(), #required_count, Some(#positional))?;
         }
     };
 
-    Ok(quote! {
-        #[allow(unused_unsafe)]
-        unsafe extern "C" fn __wrapper(
-            #(#extern_params),*
-        ) -> solidus::rb_sys::VALUE {
-            let result = ::std::panic::catch_unwind(|| {
-                #body
+    Ok(generate_wrapper_fn(
+        &extern_params,
+        quote! {
+            #ctx_stmts
+            #(#self_stmts)*
 
-                use solidus::method::IntoReturnValue;
-                result.into_return_value()
-            });
+            // SAFETY: Ruby passes argc VALUEs in argv, which live on its
+            // VM stack for the duration of the call
+            let args = unsafe { solidus::method::args_from_raw(argc, argv) };
+            #kwargs_stmts
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => solidus::Error::from_panic(panic).raise(),
-            }
-        }
+            #(#conversion_stmts)*
 
-        unsafe { ::std::mem::transmute(__wrapper as usize) }
-    })
+            let result = #callee(#(#call_args),*);
+            #record_stmts
+        },
+    ))
 }
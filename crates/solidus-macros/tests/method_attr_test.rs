@@ -6,6 +6,7 @@
 //! These tests require Ruby to be linked, so they only run with the `link-ruby` feature.
 #![cfg(feature = "link-ruby")]
 
+use solidus::method::MethodFn;
 use solidus::prelude::*;
 use solidus::rb_sys;
 use std::pin::Pin;
@@ -137,18 +138,18 @@ fn test_function_arity_2_generates_correct_arity() {
 
 #[test]
 fn test_method_wrapper_returns_correct_type() {
-    // The wrapper() function should return a function pointer
-    let wrapper: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_arity_0::wrapper();
+    // The wrapper() function should return a MethodFn carrying the arity
+    let wrapper: MethodFn<0> = __solidus_method_method_arity_0::wrapper();
     // We can't call it without Ruby, but we can verify the type
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = wrapper;
+    let _: unsafe extern "C" fn() -> rb_sys::VALUE = wrapper.as_raw();
+    assert_eq!(wrapper.arity(), __solidus_method_method_arity_0::ARITY);
 }
 
 #[test]
 fn test_function_wrapper_returns_correct_type() {
-    let wrapper: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_function_function_arity_0::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = wrapper;
+    let wrapper: MethodFn<0> = __solidus_function_function_arity_0::wrapper();
+    let _: unsafe extern "C" fn() -> rb_sys::VALUE = wrapper.as_raw();
+    assert_eq!(wrapper.arity(), __solidus_function_function_arity_0::ARITY);
 }
 
 // ============================================================================
@@ -157,16 +158,16 @@ fn test_function_wrapper_returns_correct_type() {
 
 #[test]
 fn test_all_method_wrappers_compile() {
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_method_method_arity_0::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_method_method_arity_1::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_method_method_arity_2::wrapper();
+    let _: MethodFn<0> = __solidus_method_method_arity_0::wrapper();
+    let _: MethodFn<1> = __solidus_method_method_arity_1::wrapper();
+    let _: MethodFn<2> = __solidus_method_method_arity_2::wrapper();
 }
 
 #[test]
 fn test_all_function_wrappers_compile() {
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_function_function_arity_0::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_function_function_arity_1::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE = __solidus_function_function_arity_2::wrapper();
+    let _: MethodFn<0> = __solidus_function_function_arity_0::wrapper();
+    let _: MethodFn<1> = __solidus_function_function_arity_1::wrapper();
+    let _: MethodFn<2> = __solidus_function_function_arity_2::wrapper();
 }
 
 // ============================================================================
@@ -194,12 +195,9 @@ fn method_returns_bool(rb_self: TestValue) -> Result<bool, Error> {
 
 #[test]
 fn test_method_different_return_types_compile() {
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_returns_unit::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_returns_value::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_returns_bool::wrapper();
+    let _: MethodFn<0> = __solidus_method_method_returns_unit::wrapper();
+    let _: MethodFn<0> = __solidus_method_method_returns_value::wrapper();
+    let _: MethodFn<0> = __solidus_method_method_returns_bool::wrapper();
 }
 
 /// Function that returns unit.
@@ -216,10 +214,8 @@ fn function_returns_bool() -> Result<bool, Error> {
 
 #[test]
 fn test_function_different_return_types_compile() {
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_function_function_returns_unit::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_function_function_returns_bool::wrapper();
+    let _: MethodFn<0> = __solidus_function_function_returns_unit::wrapper();
+    let _: MethodFn<0> = __solidus_function_function_returns_bool::wrapper();
 }
 
 // ============================================================================
@@ -245,7 +241,7 @@ fn test_usage_pattern_documentation() {
     // #[doc(hidden)]
     // pub mod __solidus_method_my_method {
     //     pub const ARITY: i32 = 1;
-    //     pub fn wrapper() -> unsafe extern "C" fn() -> VALUE {
+    //     pub fn wrapper() -> MethodFn<ARITY> {
     //         // extern "C" wrapper that handles:
     //         // - panic catching
     //         // - self conversion
@@ -255,7 +251,7 @@ fn test_usage_pattern_documentation() {
     // }
     //
     // Usage with Ruby:
-    // class.define_method("my_method", __solidus_method_my_method::wrapper(), __solidus_method_my_method::ARITY)?;
+    // class.define_method("my_method", __solidus_method_my_method::wrapper(),)?;
 
     // Verify the pattern works:
     assert_eq!(__solidus_method_method_arity_1::ARITY, 1);
@@ -277,13 +273,11 @@ fn test_comparison_with_declarative_macros() {
     ) -> Result<i64, Error> {
         Ok(0)
     }
-    let _declarative_wrapper: unsafe extern "C" fn() -> rb_sys::VALUE =
-        solidus::method!(existing_method, 1);
+    let _declarative_wrapper: MethodFn<1> = solidus::method!(existing_method, 1);
 
     // Using attribute macro (new approach):
     // The method_arity_1 function was defined above with #[solidus_macros::method]
-    let _attribute_wrapper: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_arity_1::wrapper();
+    let _attribute_wrapper: MethodFn<1> = __solidus_method_method_arity_1::wrapper();
 
     // Both produce the same type of wrapper function pointer
     // The key differences:
@@ -376,16 +370,11 @@ fn test_method_mixed_pinning_arity() {
 #[test]
 fn test_implicit_pinning_wrappers_compile() {
     // Verify all implicit pinning wrappers have the correct type
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_implicit_pinning::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_implicit_pinning_2args::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_function_function_implicit_pinning::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_function_function_implicit_pinning_2args::wrapper();
-    let _: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_mixed_pinning::wrapper();
+    let _: MethodFn<1> = __solidus_method_method_implicit_pinning::wrapper();
+    let _: MethodFn<2> = __solidus_method_method_implicit_pinning_2args::wrapper();
+    let _: MethodFn<1> = __solidus_function_function_implicit_pinning::wrapper();
+    let _: MethodFn<2> = __solidus_function_function_implicit_pinning_2args::wrapper();
+    let _: MethodFn<2> = __solidus_method_method_mixed_pinning::wrapper();
 }
 
 // ============================================================================
@@ -458,12 +447,361 @@ fn test_implicit_pinning_documentation() {
 #[test]
 fn test_backward_compatibility() {
     // Both explicit (old style) and implicit (new style) work
-    let _explicit: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_arity_1::wrapper();
-    let _implicit: unsafe extern "C" fn() -> rb_sys::VALUE =
-        __solidus_method_method_implicit_pinning::wrapper();
+    let _explicit: MethodFn<1> = __solidus_method_method_arity_1::wrapper();
+    let _implicit: MethodFn<1> = __solidus_method_method_implicit_pinning::wrapper();
 
     // They have the same arity
     assert_eq!(__solidus_method_method_arity_1::ARITY, 1);
     assert_eq!(__solidus_method_method_implicit_pinning::ARITY, 1);
 }
+
+// ============================================================================
+// Keyword Argument Tests
+// ============================================================================
+
+/// Method with one positional argument and required/optional keywords.
+#[solidus_macros::method]
+fn method_with_kwargs(
+    rb_self: TestValue,
+    count: i64,
+    #[kwarg] host: String,
+    #[kwarg] port: Option<i64>,
+) -> Result<i64, Error> {
+    Ok(rb_self.0 + count + host.len() as i64 + port.unwrap_or(80))
+}
+
+/// Function taking only keywords.
+#[solidus_macros::function]
+fn function_with_kwargs(#[kwarg] verbose: Option<bool>) -> Result<bool, Error> {
+    Ok(verbose.unwrap_or(false))
+}
+
+#[test]
+fn test_kwargs_generate_variadic_arity() {
+    assert_eq!(__solidus_method_method_with_kwargs::ARITY, -1);
+    assert_eq!(__solidus_function_function_with_kwargs::ARITY, -1);
+}
+
+#[test]
+fn test_kwargs_wrappers_compile() {
+    let _: MethodFn<-1> = __solidus_method_method_with_kwargs::wrapper();
+    let _: MethodFn<-1> = __solidus_function_function_with_kwargs::wrapper();
+}
+
+#[test]
+fn test_kwargs_direct_call() {
+    // The #[kwarg] attributes are stripped, leaving a plain Rust function
+    let result = method_with_kwargs(TestValue(1), 2, "abc".to_string(), None);
+    assert_eq!(result.unwrap(), 86);
+    assert!(function_with_kwargs(Some(true)).unwrap());
+}
+
+// ============================================================================
+// Optional and Default Parameter Tests
+// ============================================================================
+
+/// Method with one required and two optional arguments.
+#[solidus_macros::method]
+fn method_with_optional(
+    rb_self: TestValue,
+    base: i64,
+    extra: Option<i64>,
+    #[default = 10] scale: i64,
+) -> Result<i64, Error> {
+    Ok(rb_self.0 + (base + extra.unwrap_or(0)) * scale)
+}
+
+/// Function whose only argument is optional.
+#[solidus_macros::function]
+fn function_with_optional(name: Option<String>) -> Result<bool, Error> {
+    Ok(name.is_some())
+}
+
+/// Function mixing a defaulted argument with a defaulted keyword.
+#[solidus_macros::function]
+fn function_with_defaults(
+    #[default = 1] count: i64,
+    #[kwarg]
+    #[default = 2]
+    step: i64,
+) -> Result<i64, Error> {
+    Ok(count * step)
+}
+
+/// Function with an optional argument and no keywords, so Ruby passes
+/// `key: value` arguments to it as a positional hash.
+#[solidus_macros::function]
+fn function_with_optional_hash(options: Option<RHash>) -> Result<i64, Error> {
+    Ok(options.map_or(-1, |options| options.len() as i64))
+}
+
+#[test]
+fn test_optional_generate_variadic_arity() {
+    assert_eq!(__solidus_method_method_with_optional::ARITY, -1);
+    assert_eq!(__solidus_function_function_with_optional::ARITY, -1);
+    assert_eq!(__solidus_function_function_with_defaults::ARITY, -1);
+}
+
+#[test]
+fn test_optional_wrappers_compile() {
+    let _: MethodFn<-1> = __solidus_method_method_with_optional::wrapper();
+    let _: MethodFn<-1> = __solidus_function_function_with_optional::wrapper();
+    let _: MethodFn<-1> = __solidus_function_function_with_defaults::wrapper();
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_optional_receives_keywords_as_hash() {
+    let ruby = unsafe { Ruby::get() };
+    ruby.define_global_function(
+        "function_with_optional_hash",
+        __solidus_function_function_with_optional_hash::wrapper(),
+    )
+    .unwrap();
+
+    let call = |code: &str| {
+        let result = Value::nil().funcall_boxed("eval", (code,)).unwrap();
+        i64::try_convert(result.as_value()).unwrap()
+    };
+    assert_eq!(call("function_with_optional_hash(a: 1, b: 2)"), 2);
+    assert_eq!(call("function_with_optional_hash({a: 1})"), 1);
+    assert_eq!(call("function_with_optional_hash"), -1);
+}
+
+#[test]
+fn test_optional_direct_call() {
+    // #[default] attributes are stripped; defaults only apply to Ruby callers
+    assert_eq!(
+        method_with_optional(TestValue(1), 2, Some(3), 4).unwrap(),
+        21
+    );
+    assert!(!function_with_optional(None).unwrap());
+    assert_eq!(function_with_defaults(3, 5).unwrap(), 15);
+}
+
+// ============================================================================
+// Block Parameter Tests
+// ============================================================================
+
+/// Method that receives the block alongside an argument.
+#[solidus_macros::method]
+fn method_with_block(rb_self: TestValue, count: i64, block: Block) -> Result<i64, Error> {
+    let _ = block;
+    Ok(rb_self.0 + count)
+}
+
+/// Function that only receives the block.
+#[solidus_macros::function]
+fn function_with_block(block: Block<'_>) -> Result<bool, Error> {
+    let _ = block;
+    Ok(true)
+}
+
+/// A block parameter next to an optional argument.
+#[solidus_macros::function]
+fn function_with_block_and_optional(block: Block, limit: Option<i64>) -> Result<i64, Error> {
+    let _ = block;
+    Ok(limit.unwrap_or(0))
+}
+
+#[test]
+fn test_block_not_counted_in_arity() {
+    assert_eq!(__solidus_method_method_with_block::ARITY, 1);
+    assert_eq!(__solidus_function_function_with_block::ARITY, 0);
+    assert_eq!(
+        __solidus_function_function_with_block_and_optional::ARITY,
+        -1
+    );
+}
+
+#[test]
+fn test_block_wrappers_compile() {
+    let _: MethodFn<1> = __solidus_method_method_with_block::wrapper();
+    let _: MethodFn<0> = __solidus_function_function_with_block::wrapper();
+    let _: MethodFn<-1> = __solidus_function_function_with_block_and_optional::wrapper();
+}
+
+#[test]
+fn test_block_direct_call() {
+    assert_eq!(method_with_block(TestValue(1), 2, Block::new()).unwrap(), 3);
+    assert!(function_with_block(Block::new()).unwrap());
+}
+
+// ============================================================================
+// Typed Self Tests
+// ============================================================================
+
+/// A wrapped struct whose methods borrow it directly.
+#[solidus_macros::wrap(class = "TestCounter")]
+struct TestCounter {
+    count: i64,
+}
+
+/// Method reading the wrapped data through `&T`.
+#[solidus_macros::method]
+fn counter_value(counter: &TestCounter) -> Result<i64, Error> {
+    Ok(counter.count)
+}
+
+/// Method mutating the wrapped data through `&mut T`.
+#[solidus_macros::method]
+fn counter_add(counter: &mut TestCounter, by: i64) -> Result<i64, Error> {
+    counter.count += by;
+    Ok(counter.count)
+}
+
+/// Typed self with an optional argument, which makes the method variadic.
+#[solidus_macros::method]
+fn counter_reset(counter: &mut TestCounter, to: Option<i64>) -> Result<(), Error> {
+    counter.count = to.unwrap_or(0);
+    Ok(())
+}
+
+#[test]
+fn test_typed_self_arity() {
+    assert_eq!(__solidus_method_counter_value::ARITY, 0);
+    assert_eq!(__solidus_method_counter_add::ARITY, 1);
+    assert_eq!(__solidus_method_counter_reset::ARITY, -1);
+}
+
+#[test]
+fn test_typed_self_wrappers_compile() {
+    let _: MethodFn<0> = __solidus_method_counter_value::wrapper();
+    let _: MethodFn<1> = __solidus_method_counter_add::wrapper();
+    let _: MethodFn<-1> = __solidus_method_counter_reset::wrapper();
+}
+
+#[test]
+fn test_typed_self_direct_call() {
+    let mut counter = TestCounter { count: 1 };
+    assert_eq!(counter_add(&mut counter, 2).unwrap(), 3);
+    assert_eq!(counter_value(&counter).unwrap(), 3);
+    counter_reset(&mut counter, None).unwrap();
+    assert_eq!(counter.count, 0);
+}
+
+// ============================================================================
+// Impl Block Tests
+// ============================================================================
+
+/// A wrapped struct defined through #[methods].
+#[solidus_macros::wrap(class = "TestShape")]
+#[derive(Default)]
+struct TestShape {
+    sides: i64,
+}
+
+#[solidus_macros::methods(class = "TestShape", alloc)]
+impl TestShape {
+    #[constant]
+    const MAX_SIDES: i64 = 12;
+
+    #[constant(name = "DEFAULT_SIDES")]
+    const DEFAULT: i64 = 3;
+
+    #[method]
+    fn initialize(&mut self, sides: Option<i64>) -> Result<(), Error> {
+        self.sides = sides.unwrap_or(Self::DEFAULT);
+        Ok(())
+    }
+
+    #[method]
+    fn sides(&self) -> Result<i64, Error> {
+        Ok(self.sides)
+    }
+
+    #[method(name = "sides=")]
+    fn set_sides(&mut self, sides: i64) -> Result<(), Error> {
+        self.sides = sides.min(Self::MAX_SIDES);
+        Ok(())
+    }
+
+    #[method]
+    fn each_side(&self, block: Block) -> Result<i64, Error> {
+        let _ = block;
+        Ok(self.sides)
+    }
+
+    #[method]
+    fn triangle_sides() -> Result<i64, Error> {
+        Ok(3)
+    }
+
+    #[method(slots = 4)]
+    fn spare_slots<const N: usize>(&self, ctx: &Context<'_, N>) -> Result<usize, Error> {
+        Ok(ctx.available())
+    }
+
+    /// Not marked, so not registered.
+    fn double(&self) -> i64 {
+        self.sides * 2
+    }
+}
+
+#[test]
+fn test_methods_register_compiles() {
+    let _: fn(&Ruby) -> Result<RClass, Error> = TestShape::register;
+}
+
+#[test]
+fn test_methods_direct_call() {
+    // The impl block is kept, with the helper attributes removed
+    let mut shape = TestShape::default();
+    shape.initialize(None).unwrap();
+    assert_eq!(shape.sides().unwrap(), 3);
+    shape.set_sides(20).unwrap();
+    assert_eq!(shape.each_side(Block::new()).unwrap(), 12);
+    assert_eq!(TestShape::triangle_sides().unwrap(), 3);
+    assert_eq!(shape.double(), 24);
+}
+
+// ============================================================================
+// Context Parameter Tests
+// ============================================================================
+
+/// Method taking the Context first, like with method!.
+#[solidus_macros::method]
+fn method_with_context(_ctx: &Context, rb_self: TestValue, by: i64) -> Result<i64, Error> {
+    Ok(rb_self.0 + by)
+}
+
+/// Function asking for a larger Context in its signature.
+#[solidus_macros::function]
+fn function_with_large_context(
+    ctx: &Context<'_, 32>,
+    name: Option<String>,
+) -> Result<usize, Error> {
+    let _ = name;
+    Ok(ctx.capacity())
+}
+
+/// Function generic over the capacity, which the attribute chooses.
+#[solidus_macros::function(slots = 16)]
+fn function_with_slots<const N: usize>(ctx: &Context<'_, N>) -> Result<usize, Error> {
+    Ok(ctx.capacity())
+}
+
+#[test]
+fn test_context_not_counted_in_arity() {
+    assert_eq!(__solidus_method_method_with_context::ARITY, 1);
+    assert_eq!(__solidus_function_function_with_large_context::ARITY, -1);
+    assert_eq!(__solidus_function_function_with_slots::ARITY, 0);
+}
+
+#[test]
+fn test_context_wrappers_compile() {
+    let _: MethodFn<1> = __solidus_method_method_with_context::wrapper();
+    let _: MethodFn<-1> = __solidus_function_function_with_large_context::wrapper();
+    let _: MethodFn<0> = __solidus_function_function_with_slots::wrapper();
+}
+
+#[test]
+fn test_context_direct_call() {
+    let ctx: Context<'_, 32> = Context::default();
+    assert_eq!(function_with_large_context(&ctx, None).unwrap(), 32);
+    assert_eq!(function_with_slots(&ctx).unwrap(), 32);
+    assert_eq!(
+        method_with_context(&Context::default(), TestValue(1), 2).unwrap(),
+        3
+    );
+    assert_eq!(TestShape::default().spare_slots(&ctx).unwrap(), 32);
+}
@@ -4,12 +4,21 @@
 //! ensuring they are visible to Ruby's GC during method execution.
 
 use std::cell::{Cell, UnsafeCell};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::pin::Pin;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+use crate::convert::{IntoValue, TryConvert};
 use crate::error::AllocationError;
-use crate::types::{RArray, RHash, RString};
+use crate::error::{Error, ExceptionClass, protect};
+use crate::method::Block;
+use crate::typed_data::{TypedData, wrap_raw};
+use crate::types::{
+    RArray, RBignum, RClass, RFloat, RHash, RMethod, RModule, RProc, RString, Symbol,
+};
 use crate::value::{BoxValue, ReprValue, StackPinned, Value};
 
 /// Context for creating Ruby values within a method call.
@@ -20,9 +29,27 @@ use crate::value::{BoxValue, ReprValue, StackPinned, Value};
 ///
 /// # Capacity
 ///
-/// By default, Context provides 8 VALUE slots. If you need more, the capacity
-/// can be customized via const generics. If slots are exhausted, `new_xxx()`
-/// methods return `Err(AllocationError)`.
+/// By default, Context provides 8 VALUE slots. If slots are exhausted,
+/// `new_xxx()` methods return `Err(AllocationError)`.
+///
+/// A method that needs more asks for them in its signature. The `method!` and
+/// `function!` wrappers, and `#[solidus::method]`/`#[solidus::function]`,
+/// create the Context the function takes:
+///
+/// ```ignore
+/// fn build<'ctx>(ctx: &'ctx Context<'ctx, 32>, rb_self: Value) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+///     // up to 32 values
+/// }
+/// ```
+///
+/// A function generic over the capacity gets it from the macro instead, with
+/// `method!(build, 0, slots = 32)` or `#[solidus::method(slots = 32)]`.
+///
+/// Values created in a loop can use a nested [`scope`](Self::scope) instead,
+/// whose slots are reclaimed after every iteration.
+///
+/// In debug builds, the wrappers record how many slots each method has
+/// needed; see [`slot_usage`].
 ///
 /// # Interior Mutability
 ///
@@ -54,21 +81,6 @@ pub struct Context<'a, const N: usize = 8> {
 }
 
 impl<'a, const N: usize> Context<'a, N> {
-    /// Create a new Context.
-    ///
-    /// This is `#[doc(hidden)]` because only the `method!`/`function!` macros
-    /// should create Contexts.
-    #[doc(hidden)]
-    #[inline]
-    pub fn new() -> Self {
-        Context {
-            // SAFETY: MaybeUninit doesn't require initialization
-            slots: UnsafeCell::new(unsafe { MaybeUninit::uninit().assume_init() }),
-            used: Cell::new(0),
-            _marker: PhantomData,
-        }
-    }
-
     /// Returns the number of available slots.
     #[inline]
     pub fn available(&self) -> usize {
@@ -92,6 +104,12 @@ impl<'a, const N: usize> Context<'a, N> {
         &'a self,
         value: T,
     ) -> Result<Pin<&'a StackPinned<T>>, AllocationError> {
+        // Slots hold a single VALUE, so T must be one too. This rules out the
+        // enums `Float` and `Integer`; pin their variants instead.
+        const {
+            assert!(std::mem::size_of::<T>() == std::mem::size_of::<rb_sys::VALUE>());
+        }
+
         let idx = self.used.get();
         if idx >= N {
             return Err(AllocationError);
@@ -114,12 +132,47 @@ impl<'a, const N: usize> Context<'a, N> {
         Ok(unsafe { Pin::new_unchecked(&*slot_ptr) })
     }
 
+    // ========================================================================
+    // Generic creation
+    // ========================================================================
+
+    /// Convert a Rust value to Ruby and store it in Context's stack slots as
+    /// a `T`.
+    ///
+    /// This works for every [`IntoValue`] type, picking the Ruby type to pin
+    /// it as with [`TryConvert`]:
+    ///
+    /// ```ignore
+    /// fn build<'ctx>(ctx: &'ctx Context<'ctx>) -> Result<(), Error> {
+    ///     let name = ctx.new::<RString>("hello")?;
+    ///     let big = ctx.new::<RBignum>(u64::MAX)?;
+    ///     let list = ctx.new::<RArray>(vec![1i64, 2, 3])?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if the Ruby value isn't a `T`, or an error if
+    /// all slots are exhausted.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<T: ReprValue + TryConvert>(
+        &'a self,
+        value: impl IntoValue,
+    ) -> Result<Pin<&'a StackPinned<T>>, Error> {
+        let value = T::try_convert(value.into_value())?;
+        // SAFETY: value was just converted, so it is a valid Ruby value
+        Ok(unsafe { self.alloc_slot(value) }?)
+    }
+
     // ========================================================================
     // String creation
     // ========================================================================
 
     /// Create a new Ruby string, stored in Context's stack slots.
     ///
+    /// The string has UTF-8 encoding.
+    ///
     /// Returns `Err(AllocationError)` if all slots are exhausted.
     ///
     /// # Example
@@ -130,11 +183,8 @@ impl<'a, const N: usize> Context<'a, N> {
     /// }
     /// ```
     pub fn new_string(&'a self, s: &str) -> Result<Pin<&'a StackPinned<RString>>, AllocationError> {
-        // SAFETY: rb_str_new creates a valid VALUE
-        let value = unsafe {
-            let val = rb_sys::rb_str_new(s.as_ptr() as *const std::os::raw::c_char, s.len() as _);
-            RString::from_value_unchecked(Value::from_raw(val))
-        };
+        // SAFETY: from_str_raw creates a valid UTF-8 string
+        let value = unsafe { RString::from_str_raw(s) };
         // SAFETY: value is a valid Ruby string VALUE
         unsafe { self.alloc_slot(value) }
     }
@@ -198,6 +248,24 @@ impl<'a, const N: usize> Context<'a, N> {
         RArray::new_boxed()
     }
 
+    /// Create a Ruby array from a Rust slice, stored in Context's stack slots.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_array_from_slice<T: IntoValue + Copy>(
+        &'a self,
+        slice: &[T],
+    ) -> Result<Pin<&'a StackPinned<RArray>>, AllocationError> {
+        let value = unsafe { RArray::from_slice(slice) };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a Ruby array from a Rust slice, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_array_from_slice_boxed<T: IntoValue + Copy>(&self, slice: &[T]) -> BoxValue<RArray> {
+        RArray::from_slice_boxed(slice)
+    }
+
     // ========================================================================
     // Hash creation
     // ========================================================================
@@ -213,6 +281,15 @@ impl<'a, const N: usize> Context<'a, N> {
         unsafe { self.alloc_slot(value) }
     }
 
+    /// Create a new Ruby hash with room for `capacity` pairs.
+    pub fn new_hash_with_capacity(
+        &'a self,
+        capacity: usize,
+    ) -> Result<Pin<&'a StackPinned<RHash>>, AllocationError> {
+        let value = unsafe { RHash::with_capacity(capacity) };
+        unsafe { self.alloc_slot(value) }
+    }
+
     /// Create a new Ruby hash, boxed for heap storage.
     ///
     /// This always succeeds (uses heap allocation, not Context slots).
@@ -220,6 +297,332 @@ impl<'a, const N: usize> Context<'a, N> {
         RHash::new_boxed()
     }
 
+    /// Create a Ruby hash from a Rust `HashMap`, stored in Context's stack
+    /// slots.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_hash_from_hash_map<K, V>(
+        &'a self,
+        map: HashMap<K, V>,
+    ) -> Result<Pin<&'a StackPinned<RHash>>, AllocationError>
+    where
+        K: IntoValue,
+        V: IntoValue,
+    {
+        let value = unsafe { RHash::from_hash_map(map) };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a Ruby hash from a Rust `HashMap`, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_hash_from_hash_map_boxed<K, V>(&self, map: HashMap<K, V>) -> BoxValue<RHash>
+    where
+        K: IntoValue,
+        V: IntoValue,
+    {
+        RHash::from_hash_map_boxed(map)
+    }
+
+    // ========================================================================
+    // Proc creation
+    // ========================================================================
+
+    /// Create a Ruby proc that calls a Rust closure, stored in Context's stack slots.
+    ///
+    /// See [`RProc::from_fn`] for how the closure is called.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_proc<F, R>(&'a self, func: F) -> Result<Pin<&'a StackPinned<RProc>>, AllocationError>
+    where
+        F: Fn(&[Value]) -> Result<R, Error> + 'static,
+        R: IntoValue,
+    {
+        let value = unsafe { RProc::from_fn(func) };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a Ruby proc that calls a Rust closure, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_proc_boxed<F, R>(&self, func: F) -> BoxValue<RProc>
+    where
+        F: Fn(&[Value]) -> Result<R, Error> + 'static,
+        R: IntoValue,
+    {
+        RProc::from_fn_boxed(func)
+    }
+
+    // ========================================================================
+    // Numeric creation
+    // ========================================================================
+
+    /// Create a heap-allocated Ruby float, stored in Context's stack slots.
+    ///
+    /// Floats that fit in a [`Flonum`](crate::types::Flonum) are immediates
+    /// and need no slot; this is for the ones that don't, or when an
+    /// [`RFloat`] is needed either way.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_float(&'a self, n: f64) -> Result<Pin<&'a StackPinned<RFloat>>, AllocationError> {
+        // SAFETY: the value is pinned right away
+        let value = unsafe { RFloat::from_f64(n).into_inner() };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a heap-allocated Ruby float, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_float_boxed(&self, n: f64) -> BoxValue<RFloat> {
+        RFloat::from_f64_boxed(n)
+    }
+
+    /// Create a Ruby Bignum from an i64, stored in Context's stack slots.
+    ///
+    /// Returns `Ok(None)` if the value fits in a Fixnum, which needs no slot,
+    /// and `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_bignum_from_i64(
+        &'a self,
+        n: i64,
+    ) -> Result<Option<Pin<&'a StackPinned<RBignum>>>, AllocationError> {
+        // SAFETY: the value is pinned right away
+        match unsafe { RBignum::from_i64(n) } {
+            Some(value) => unsafe { self.alloc_slot(value.into_inner()) }.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a Ruby Bignum from a u64, stored in Context's stack slots.
+    ///
+    /// Returns `Ok(None)` if the value fits in a Fixnum, which needs no slot,
+    /// and `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_bignum_from_u64(
+        &'a self,
+        n: u64,
+    ) -> Result<Option<Pin<&'a StackPinned<RBignum>>>, AllocationError> {
+        // SAFETY: the value is pinned right away
+        match unsafe { RBignum::from_u64(n) } {
+            Some(value) => unsafe { self.alloc_slot(value.into_inner()) }.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a Ruby Bignum from an i64, boxed for heap storage.
+    ///
+    /// Returns `None` if the value fits in a Fixnum.
+    pub fn new_bignum_from_i64_boxed(&self, n: i64) -> Option<BoxValue<RBignum>> {
+        RBignum::from_i64_boxed(n)
+    }
+
+    /// Create a Ruby Bignum from a u64, boxed for heap storage.
+    ///
+    /// Returns `None` if the value fits in a Fixnum.
+    pub fn new_bignum_from_u64_boxed(&self, n: u64) -> Option<BoxValue<RBignum>> {
+        RBignum::from_u64_boxed(n)
+    }
+
+    // ========================================================================
+    // Symbol creation
+    // ========================================================================
+
+    /// Create a dynamic Ruby symbol, stored in Context's stack slots.
+    ///
+    /// Unlike [`Symbol::new`], which creates a static symbol that is never
+    /// collected, this interns `name` like `String#to_sym`, so symbols built
+    /// from user input can be garbage collected again. The name is UTF-8 and
+    /// may contain null bytes.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_symbol(
+        &'a self,
+        name: &str,
+    ) -> Result<Pin<&'a StackPinned<Symbol>>, AllocationError> {
+        // SAFETY: rb_str_intern returns a Symbol VALUE for any string
+        let value = unsafe {
+            let val = rb_sys::rb_str_intern(rb_sys::rb_utf8_str_new(
+                name.as_ptr() as *const std::os::raw::c_char,
+                name.len() as _,
+            ));
+            Symbol::from_value_unchecked(Value::from_raw(val))
+        };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a dynamic Ruby symbol, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_symbol_boxed(&self, name: &str) -> BoxValue<Symbol> {
+        // SAFETY: rb_str_intern returns a Symbol VALUE for any string
+        let value = unsafe {
+            let val = rb_sys::rb_str_intern(rb_sys::rb_utf8_str_new(
+                name.as_ptr() as *const std::os::raw::c_char,
+                name.len() as _,
+            ));
+            Symbol::from_value_unchecked(Value::from_raw(val))
+        };
+        BoxValue::new(value)
+    }
+
+    // ========================================================================
+    // Range creation
+    // ========================================================================
+
+    /// Create a Ruby range, stored in Context's stack slots.
+    ///
+    /// This is `start..end`, or `start...end` if `exclusive` is true. Pass
+    /// `Value::nil()` for an endless or beginless range.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if `start` and `end` can't be compared, or
+    /// an error if all slots are exhausted.
+    pub fn new_range(
+        &'a self,
+        start: impl IntoValue,
+        end: impl IntoValue,
+        exclusive: bool,
+    ) -> Result<Pin<&'a StackPinned<Value>>, Error> {
+        let value = range_new(start.into_value(), end.into_value(), exclusive)?;
+        // SAFETY: rb_range_new returned a valid Range VALUE
+        Ok(unsafe { self.alloc_slot(value) }?)
+    }
+
+    /// Create a Ruby range, boxed for heap storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if `start` and `end` can't be compared.
+    pub fn new_range_boxed(
+        &self,
+        start: impl IntoValue,
+        end: impl IntoValue,
+        exclusive: bool,
+    ) -> Result<BoxValue<Value>, Error> {
+        range_new(start.into_value(), end.into_value(), exclusive).map(BoxValue::new)
+    }
+
+    // ========================================================================
+    // Exception creation
+    // ========================================================================
+
+    /// Create a Ruby exception with a UTF-8 message, stored in Context's
+    /// stack slots, without raising it.
+    ///
+    /// To raise an exception, return an [`Error`] instead.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_exception(
+        &'a self,
+        class: ExceptionClass,
+        message: &str,
+    ) -> Result<Pin<&'a StackPinned<Value>>, AllocationError> {
+        let value = exception_new(class, message);
+        // SAFETY: rb_exc_new_str returned a valid exception VALUE
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Create a Ruby exception with a UTF-8 message, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_exception_boxed(&self, class: ExceptionClass, message: &str) -> BoxValue<Value> {
+        BoxValue::new(exception_new(class, message))
+    }
+
+    // ========================================================================
+    // Class, module and method creation
+    // ========================================================================
+
+    /// Create an anonymous class inheriting from `superclass`, stored in
+    /// Context's stack slots.
+    ///
+    /// Like `Class.new(superclass)`, except that the `inherited` hook isn't
+    /// called. The class has no name until it is assigned to a constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if `superclass` can't be subclassed (a singleton
+    /// class or `Class`), or an error if all slots are exhausted.
+    pub fn new_class(&'a self, superclass: &RClass) -> Result<Pin<&'a StackPinned<RClass>>, Error> {
+        let superclass = superclass.as_raw();
+        // SAFETY: superclass is a valid class
+        let val = protect(|| unsafe { rb_sys::rb_class_new(superclass) })?;
+        let value = unsafe { RClass::from_value_unchecked(Value::from_raw(val)) };
+        Ok(unsafe { self.alloc_slot(value) }?)
+    }
+
+    /// Create an anonymous module, stored in Context's stack slots.
+    ///
+    /// Like `Module.new`; the module has no name until it is assigned to a
+    /// constant.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    pub fn new_module(&'a self) -> Result<Pin<&'a StackPinned<RModule>>, AllocationError> {
+        let value = unsafe {
+            let val = rb_sys::rb_module_new();
+            RModule::from_value_unchecked(Value::from_raw(val))
+        };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Look up the method `name` of `receiver` as a bound [`RMethod`], stored
+    /// in Context's stack slots.
+    ///
+    /// This is `receiver.method(name)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `NameError` if `receiver` has no such method, or an error if
+    /// all slots are exhausted.
+    pub fn new_method<R: ReprValue>(
+        &'a self,
+        receiver: &R,
+        name: &str,
+    ) -> Result<Pin<&'a StackPinned<RMethod>>, Error> {
+        let receiver = receiver.as_raw();
+        let name = Symbol::new(name).as_raw();
+        // SAFETY: receiver is a valid VALUE and name a Symbol
+        let val = protect(|| unsafe { rb_sys::rb_obj_method(receiver, name) })?;
+        let value = unsafe { RMethod::from_value_unchecked(Value::from_raw(val)) };
+        Ok(unsafe { self.alloc_slot(value) }?)
+    }
+
+    // ========================================================================
+    // TypedData creation
+    // ========================================================================
+
+    /// Wrap a Rust value in a new object of `class`, stored in Context's
+    /// stack slots.
+    ///
+    /// This is [`typed_data::wrap`](crate::typed_data::wrap) for use inside a
+    /// method. The value is freed when the object is garbage collected.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn origin<'ctx>(ctx: &'ctx Context<'ctx>, class: RClass) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+    ///     Ok(ctx.new_typed_data(&class, Point { x: 0.0, y: 0.0 })?)
+    /// }
+    /// ```
+    pub fn new_typed_data<T: TypedData>(
+        &'a self,
+        class: &RClass,
+        value: T,
+    ) -> Result<Pin<&'a StackPinned<Value>>, AllocationError> {
+        // SAFETY: class is a valid class and the object is pinned right away
+        let value = unsafe { Value::from_raw(wrap_raw(class.as_raw(), value)) };
+        unsafe { self.alloc_slot(value) }
+    }
+
+    /// Wrap a Rust value in a new object of `class`, boxed for heap storage.
+    ///
+    /// This always succeeds (uses heap allocation, not Context slots).
+    pub fn new_typed_data_boxed<T: TypedData>(&self, class: &RClass, value: T) -> BoxValue<Value> {
+        // SAFETY: class is a valid class and the object is boxed right away
+        BoxValue::new(unsafe { Value::from_raw(wrap_raw(class.as_raw(), value)) })
+    }
+
     // ========================================================================
     // Generic value pinning
     // ========================================================================
@@ -244,14 +647,252 @@ impl<'a, const N: usize> Context<'a, N> {
         // SAFETY: Caller provides a valid Ruby value
         unsafe { self.alloc_slot(value) }
     }
+
+    // ========================================================================
+    // Scopes
+    // ========================================================================
+
+    /// Run `func` with a nested Context, whose slots are reclaimed when it
+    /// returns.
+    ///
+    /// Slots are never released individually, so a loop creating values
+    /// would exhaust the Context. Creating them in a scope instead uses the
+    /// nested Context's slots, which are free again for the next iteration.
+    /// The nested Context has the same capacity as this one.
+    ///
+    /// Values created in the scope can't outlive it. To keep one, pin it in
+    /// this Context with [`promote`](Self::promote) or box it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn labels<'ctx>(ctx: &'ctx Context<'ctx>, rb_self: RArray) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+    ///     let labels = ctx.new_array()?;
+    ///     for i in 0..rb_self.len() {
+    ///         ctx.scope(|inner| -> Result<(), Error> {
+    ///             let label = inner.new_string(&format!("item {}", i))?;
+    ///             labels.get().push(label.get().clone())?;
+    ///             Ok(())
+    ///         })?;
+    ///     }
+    ///     Ok(labels)
+    /// }
+    /// ```
+    ///
+    /// Returning a value created in the scope doesn't compile:
+    ///
+    /// ```compile_fail
+    /// use solidus::prelude::*;
+    ///
+    /// fn escape<'ctx>(ctx: &'ctx Context<'ctx>) -> Result<(), Error> {
+    ///     let s = ctx.scope(|inner| inner.new_string("hello"))?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn scope<F, R>(&self, func: F) -> R
+    where
+        F: for<'s> FnOnce(&'s Context<'s, N>) -> R,
+    {
+        let inner = Context::default();
+        func(&inner)
+    }
+
+    /// Pin a value created in a nested [`scope`](Self::scope) in this
+    /// Context, so it outlives the scope.
+    ///
+    /// Returns `Err(AllocationError)` if all slots are exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn first_label<'ctx>(ctx: &'ctx Context<'ctx>) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+    ///     ctx.scope(|inner| {
+    ///         let label = inner.new_string("first")?;
+    ///         Ok(ctx.promote(label)?)
+    ///     })
+    /// }
+    /// ```
+    pub fn promote<T: ReprValue>(
+        &'a self,
+        value: Pin<&StackPinned<T>>,
+    ) -> Result<Pin<&'a StackPinned<T>>, AllocationError> {
+        // SAFETY: value is pinned, so it is a valid Ruby value
+        unsafe { self.alloc_slot(value.get().clone()) }
+    }
+
+    // ========================================================================
+    // Blocks
+    // ========================================================================
+
+    /// Access the block passed to the current method call.
+    ///
+    /// Use [`Block::is_given()`] to check whether there is one before
+    /// yielding to it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn map_one<'ctx>(ctx: &'ctx Context, rb_self: Value) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+    ///     ctx.block().yield_value(ctx, 1i64)
+    /// }
+    /// ```
+    #[inline]
+    pub fn block(&self) -> Block<'_> {
+        Block::new()
+    }
+}
+
+/// Create a range, raising if the ends can't be compared.
+fn range_new(start: Value, end: Value, exclusive: bool) -> Result<Value, Error> {
+    let (start, end) = (start.as_raw(), end.as_raw());
+    // SAFETY: start and end are valid VALUEs
+    let val = protect(|| unsafe { rb_sys::rb_range_new(start, end, exclusive as _) })?;
+    Ok(unsafe { Value::from_raw(val) })
+}
+
+/// Create an exception of `class` with a UTF-8 `message`.
+fn exception_new(class: ExceptionClass, message: &str) -> Value {
+    // SAFETY: class is a built-in exception class, and rb_utf8_str_new copies
+    // exactly `len` bytes of the message
+    unsafe {
+        let message = rb_sys::rb_utf8_str_new(
+            message.as_ptr() as *const std::os::raw::c_char,
+            message.len() as _,
+        );
+        Value::from_raw(rb_sys::rb_exc_new_str(class.as_value().as_raw(), message))
+    }
 }
 
 impl<const N: usize> Default for Context<'_, N> {
+    /// Create an empty Context.
+    ///
+    /// Only the `method!`/`function!` macros and the attribute macros should
+    /// need to create Contexts; everything else gets one passed in.
+    #[inline]
     fn default() -> Self {
-        Self::new()
+        Context {
+            // SAFETY: MaybeUninit doesn't require initialization
+            slots: UnsafeCell::new(unsafe { MaybeUninit::uninit().assume_init() }),
+            used: Cell::new(0),
+            _marker: PhantomData,
+        }
     }
 }
 
+// ============================================================================
+// Slot usage diagnostics
+// ============================================================================
+
+/// The most Context slots a method has used in a single call.
+///
+/// Recorded by the method wrappers in debug builds, see [`slot_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotUsage {
+    /// The Rust function the wrapper calls.
+    pub method: &'static str,
+    /// The most slots used by a single call.
+    pub high_water: usize,
+    /// The capacity of the method's Context.
+    pub capacity: usize,
+}
+
+/// Returns the slot usage of every method that has been called so far.
+///
+/// Only debug builds of the extension record usage, so this is empty in
+/// release builds. Use it to pick a capacity: a method whose `high_water`
+/// equals its `capacity` has run out of slots, or is about to.
+///
+/// Setting the `SOLIDUS_SLOT_USAGE` environment variable also prints a line
+/// to stderr whenever a method's high-water mark grows:
+///
+/// ```text
+/// solidus: build used 9 of 32 Context slots
+/// ```
+pub fn slot_usage() -> Vec<SlotUsage> {
+    let recorders = SLOT_RECORDERS.lock().unwrap_or_else(|e| e.into_inner());
+    recorders.iter().map(|recorder| recorder.usage()).collect()
+}
+
+/// Every [`SlotRecorder`] that has recorded a call.
+static SLOT_RECORDERS: Mutex<Vec<&'static SlotRecorder>> = Mutex::new(Vec::new());
+
+/// Records the slot usage of one method, in a static of its wrapper.
+#[doc(hidden)]
+pub struct SlotRecorder {
+    method: &'static str,
+    high_water: AtomicUsize,
+    capacity: AtomicUsize,
+    registered: AtomicBool,
+}
+
+impl SlotRecorder {
+    /// Create a recorder for the wrapper of `method`.
+    pub const fn new(method: &'static str) -> Self {
+        SlotRecorder {
+            method,
+            high_water: AtomicUsize::new(0),
+            capacity: AtomicUsize::new(0),
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    /// Record the slots `ctx` has used.
+    pub fn record<const N: usize>(&'static self, ctx: &Context<'_, N>) {
+        let used = N - ctx.available();
+        self.capacity.store(N, Ordering::Relaxed);
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            SLOT_RECORDERS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(self);
+        }
+
+        let previous = self.high_water.fetch_max(used, Ordering::Relaxed);
+        if used > previous && std::env::var_os("SOLIDUS_SLOT_USAGE").is_some() {
+            eprintln!(
+                "solidus: {} used {} of {} Context slots",
+                self.method, used, N
+            );
+        }
+    }
+
+    fn usage(&self) -> SlotUsage {
+        SlotUsage {
+            method: self.method,
+            high_water: self.high_water.load(Ordering::Relaxed),
+            capacity: self.capacity.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Record the slots used by a wrapper's Context, in debug builds.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __record_slot_usage {
+    ($method:expr, $ctx:expr) => {
+        #[cfg(debug_assertions)]
+        {
+            static RECORDER: $crate::context::SlotRecorder =
+                $crate::context::SlotRecorder::new($method);
+            RECORDER.record($ctx);
+        }
+    };
+}
+
+/// Create the Context for a wrapper, with the capacity given to the macro or
+/// else the one the function's signature asks for.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __wrapper_context {
+    () => {
+        $crate::context::Context::default()
+    };
+    ($slots:expr) => {
+        $crate::context::Context::<{ $slots }>::default()
+    };
+}
+
 // Context is !Send and !Sync because:
 // 1. It contains raw pointers to Ruby VALUEs
 // 2. Ruby's GC is not thread-safe
@@ -264,18 +905,32 @@ mod tests {
 
     #[test]
     fn test_context_capacity() {
-        let ctx: Context<'_, 8> = Context::new();
+        let ctx: Context<'_, 8> = Context::default();
         assert_eq!(ctx.capacity(), 8);
         assert_eq!(ctx.available(), 8);
     }
 
     #[test]
     fn test_context_custom_capacity() {
-        let ctx: Context<'_, 16> = Context::new();
+        let ctx: Context<'_, 16> = Context::default();
         assert_eq!(ctx.capacity(), 16);
         assert_eq!(ctx.available(), 16);
     }
 
+    #[test]
+    fn test_slot_recorder() {
+        static RECORDER: SlotRecorder = SlotRecorder::new("test_slot_recorder");
+        let ctx: Context<'_, 4> = Context::default();
+        RECORDER.record(&ctx);
+
+        let usage = slot_usage()
+            .into_iter()
+            .find(|usage| usage.method == "test_slot_recorder")
+            .unwrap();
+        assert_eq!(usage.high_water, 0);
+        assert_eq!(usage.capacity, 4);
+    }
+
     // Tests that call Ruby require the embed or link-ruby feature
     #[cfg(any(feature = "embed", feature = "link-ruby"))]
     mod ruby_tests {
@@ -284,7 +939,7 @@ mod tests {
 
         #[ruby_test]
         fn test_new_string() {
-            let ctx: Context<'_> = Context::new();
+            let ctx: Context<'_> = Context::default();
             let s = ctx.new_string("hello").unwrap();
             assert_eq!(s.get().len(), 5);
             assert_eq!(ctx.available(), 7);
@@ -292,32 +947,32 @@ mod tests {
 
         #[ruby_test]
         fn test_new_string_from_slice() {
-            let ctx: Context<'_> = Context::new();
+            let ctx: Context<'_> = Context::default();
             let s = ctx.new_string_from_slice(b"hello\x00world").unwrap();
             assert_eq!(s.get().len(), 11);
         }
 
         #[ruby_test]
         fn test_new_array() {
-            let ctx: Context<'_> = Context::new();
+            let ctx: Context<'_> = Context::default();
             let arr = ctx.new_array().unwrap();
             assert_eq!(arr.get().len(), 0);
-            arr.get().push(42i64);
+            arr.get().push(42i64).unwrap();
             assert_eq!(arr.get().len(), 1);
         }
 
         #[ruby_test]
         fn test_new_hash() {
-            let ctx: Context<'_> = Context::new();
+            let ctx: Context<'_> = Context::default();
             let hash = ctx.new_hash().unwrap();
             assert!(hash.get().is_empty());
-            hash.get().insert("key", 42i64);
+            hash.get().insert("key", 42i64).unwrap();
             assert_eq!(hash.get().len(), 1);
         }
 
         #[ruby_test]
         fn test_multiple_values() {
-            let ctx: Context<'_> = Context::new();
+            let ctx: Context<'_> = Context::default();
             let s1 = ctx.new_string("hello").unwrap();
             let s2 = ctx.new_string("world").unwrap();
             assert_eq!(s1.get().len(), 5);
@@ -327,16 +982,171 @@ mod tests {
 
         #[ruby_test]
         fn test_exhaustion() {
-            let ctx: Context<'_, 2> = Context::new();
+            let ctx: Context<'_, 2> = Context::default();
             let _s1 = ctx.new_string("a").unwrap();
             let _s2 = ctx.new_string("b").unwrap();
             let result = ctx.new_string("c");
             assert!(result.is_err());
         }
 
+        #[ruby_test]
+        fn test_scope_reclaims_slots() {
+            let ctx: Context<'_, 2> = Context::default();
+            let array = ctx.new_array().unwrap();
+            for i in 0..10 {
+                ctx.scope(|inner| {
+                    let a = inner.new_string(&i.to_string()).unwrap();
+                    let b = inner.new_string("!").unwrap();
+                    assert!(inner.new_string("full").is_err());
+                    array.get().push(a.get().clone()).unwrap();
+                    array.get().push(b.get().clone()).unwrap();
+                });
+            }
+            assert_eq!(array.get().len(), 20);
+            assert_eq!(ctx.available(), 1);
+        }
+
+        #[ruby_test]
+        fn test_promote() {
+            let ctx: Context<'_> = Context::default();
+            let promoted = ctx.scope(|inner| {
+                let s = inner.new_string("kept").unwrap();
+                ctx.promote(s).unwrap()
+            });
+            assert_eq!(promoted.get().to_string().unwrap(), "kept");
+            assert_eq!(ctx.available(), 7);
+        }
+
+        fn inspect<T: ReprValue>(value: &T) -> String {
+            let s = value.as_value().funcall_boxed("inspect", ()).unwrap();
+            String::try_convert(s.inner()).unwrap()
+        }
+
+        #[ruby_test]
+        fn test_new_generic() {
+            let ctx: Context<'_> = Context::default();
+            let s = ctx.new::<RString>("hello").unwrap();
+            assert_eq!(s.get().len(), 5);
+            let arr = ctx.new::<RArray>(vec![1i64, 2, 3]).unwrap();
+            assert_eq!(arr.get().len(), 3);
+            let big = ctx.new::<RBignum>(u64::MAX).unwrap();
+            assert_eq!(big.get().to_u64().unwrap(), u64::MAX);
+
+            let err = ctx.new::<RHash>("hello").err().unwrap();
+            assert!(err.is_a(&ExceptionClass::TypeError.as_value()));
+            assert_eq!(ctx.available(), 5);
+        }
+
+        #[ruby_test]
+        fn test_new_float() {
+            let ctx: Context<'_> = Context::default();
+            let f = ctx.new_float(1e300).unwrap();
+            assert_eq!(f.get().to_f64(), 1e300);
+            assert_eq!(ctx.new_float_boxed(0.5).to_f64(), 0.5);
+        }
+
+        #[ruby_test]
+        fn test_new_bignum() {
+            let ctx: Context<'_> = Context::default();
+            let big = ctx.new_bignum_from_i64(i64::MIN).unwrap().unwrap();
+            assert_eq!(big.get().to_i64().unwrap(), i64::MIN);
+            let big = ctx.new_bignum_from_u64(u64::MAX).unwrap().unwrap();
+            assert_eq!(big.get().to_u64().unwrap(), u64::MAX);
+
+            // Fixnums need no slot
+            assert!(ctx.new_bignum_from_i64(42).unwrap().is_none());
+            assert_eq!(ctx.available(), 6);
+        }
+
+        #[ruby_test]
+        fn test_new_symbol() {
+            let ctx: Context<'_> = Context::default();
+            let sym = ctx.new_symbol("dynamic_näme").unwrap();
+            assert_eq!(sym.get().name().unwrap(), "dynamic_näme");
+            assert_eq!(
+                sym.get().as_raw(),
+                ctx.new_symbol_boxed("dynamic_näme").as_raw()
+            );
+        }
+
+        #[ruby_test]
+        fn test_new_range() {
+            let ctx: Context<'_> = Context::default();
+            let range = ctx.new_range(1i64, 10i64, false).unwrap();
+            assert_eq!(inspect(&*range.get()), "1..10");
+            let range = ctx.new_range(1i64, Value::nil(), true).unwrap();
+            assert_eq!(inspect(&*range.get()), "1...");
+
+            let err = ctx.new_range(1i64, "z", false).err().unwrap();
+            assert!(err.is_a(&ExceptionClass::ArgumentError.as_value()));
+        }
+
+        #[ruby_test]
+        fn test_new_exception() {
+            let ctx: Context<'_> = Context::default();
+            let exc = ctx
+                .new_exception(ExceptionClass::ArgumentError, "bad välue")
+                .unwrap();
+            assert_eq!(inspect(&*exc.get()), "#<ArgumentError: bad välue>");
+        }
+
+        #[ruby_test]
+        fn test_new_class_and_module() {
+            let ctx: Context<'_> = Context::default();
+            let string = RClass::from_name("String").unwrap();
+            let class = ctx.new_class(&string).unwrap();
+            assert!(class.get().name().is_none());
+            assert_eq!(
+                class.get().clone().superclass().unwrap().as_raw(),
+                string.as_raw()
+            );
+            let module = ctx.new_module().unwrap();
+            assert!(module.get().name().is_none());
+
+            let class_class = RClass::from_name("Class").unwrap();
+            assert!(ctx.new_class(&class_class).is_err());
+        }
+
+        #[ruby_test]
+        fn test_new_method() {
+            let ctx: Context<'_> = Context::default();
+            let s = ctx.new_string("hello").unwrap();
+            let method = ctx.new_method(&*s.get(), "upcase").unwrap();
+            let result = method.get().call_boxed(()).unwrap();
+            assert_eq!(String::try_convert(result.inner()).unwrap(), "HELLO");
+
+            let err = ctx.new_method(&*s.get(), "no_such_method").err().unwrap();
+            assert!(err.is_a(&ExceptionClass::NameError.as_value()));
+        }
+
+        struct Point(i64);
+
+        impl TypedData for Point {
+            fn class_name() -> &'static str {
+                "TestContextPoint"
+            }
+            fn data_type() -> &'static crate::typed_data::DataType {
+                static DT: std::sync::OnceLock<crate::typed_data::DataType> =
+                    std::sync::OnceLock::new();
+                DT.get_or_init(|| {
+                    crate::typed_data::DataTypeBuilder::<Point>::new("TestContextPoint").build()
+                })
+            }
+        }
+
+        #[ruby_test]
+        fn test_new_typed_data() {
+            let ctx: Context<'_> = Context::default();
+            let object = RClass::from_name("Object").unwrap();
+            let point = ctx.new_typed_data(&object, Point(3)).unwrap();
+            assert_eq!(crate::typed_data::get::<Point>(point.get()).unwrap().0, 3);
+            let boxed = ctx.new_typed_data_boxed(&object, Point(4));
+            assert_eq!(crate::typed_data::get::<Point>(&boxed).unwrap().0, 4);
+        }
+
         #[ruby_test]
         fn test_boxed_always_succeeds() {
-            let ctx: Context<'_, 0> = Context::new();
+            let ctx: Context<'_, 0> = Context::default();
             // Even with 0 slots, boxed methods work
             let boxed = ctx.new_string_boxed("hello");
             assert_eq!(boxed.len(), 5);
@@ -0,0 +1,96 @@
+//! Argument lists for calling Ruby methods from Rust.
+
+use super::IntoValue;
+use crate::value::Value;
+
+/// A list of arguments that can be passed to a Ruby method.
+///
+/// This trait is implemented for tuples of up to 8 elements and for arrays,
+/// where every element implements [`IntoValue`]. The converted arguments are
+/// collected into a fixed-size array, so they stay on the stack (and thus
+/// visible to Ruby's GC) for the duration of the call.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// fn example(receiver: &Value) -> Result<BoxValue<Value>, Error> {
+///     // No arguments
+///     let _ = receiver.funcall_boxed("to_s", ())?;
+///
+///     // A single argument must be written as a one-element tuple
+///     let _ = receiver.funcall_boxed("+", (1i64,))?;
+///
+///     // Mixed argument types
+///     receiver.funcall_boxed("send_message", ("hello", 42i64, true))
+/// }
+/// ```
+pub trait ArgList {
+    /// Stack storage for the converted arguments.
+    type Output: AsRef<[Value]>;
+
+    /// Convert the arguments into Ruby values.
+    fn into_arg_list(self) -> Self::Output;
+}
+
+impl<T: IntoValue, const N: usize> ArgList for [T; N] {
+    type Output = [Value; N];
+
+    #[inline]
+    fn into_arg_list(self) -> Self::Output {
+        self.map(IntoValue::into_value)
+    }
+}
+
+macro_rules! impl_arg_list_for_tuple {
+    ($len:literal; $($name:ident),*) => {
+        impl<$($name: IntoValue),*> ArgList for ($($name,)*) {
+            type Output = [Value; $len];
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn into_arg_list(self) -> Self::Output {
+                let ($($name,)*) = self;
+                [$($name.into_value()),*]
+            }
+        }
+    };
+}
+
+impl_arg_list_for_tuple!(0;);
+impl_arg_list_for_tuple!(1; A);
+impl_arg_list_for_tuple!(2; A, B);
+impl_arg_list_for_tuple!(3; A, B, C);
+impl_arg_list_for_tuple!(4; A, B, C, D);
+impl_arg_list_for_tuple!(5; A, B, C, D, E);
+impl_arg_list_for_tuple!(6; A, B, C, D, E, F);
+impl_arg_list_for_tuple!(7; A, B, C, D, E, F, G);
+impl_arg_list_for_tuple!(8; A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_tuple() {
+        let args = ().into_arg_list();
+        assert!(args.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_tuple_of_values() {
+        let args = (Value::nil(), Value::r#true(), Value::r#false()).into_arg_list();
+        let args = args.as_ref();
+        assert_eq!(args.len(), 3);
+        assert!(args[0].is_nil());
+        assert!(args[1].is_true());
+        assert!(args[2].is_false());
+    }
+
+    #[test]
+    fn test_array_of_values() {
+        let args = [Value::nil(), Value::nil()].into_arg_list();
+        assert_eq!(args.as_ref().len(), 2);
+    }
+}
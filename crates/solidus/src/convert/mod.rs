@@ -5,6 +5,8 @@
 //! - [`TryConvert`] - Convert from Ruby [`crate::value::Value`] to Rust types (fallible)
 //! - [`IntoValue`] - Convert from Rust types to Ruby [`crate::value::Value`] (infallible)
 //!
+//! [`ArgList`] builds on [`IntoValue`] to pass argument tuples to Ruby methods.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -21,8 +23,10 @@
 //! }
 //! ```
 
+mod arg_list;
 mod into_value;
 mod try_convert;
 
+pub use arg_list::ArgList;
 pub use into_value::IntoValue;
 pub use try_convert::{TryConvert, TryConvertOwned};
@@ -52,3 +52,33 @@ impl TryConvert for Value {
 }
 
 impl TryConvertOwned for Value {}
+
+// `nil` converts to `None`, anything else is converted to `T`
+impl<T: TryConvert> TryConvert for Option<T> {
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        if val.is_nil() {
+            Ok(None)
+        } else {
+            T::try_convert(val).map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_from_nil() {
+        assert_eq!(Option::<bool>::try_convert(Value::nil()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_option_from_value() {
+        assert_eq!(
+            Option::<bool>::try_convert(Value::r#false()).unwrap(),
+            Some(false)
+        );
+    }
+}
@@ -1,10 +1,12 @@
 //! Error handling for Ruby exceptions.
 
 use std::any::Any;
-use std::ffi::CString;
 use std::fmt;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
 
-use crate::value::Value;
+use crate::convert::TryConvert;
+use crate::value::{BoxValue, ReprValue, Value};
 
 /// Common Ruby exception classes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,6 +41,9 @@ pub enum ExceptionClass {
     FrozenError,
     /// StopIteration - iteration has ended
     StopIteration,
+    /// EncodingError - invalid or incompatible string encoding, the base
+    /// class of the transcoding errors
+    EncodingError,
 }
 
 impl ExceptionClass {
@@ -66,6 +71,7 @@ impl ExceptionClass {
                 ExceptionClass::NotImplementedError => rb_sys::rb_eNotImpError,
                 ExceptionClass::FrozenError => rb_sys::rb_eFrozenError,
                 ExceptionClass::StopIteration => rb_sys::rb_eStopIteration,
+                ExceptionClass::EncodingError => rb_sys::rb_eEncodingError,
             })
         }
     }
@@ -78,6 +84,11 @@ enum ErrorClass {
     BuiltIn(ExceptionClass),
     /// A custom exception class (already resolved)
     Custom(Value),
+    /// An exception object caught from Ruby, re-raised unchanged
+    Exception(BoxValue<Value>),
+    /// A non-exception jump (e.g. `throw` or `break`) caught from Ruby,
+    /// identified by its tag
+    Jump(c_int),
 }
 
 impl ErrorClass {
@@ -86,10 +97,19 @@ impl ErrorClass {
         match self {
             ErrorClass::BuiltIn(class) => class.as_value(),
             ErrorClass::Custom(value) => value.clone(),
+            // SAFETY: the boxed exception is a valid, GC-protected object
+            ErrorClass::Exception(exception) => unsafe {
+                Value::from_raw(rb_sys::rb_obj_class(exception.as_raw()))
+            },
+            // Jumps have no exception object; report them as RuntimeError
+            ErrorClass::Jump(_) => ExceptionClass::RuntimeError.as_value(),
         }
     }
 }
 
+/// Ruby's tag for a jump caused by raising an exception (`TAG_RAISE`).
+const TAG_RAISE: c_int = 6;
+
 /// A Ruby exception.
 ///
 /// This type represents a Ruby exception that can be raised or returned
@@ -167,11 +187,146 @@ impl Error {
         Error::new(ExceptionClass::RuntimeError, message)
     }
 
+    /// Create an error from an existing Ruby exception object.
+    ///
+    /// The exception is kept alive with a [`BoxValue`] for as long as the
+    /// error exists. Raising the error re-raises this exact object, keeping
+    /// its class, message, backtrace, `cause` and any instance variables.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn fail_with(exception: Value) -> Result<(), Error> {
+    ///     Err(Error::from_exception(exception))
+    /// }
+    /// ```
+    pub fn from_exception(exception: Value) -> Self {
+        Error {
+            message: exception_message(&exception),
+            class: ErrorClass::Exception(BoxValue::new(exception)),
+        }
+    }
+
+    /// Create an error from the state returned by `rb_protect`.
+    ///
+    /// # Safety
+    ///
+    /// `state` must be a non-zero state just returned by `rb_protect`, so that
+    /// `rb_errinfo()` still holds the pending exception.
+    unsafe fn from_protect_state(state: c_int) -> Self {
+        if state != TAG_RAISE {
+            return Error {
+                class: ErrorClass::Jump(state),
+                message: format!("unexpected non-local jump (tag {})", state),
+            };
+        }
+
+        // SAFETY: a raise just happened, so errinfo holds the exception object.
+        // Clear it so Ruby doesn't consider it still pending.
+        let exception = unsafe {
+            let exception = Value::from_raw(rb_sys::rb_errinfo());
+            rb_sys::rb_set_errinfo(rb_sys::Qnil.into());
+            exception
+        };
+
+        Error::from_exception(exception)
+    }
+
     /// Get the error message.
     pub fn message(&self) -> &str {
         &self.message
     }
 
+    /// Get the original Ruby exception object, if this error was caught from Ruby.
+    ///
+    /// Errors returned by [`protect`] (and the wrappers built on it) carry the
+    /// exception that was raised, as do errors created with
+    /// [`Error::from_exception`]. Errors created from Rust with [`Error::new`]
+    /// and friends return `None`.
+    pub fn exception(&self) -> Option<&Value> {
+        match &self.class {
+            ErrorClass::Exception(exception) => Some(exception),
+            _ => None,
+        }
+    }
+
+    /// Get the backtrace of the carried Ruby exception.
+    ///
+    /// Returns `None` for errors created from Rust (they have no backtrace
+    /// until raised) and for exceptions that were never raised.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn log_failure(error: &Error) {
+    ///     eprintln!("{}", error);
+    ///     for line in error.backtrace().unwrap_or_default() {
+    ///         eprintln!("    {}", line);
+    ///     }
+    /// }
+    /// ```
+    pub fn backtrace(&self) -> Option<Vec<String>> {
+        let exception = self.exception()?;
+        let backtrace = exception.funcall_boxed("backtrace", ()).ok()?;
+        if backtrace.is_nil() {
+            return None;
+        }
+        Vec::<String>::try_convert(backtrace.inner()).ok()
+    }
+
+    /// Get the cause of the carried Ruby exception.
+    ///
+    /// The cause is the exception that was being handled when this one was
+    /// raised (Ruby's `Exception#cause`). Returns `None` if there is no cause,
+    /// or if this error was created from Rust.
+    pub fn cause(&self) -> Option<Error> {
+        let exception = self.exception()?;
+        let cause = exception.funcall_boxed("cause", ()).ok()?;
+        if cause.is_nil() {
+            return None;
+        }
+        Some(Error::from_exception(cause.inner()))
+    }
+
+    /// Check if this error is an instance of `class` or one of its subclasses.
+    ///
+    /// Works both for exceptions caught from Ruby and for errors created from
+    /// Rust, where the error's exception class is compared. Returns `false`
+    /// if `class` is not a class or module.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn is_missing_key(error: &Error) -> bool {
+    ///     // KeyError is a subclass of IndexError
+    ///     error.is_a(&ExceptionClass::IndexError.as_value())
+    /// }
+    /// ```
+    pub fn is_a<T: ReprValue>(&self, class: &T) -> bool {
+        let class = class.as_raw();
+        let result = match &self.class {
+            ErrorClass::Exception(exception) => {
+                let exception = exception.as_raw();
+                // SAFETY: exception and class are valid VALUEs; this raises
+                // TypeError if class is not a class or module
+                protect(|| unsafe { rb_sys::rb_obj_is_kind_of(exception, class) })
+            }
+            _ => {
+                let own_class = self.class.as_value().as_raw();
+                // SAFETY: own_class and class are valid VALUEs; this raises
+                // TypeError if class is not a class or module
+                protect(|| unsafe { rb_sys::rb_class_inherited_p(own_class, class) })
+            }
+        };
+        result.is_ok_and(rb_sys::TEST)
+    }
+
     /// Get the exception class as a VALUE.
     ///
     /// # Safety
@@ -184,7 +339,13 @@ impl Error {
     /// Raise this error as a Ruby exception (diverges).
     ///
     /// This function never returns - it raises a Ruby exception using
-    /// `rb_raise` which performs a longjmp.
+    /// `rb_exc_raise` which performs a longjmp. The error is dropped before
+    /// the raise, so its message is not leaked. Errors caught from Ruby
+    /// re-raise the original exception object (or resume the original jump).
+    ///
+    /// Any other Rust values still live in the calling frames are *not*
+    /// dropped. Method wrappers should use [`into_raise`](Self::into_raise)
+    /// and raise once their own state has gone out of scope.
     ///
     /// # Safety
     ///
@@ -192,16 +353,26 @@ impl Error {
     /// be raised (i.e., during a Ruby method call). Raising outside of
     /// Ruby context will crash the process.
     pub fn raise(self) -> ! {
-        let c_message = CString::new(self.message.as_str())
-            .unwrap_or_else(|_| CString::new("error message contained null byte").unwrap());
+        let pending = self.into_raise();
+        // SAFETY: into_raise consumed the error, so nothing is left to drop
+        unsafe { pending.perform() }
+    }
 
-        // SAFETY: rb_raise never returns, it longjmps to Ruby's exception handler
-        unsafe {
-            rb_sys::rb_raise(
-                self.class.as_value().as_raw(),
-                c_str!("%s"),
-                c_message.as_ptr(),
-            );
+    /// Convert this error into a raise that owns no Rust resources.
+    ///
+    /// The message, any boxed exception and the error itself are all dropped
+    /// before this returns. The resulting [`PendingRaise`] can then be carried
+    /// out of the frame that produced the error and performed from one with
+    /// no destructors left to run.
+    pub fn into_raise(self) -> PendingRaise {
+        match &self.class {
+            // The raw VALUE stays on the stack, so the exception remains
+            // visible to the GC after the box is unregistered
+            ErrorClass::Exception(exception) => PendingRaise::Exception(exception.as_raw()),
+            ErrorClass::Jump(tag) => PendingRaise::Jump(*tag),
+            ErrorClass::BuiltIn(_) | ErrorClass::Custom(_) => {
+                PendingRaise::Exception(self.to_exception().as_raw())
+            }
         }
     }
 
@@ -211,19 +382,56 @@ impl Error {
     /// creates the exception object. This is useful for storing
     /// exceptions to raise later.
     pub fn to_exception(&self) -> Value {
-        let c_message = CString::new(self.message.as_str())
-            .unwrap_or_else(|_| CString::new("error message contained null byte").unwrap());
+        if let ErrorClass::Exception(exception) = &self.class {
+            return exception.inner();
+        }
 
-        // SAFETY: rb_exc_new creates a new exception object
+        // SAFETY: rb_exc_new copies exactly `len` bytes of the message, so
+        // interior null bytes are preserved
         unsafe {
-            Value::from_raw(rb_sys::rb_exc_new_cstr(
+            Value::from_raw(rb_sys::rb_exc_new(
                 self.class.as_value().as_raw(),
-                c_message.as_ptr(),
+                self.message.as_ptr() as *const c_char,
+                self.message.len() as _,
             ))
         }
     }
 }
 
+/// A Ruby non-local exit that is ready to be performed.
+///
+/// Created by [`Error::into_raise`]. It is `Copy` and holds only raw Ruby
+/// state, so performing it from a frame with no live Rust values leaks
+/// nothing when Ruby longjmps past that frame. The generated method
+/// wrappers use this to raise only after the `Context`, the converted
+/// arguments and the error itself have been dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingRaise {
+    /// Raise this exception object.
+    Exception(rb_sys::VALUE),
+    /// Resume a non-local jump (`throw`, `break`, ...) caught by [`protect`].
+    Jump(c_int),
+}
+
+impl PendingRaise {
+    /// Perform the raise or jump.
+    ///
+    /// # Safety
+    ///
+    /// Ruby longjmps out of this call without unwinding, so the caller must
+    /// not have any values with destructors live in its frame (or in any
+    /// Rust frame between it and Ruby). A `Jump` must come from [`protect`]
+    /// in the current Ruby method call.
+    pub unsafe fn perform(self) -> ! {
+        match self {
+            // SAFETY: the caller guarantees there is nothing left to drop
+            PendingRaise::Exception(exception) => unsafe { rb_sys::rb_exc_raise(exception) },
+            // SAFETY: the tag came from rb_protect and the jump state is still intact
+            PendingRaise::Jump(tag) => unsafe { rb_sys::rb_jump_tag(tag) },
+        }
+    }
+}
+
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Error")
@@ -259,13 +467,125 @@ impl From<AllocationError> for Error {
     }
 }
 
+/// Get the message of an exception object, without letting a failing
+/// `to_s` escape.
+fn exception_message(exception: &Value) -> String {
+    let mut state: c_int = 0;
+    // SAFETY: rb_obj_as_string has the callback signature rb_protect expects
+    // and exception is a valid VALUE
+    let raw = unsafe {
+        rb_sys::rb_protect(
+            Some(rb_sys::rb_obj_as_string),
+            exception.as_raw(),
+            &mut state,
+        )
+    };
+    if state != 0 {
+        // SAFETY: clear the exception raised by to_s, we fall back to the class name
+        unsafe { rb_sys::rb_set_errinfo(rb_sys::Qnil.into()) };
+        return exception
+            .class_name()
+            .unwrap_or_else(|_| "unknown exception".to_string());
+    }
+
+    // SAFETY: rb_obj_as_string always returns a String, and we copy the bytes
+    // before anything else can run
+    unsafe {
+        let ptr = rb_sys::RSTRING_PTR(raw) as *const u8;
+        let len = rb_sys::RSTRING_LEN(raw) as usize;
+        String::from_utf8_lossy(std::slice::from_raw_parts(ptr, len)).into_owned()
+    }
+}
+
+/// Call a closure, converting any Ruby exception it raises into an [`Error`].
+///
+/// Most Ruby C API functions report errors by raising an exception, which
+/// performs a `longjmp` straight through any Rust frames in between, skipping
+/// their destructors. `protect` runs the closure under `rb_protect` instead, so
+/// a raised exception becomes `Err(Error)` carrying the original exception
+/// object (see [`Error::exception`]). Returning that error from a method
+/// re-raises the exception unchanged.
+///
+/// Non-exception jumps such as `throw` or `break` are captured too, and are
+/// resumed when the error is raised.
+///
+/// The closure itself should stay small - ideally a single call into Ruby.
+/// If it raises, anything it owns is not dropped.
+///
+/// Panics inside the closure are propagated to the caller.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// fn call_to_s(value: &Value) -> Result<Value, Error> {
+///     let raw = protect(|| {
+///         // SAFETY: value is a valid VALUE; rb_obj_as_string may raise
+///         unsafe { rb_sys::rb_obj_as_string(value.as_raw()) }
+///     })?;
+///     // SAFETY: rb_obj_as_string returns a valid VALUE
+///     Ok(unsafe { Value::from_raw(raw) })
+/// }
+/// ```
+pub fn protect<F, T>(func: F) -> Result<T, Error>
+where
+    F: FnOnce() -> T,
+{
+    /// State shared between `protect` and the `rb_protect` callback.
+    struct Call<F, T> {
+        func: Option<F>,
+        result: Option<std::thread::Result<T>>,
+    }
+
+    unsafe extern "C" fn trampoline<F, T>(arg: rb_sys::VALUE) -> rb_sys::VALUE
+    where
+        F: FnOnce() -> T,
+    {
+        // SAFETY: arg is the pointer to the Call passed to rb_protect below
+        let call = unsafe { &mut *(arg as *mut Call<F, T>) };
+        if let Some(func) = call.func.take() {
+            // Panics must not unwind into Ruby's C frames
+            call.result = Some(panic::catch_unwind(AssertUnwindSafe(func)));
+        }
+        rb_sys::Qnil.into()
+    }
+
+    let mut call = Call {
+        func: Some(func),
+        result: None,
+    };
+    let mut state: c_int = 0;
+
+    // SAFETY: trampoline matches the callback signature and the pointer to
+    // call is valid for the duration of rb_protect
+    unsafe {
+        rb_sys::rb_protect(
+            Some(trampoline::<F, T>),
+            &mut call as *mut Call<F, T> as rb_sys::VALUE,
+            &mut state,
+        );
+    }
+
+    if state != 0 {
+        // SAFETY: state is the non-zero state rb_protect just returned
+        return Err(unsafe { Error::from_protect_state(state) });
+    }
+
+    match call.result {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(panic)) => panic::resume_unwind(panic),
+        None => unreachable!("protect callback was not called"),
+    }
+}
+
 // Helper macro for C format strings
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
 macro_rules! c_str {
     ($s:literal) => {
         concat!($s, "\0").as_ptr() as *const std::os::raw::c_char
     };
 }
-use c_str;
 
 #[cfg(test)]
 mod tests {
@@ -339,3 +659,136 @@ mod tests {
         let _ = ExceptionClass::ArgumentError;
     }
 }
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod ruby_tests {
+    use super::*;
+    use rb_sys_test_helpers::ruby_test;
+
+    #[ruby_test]
+    fn test_protect_ok() {
+        let result = protect(|| 42);
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[ruby_test]
+    fn test_protect_catches_exception() {
+        let result = protect(|| unsafe {
+            rb_sys::rb_raise(rb_sys::rb_eArgError, c_str!("%s"), c_str!("bad argument"));
+        });
+        let err = result.unwrap_err();
+        assert_eq!(err.message(), "bad argument");
+        assert!(err.exception().is_some());
+
+        // The pending exception is cleared
+        assert!(unsafe { Value::from_raw(rb_sys::rb_errinfo()) }.is_nil());
+    }
+
+    #[ruby_test]
+    fn test_protect_exception_class() {
+        let err = protect(|| unsafe {
+            rb_sys::rb_raise(rb_sys::rb_eTypeError, c_str!("%s"), c_str!("oops"));
+        })
+        .unwrap_err();
+        assert_eq!(err.exception_class(), ExceptionClass::TypeError.as_value());
+    }
+
+    #[ruby_test]
+    fn test_protect_propagates_panic() {
+        let result = std::panic::catch_unwind(|| protect(|| panic!("inside protect")));
+        assert!(result.is_err());
+    }
+
+    #[ruby_test]
+    fn test_error_without_exception() {
+        let err = Error::runtime("plain");
+        assert!(err.exception().is_none());
+        assert!(err.backtrace().is_none());
+        assert!(err.cause().is_none());
+    }
+
+    #[ruby_test]
+    fn test_from_exception() {
+        let exception = Error::type_error("wrapped").to_exception();
+        let err = Error::from_exception(exception.clone());
+        assert_eq!(err.message(), "wrapped");
+        assert_eq!(err.exception(), Some(&exception));
+        assert_eq!(err.to_exception(), exception);
+        assert_eq!(err.exception_class(), ExceptionClass::TypeError.as_value());
+    }
+
+    #[ruby_test]
+    fn test_is_a() {
+        let err = Error::new(ExceptionClass::KeyError, "missing");
+        assert!(err.is_a(&ExceptionClass::KeyError.as_value()));
+        assert!(err.is_a(&ExceptionClass::IndexError.as_value()));
+        assert!(err.is_a(&ExceptionClass::StandardError.as_value()));
+        assert!(!err.is_a(&ExceptionClass::TypeError.as_value()));
+
+        let caught = Error::from_exception(err.to_exception());
+        assert!(caught.is_a(&ExceptionClass::IndexError.as_value()));
+        assert!(!caught.is_a(&ExceptionClass::TypeError.as_value()));
+
+        // Anything other than a class or module is never a match
+        let name = crate::types::RString::new_boxed("KeyError");
+        assert!(!err.is_a(&Value::nil()));
+        assert!(!caught.is_a(&*name));
+    }
+
+    /// Evaluate Ruby code through `Kernel#eval`, returning the raised error.
+    fn eval_err(code: &str) -> Error {
+        Value::nil().funcall_boxed("eval", (code,)).unwrap_err()
+    }
+
+    #[ruby_test]
+    fn test_backtrace_of_raised_exception() {
+        let err = eval_err("raise 'boom'");
+        assert_eq!(err.message(), "boom");
+        let backtrace = err.backtrace().unwrap();
+        assert!(!backtrace.is_empty());
+    }
+
+    #[ruby_test]
+    fn test_backtrace_of_unraised_exception() {
+        let err = Error::from_exception(Error::runtime("never raised").to_exception());
+        assert!(err.backtrace().is_none());
+    }
+
+    #[ruby_test]
+    fn test_cause() {
+        let err = eval_err("begin; raise 'inner'; rescue; raise ArgumentError, 'outer'; end");
+        assert_eq!(err.message(), "outer");
+        assert!(err.is_a(&ExceptionClass::ArgumentError.as_value()));
+
+        let cause = err.cause().unwrap();
+        assert_eq!(cause.message(), "inner");
+        assert!(cause.is_a(&ExceptionClass::RuntimeError.as_value()));
+        assert!(cause.cause().is_none());
+    }
+
+    #[ruby_test]
+    fn test_into_raise_builds_exception() {
+        let pending = Error::argument("bad\0value").into_raise();
+        let PendingRaise::Exception(raw) = pending else {
+            panic!("expected an exception");
+        };
+        let err = Error::from_exception(unsafe { Value::from_raw(raw) });
+        assert!(err.is_a(&ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(err.message(), "bad\0value");
+    }
+
+    #[ruby_test]
+    fn test_into_raise_keeps_original_exception() {
+        let err = eval_err("raise 'boom'");
+        let raw = err.exception().unwrap().as_raw();
+        assert_eq!(err.into_raise(), PendingRaise::Exception(raw));
+    }
+
+    #[ruby_test]
+    fn test_perform_raises_from_protect() {
+        let pending = Error::type_error("performed").into_raise();
+        let err = protect(|| unsafe { pending.perform() }).unwrap_err();
+        assert!(err.is_a(&ExceptionClass::TypeError.as_value()));
+        assert_eq!(err.message(), "performed");
+    }
+}
@@ -0,0 +1,319 @@
+//! Releasing the Global VM Lock (GVL).
+//!
+//! Ruby only runs one thread at a time: a thread must hold the GVL to run Ruby
+//! code or touch Ruby objects. Long-running Rust code that doesn't need Ruby
+//! (parsing, hashing, compression, blocking I/O) can release the lock with
+//! [`without_gvl`] so other Ruby threads keep running in the meantime, and
+//! briefly take it back with [`with_gvl`] if it needs to call into Ruby.
+//!
+//! # Compile-time safety
+//!
+//! The closure passed to [`without_gvl`] must be `Send`. Every Ruby value
+//! type, including [`Value`](crate::Value), [`StackPinned<T>`](crate::StackPinned),
+//! [`BoxValue<T>`](crate::BoxValue) and the `Pin<&StackPinned<T>>` handed out
+//! by a [`Context`], is `!Send` and `!Sync`, and so is `&Context`. So the
+//! closure can capture neither Ruby values nor the Context, and pinned values
+//! can't be touched while the lock is released:
+//!
+//! ```compile_fail
+//! use solidus::prelude::*;
+//!
+//! fn len<'ctx>(ctx: &'ctx Context, rb_self: Value) -> Result<usize, Error> {
+//!     let s = ctx.new_string("hello")?;
+//!     // error: the closure captures `s`, which is not `Send`
+//!     solidus::without_gvl(|| s.get().len(), ())
+//! }
+//! ```
+//!
+//! Copy what the closure needs into plain Rust data first:
+//!
+//! ```no_run
+//! use solidus::prelude::*;
+//!
+//! fn checksum(_ctx: &Context, rb_self: RString) -> Result<u64, Error> {
+//!     let bytes = rb_self.to_bytes();
+//!     solidus::without_gvl(
+//!         move || bytes.iter().fold(0u64, |sum, b| sum.wrapping_mul(31).wrapping_add(*b as u64)),
+//!         (),
+//!     )
+//! }
+//! ```
+
+use std::ffi::c_void;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::ptr;
+
+use crate::context::Context;
+use crate::error::{Error, protect};
+
+/// A function that interrupts the work done by [`without_gvl`].
+///
+/// Ruby calls it, from another thread, when the thread running the work is
+/// interrupted (`Thread#kill`, `Thread#raise`, Ctrl-C, or the process
+/// exiting). It should make the work return early, for example by setting an
+/// `AtomicBool` the work checks.
+///
+/// Implemented for `()`, meaning the work can't be interrupted, and for any
+/// `Fn() + Sync` closure.
+pub trait Unblock: Sync {
+    /// Whether there is anything to call.
+    #[doc(hidden)]
+    const PRESENT: bool;
+
+    /// Ask the work to stop.
+    fn unblock(&self);
+}
+
+impl Unblock for () {
+    const PRESENT: bool = false;
+
+    #[inline]
+    fn unblock(&self) {}
+}
+
+impl<F: Fn() + Sync> Unblock for F {
+    const PRESENT: bool = true;
+
+    #[inline]
+    fn unblock(&self) {
+        self()
+    }
+}
+
+/// Run `func` with the GVL released, so other Ruby threads can run meanwhile.
+///
+/// `func` runs on the current thread. It must not touch any Ruby object,
+/// which the `Send` bound enforces (see the [module docs](self)); use
+/// [`with_gvl`] if it needs to call back into Ruby.
+///
+/// `unblock` is called if the thread is interrupted while `func` runs; pass
+/// `()` if `func` can't be interrupted. A panic in `func` is resumed once the
+/// GVL has been taken back.
+///
+/// # Errors
+///
+/// Returns `Err` if an interrupt is pending, such as `Thread#raise` from
+/// another thread or Ctrl-C. The interrupt may arrive before `func` runs (in
+/// which case it isn't called) or after it finished (its result is dropped).
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// let cancelled = AtomicBool::new(false);
+/// let steps = solidus::without_gvl(
+///     || {
+///         let mut steps = 0u64;
+///         while steps < 1_000_000_000 && !cancelled.load(Ordering::Relaxed) {
+///             steps += 1;
+///         }
+///         steps
+///     },
+///     || cancelled.store(true, Ordering::Relaxed),
+/// );
+/// ```
+pub fn without_gvl<F, R, U>(func: F, unblock: U) -> Result<R, Error>
+where
+    F: FnOnce() -> R + Send,
+    U: Unblock,
+{
+    let mut call = Call::<F, R> {
+        func: Some(func),
+        result: None,
+    };
+    let data = &mut call as *mut Call<F, R> as *mut c_void;
+
+    let (ubf, ubf_data) = if U::PRESENT {
+        let ubf: unsafe extern "C" fn(*mut c_void) = call_unblock::<U>;
+        (Some(ubf), &unblock as *const U as *mut c_void)
+    } else {
+        (None, ptr::null_mut())
+    };
+
+    // Ruby checks for interrupts before releasing and after re-acquiring the
+    // lock, and raises if there is one, so the call must be protected.
+    // SAFETY: call and unblock outlive the call, and call_func/call_unblock
+    // are instantiated for their types
+    protect(|| unsafe {
+        rb_sys::rb_thread_call_without_gvl(Some(call_func::<F, R>), data, ubf, ubf_data);
+    })?;
+
+    match call.result {
+        Some(Ok(result)) => Ok(result),
+        Some(Err(panic)) => resume_unwind(panic),
+        None => unreachable!("rb_thread_call_without_gvl returned without calling the function"),
+    }
+}
+
+/// Run `func` with the GVL held, from inside [`without_gvl`].
+///
+/// `func` gets a fresh [`Context`] to create Ruby values with. Its result
+/// must be `Send`, so no Ruby value can leak back out into code that runs
+/// without the lock.
+///
+/// # Errors
+///
+/// Returns `Err` if `func` returns `Err`, or a Ruby exception escapes it.
+/// The error keeps the exception's class and message, but not the exception
+/// object itself, since that can't be kept alive once the lock is released
+/// again.
+///
+/// # Panics
+///
+/// A panic in `func` is resumed after the lock is released again. Calling
+/// `with_gvl` while already holding the GVL, or from a thread Ruby didn't
+/// create, makes Ruby abort the process.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// let lines = solidus::without_gvl(
+///     || {
+///         // ...expensive work...
+///         solidus::with_gvl(|ctx| {
+///             let progress = ctx.new_string("halfway")?;
+///             Value::nil().funcall_boxed("puts", (progress.get().clone(),))?;
+///             Ok(())
+///         })
+///     },
+///     (),
+/// );
+/// ```
+pub fn with_gvl<F, R>(func: F) -> Result<R, Error>
+where
+    F: FnOnce(&Context) -> Result<R, Error>,
+    R: Send,
+{
+    let mut call = Call::<_, Result<R, Error>> {
+        func: Some(move || {
+            let ctx: Context<'_> = Context::default();
+            match protect(|| func(&ctx)) {
+                Ok(Ok(result)) => Ok(result),
+                Ok(Err(error)) | Err(error) => Err(detach(error)),
+            }
+        }),
+        result: None,
+    };
+    let func = call_func_for(&call);
+    let data = &mut call as *mut Call<_, Result<R, Error>> as *mut c_void;
+
+    // SAFETY: call outlives the call and func is instantiated for its type
+    unsafe { rb_sys::rb_thread_call_with_gvl(Some(func), data) };
+
+    match call.result {
+        Some(Ok(result)) => result,
+        Some(Err(panic)) => resume_unwind(panic),
+        None => unreachable!("rb_thread_call_with_gvl returned without calling the function"),
+    }
+}
+
+/// Replace the exception object in `error` by its class and message.
+fn detach(error: Error) -> Error {
+    match error.exception() {
+        Some(_) => Error::with_class(error.exception_class(), error.message()),
+        None => error,
+    }
+}
+
+/// A closure passed through Ruby's `void *` callback argument.
+struct Call<F, R> {
+    func: Option<F>,
+    result: Option<std::thread::Result<R>>,
+}
+
+/// Pick [`call_func`] for the type of `call`, which may hold a closure.
+fn call_func_for<F: FnOnce() -> R, R>(
+    _call: &Call<F, R>,
+) -> unsafe extern "C" fn(*mut c_void) -> *mut c_void {
+    call_func::<F, R>
+}
+
+/// Run the closure stored in a [`Call`], catching any panic.
+unsafe extern "C" fn call_func<F: FnOnce() -> R, R>(data: *mut c_void) -> *mut c_void {
+    // SAFETY: data is the Call<F, R> passed to rb_thread_call_*_gvl
+    let call = unsafe { &mut *(data as *mut Call<F, R>) };
+    if let Some(func) = call.func.take() {
+        call.result = Some(catch_unwind(AssertUnwindSafe(func)));
+    }
+    ptr::null_mut()
+}
+
+/// Call an [`Unblock`] function, which must not unwind into Ruby.
+unsafe extern "C" fn call_unblock<U: Unblock>(data: *mut c_void) {
+    // SAFETY: data is the &U passed to rb_thread_call_without_gvl, which
+    // outlives the call
+    let unblock = unsafe { &*(data as *const U) };
+    if catch_unwind(AssertUnwindSafe(|| unblock.unblock())).is_err() {
+        // There is no way to report the panic from here
+        std::process::abort();
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use crate::value::Value;
+    use rb_sys_test_helpers::ruby_test;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[ruby_test]
+    fn test_without_gvl() {
+        let numbers: Vec<u64> = (1..=100).collect();
+        let sum = without_gvl(move || numbers.iter().sum::<u64>(), ()).unwrap();
+        assert_eq!(sum, 5050);
+    }
+
+    #[ruby_test]
+    fn test_without_gvl_with_unblock() {
+        let cancelled = AtomicBool::new(false);
+        let result = without_gvl(
+            || cancelled.load(Ordering::Relaxed),
+            || cancelled.store(true, Ordering::Relaxed),
+        )
+        .unwrap();
+        assert!(!result);
+    }
+
+    #[ruby_test]
+    fn test_without_gvl_panic_is_resumed() {
+        let result = catch_unwind(|| without_gvl(|| panic!("no lock"), ()));
+        assert!(result.is_err());
+    }
+
+    #[ruby_test]
+    fn test_with_gvl() {
+        let length = without_gvl(
+            || {
+                with_gvl(|ctx| {
+                    let s = ctx.new_string("hello")?;
+                    Ok(s.get().len())
+                })
+            },
+            (),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(length, 5);
+    }
+
+    #[ruby_test]
+    fn test_with_gvl_error_is_detached() {
+        let err = without_gvl(
+            || {
+                with_gvl(|_ctx| {
+                    Value::nil().funcall_boxed("eval", ("raise ArgumentError, 'inside'",))?;
+                    Ok(())
+                })
+            },
+            (),
+        )
+        .unwrap()
+        .unwrap_err();
+        assert!(err.exception().is_none());
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(err.message(), "inside");
+    }
+}
@@ -68,7 +68,7 @@
 //! fn init(ruby: &Ruby) -> Result<(), Error> {
 //!     let class_val = ruby.define_class("MyString", ruby.class_object());
 //!     let class = RClass::try_convert(class_val)?;
-//!     class.define_method("concat", method!(concat, 1), 1)?;
+//!     class.define_method("concat", method!(concat, 1))?;
 //!     Ok(())
 //! }
 //! ```
@@ -92,30 +92,35 @@ pub use rb_sys;
 // Users should use:
 //   - solidus::method!(func, arity) for the declarative macro
 //   - #[solidus_macros::method] for the attribute macro
-// The #[init] and #[wrap] attribute macros don't conflict, so they're re-exported.
-pub use solidus_macros::{init, wrap};
+// The #[init], #[methods] and #[wrap] attribute macros don't conflict, so they're re-exported.
+pub use solidus_macros::{init, methods, wrap};
 
 // Modules
 pub mod context;
 pub mod convert;
 pub mod error;
 pub mod gc;
+pub mod gvl;
 pub mod method;
 pub mod ruby;
 pub mod typed_data;
 pub mod types;
 pub mod value;
 
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod test_util;
+
 // Re-exports for convenience
 pub use context::Context;
-pub use error::{AllocationError, Error, ExceptionClass};
+pub use error::{AllocationError, Error, ExceptionClass, PendingRaise, protect};
+pub use gvl::{with_gvl, without_gvl};
 pub use ruby::Ruby;
 pub use value::{BoxValue, NewValue, ReprValue, StackPinned, Value, ValueType};
 
 // Re-export all types
 pub use types::{
     Encoding, Fixnum, Float, Integer, Module, Qfalse, Qnil, Qtrue, RArray, RBignum, RClass, RFloat,
-    RHash, RModule, RString, Symbol,
+    RHash, RMethod, RModule, RProc, RString, Symbol,
 };
 
 #[cfg(target_pointer_width = "64")]
@@ -128,18 +133,19 @@ pub mod prelude {
     pub use std::pin::Pin;
 
     pub use crate::context::Context;
-    pub use crate::convert::{IntoValue, TryConvert};
-    pub use crate::error::{AllocationError, Error, ExceptionClass};
+    pub use crate::convert::{ArgList, IntoValue, TryConvert};
+    pub use crate::error::{AllocationError, Error, ExceptionClass, protect};
     pub use crate::init;
-    pub use crate::method::{ReturnWitness, WitnessedReturn};
+    pub use crate::method::{Block, ReturnWitness, WitnessedReturn, scan_args};
     pub use crate::pin_on_stack;
     pub use crate::ruby::Ruby;
     pub use crate::typed_data::{
-        Compactor, DataType, DataTypeFunctions, Marker, TypedData, get, get_mut, wrap,
+        Compactor, DataType, DataTypeFunctions, Marker, TypedData, TypedRef, TypedRefMut, get,
+        get_mut, wrap,
     };
     pub use crate::types::{
         Encoding, Fixnum, Float, Integer, Module, Qfalse, Qnil, Qtrue, RArray, RBignum, RClass,
-        RFloat, RHash, RModule, RString, Symbol,
+        RFloat, RHash, RMethod, RModule, RProc, RString, Symbol,
     };
     pub use crate::value::{BoxValue, NewValue, ReprValue, StackPinned, Value, ValueType};
 
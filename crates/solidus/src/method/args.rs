@@ -12,8 +12,8 @@
 //!   find them on the stack.
 
 use crate::types::{
-    Fixnum, Float, Integer, Qfalse, Qnil, Qtrue, RArray, RBignum, RClass, RFloat, RHash, RModule,
-    RString, Symbol,
+    Fixnum, Float, Integer, Qfalse, Qnil, Qtrue, RArray, RBignum, RClass, RFloat, RHash, RMethod,
+    RModule, RProc, RString, Symbol,
 };
 use crate::value::Value;
 
@@ -135,6 +135,12 @@ impl MethodArg for RClass {
 impl MethodArg for RModule {
     const NEEDS_PINNING: bool = true;
 }
+impl MethodArg for RProc {
+    const NEEDS_PINNING: bool = true;
+}
+impl MethodArg for RMethod {
+    const NEEDS_PINNING: bool = true;
+}
 impl MethodArg for Value {
     const NEEDS_PINNING: bool = true;
 }
@@ -0,0 +1,251 @@
+//! Access to the block passed to a Ruby method.
+
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+use std::pin::Pin;
+
+use crate::context::Context;
+use crate::convert::{ArgList, IntoValue};
+use crate::error::{Error, protect};
+use crate::types::RProc;
+use crate::value::{BoxValue, ReprValue, StackPinned, Value};
+
+/// The block passed to the currently running Ruby method.
+///
+/// Obtain it with [`Context::block()`] in functions wrapped by `method!` or
+/// `function!`, or by declaring a `Block` parameter in a `#[solidus::method]`
+/// or `#[solidus::function]` (it doesn't count towards the arity).
+///
+/// A `Block` only refers to the method call it was created in, so it cannot
+/// be sent to another thread or outlive the call.
+///
+/// # Errors
+///
+/// Yielding when no block was given raises `LocalJumpError`, which is
+/// returned as `Err`. A `break` or `next` in the block, and any exception it
+/// raises, also come back as `Err`; returning that error from the method
+/// lets Ruby continue the jump or raise as usual.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// // def each_twice(value) = 2.times { yield value }
+/// fn each_twice<'ctx>(
+///     ctx: &'ctx Context,
+///     _rb_self: Value,
+///     value: Pin<&StackPinned<Value>>,
+/// ) -> Result<bool, Error> {
+///     let block = ctx.block();
+///     if !block.is_given() {
+///         return Ok(false);
+///     }
+///     block.yield_value_boxed(value.get().clone())?;
+///     block.yield_value_boxed(value.get().clone())?;
+///     Ok(true)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Block<'a> {
+    /// Ties the block to the method call and makes it !Send/!Sync.
+    _marker: PhantomData<(&'a (), *const ())>,
+}
+
+impl Block<'_> {
+    /// Refer to the block of the current method call.
+    ///
+    /// This is `#[doc(hidden)]` because only the method wrappers and
+    /// [`Context::block()`] should create blocks.
+    #[doc(hidden)]
+    #[inline]
+    pub fn new() -> Self {
+        Block {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Check whether the method was called with a block (`block_given?`).
+    pub fn is_given(&self) -> bool {
+        // SAFETY: rb_block_given_p only inspects the current method frame
+        unsafe { rb_sys::rb_block_given_p() != 0 }
+    }
+
+    /// Yield a single value to the block, storing the result in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no block was given, if the block raises or breaks,
+    /// or if the Context has no free slots to hold the result.
+    pub fn yield_value<'ctx, T: IntoValue, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        value: T,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_yield((value,))?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Yield a single value to the block, returning a heap-allocated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no block was given, or if the block raises or breaks.
+    pub fn yield_value_boxed<T: IntoValue>(&self, value: T) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_yield((value,))?))
+    }
+
+    /// Yield several values to the block, storing the result in the Context.
+    ///
+    /// `args` is any [`ArgList`], so `block.yield_values(ctx, (key, value))`
+    /// behaves like `yield key, value` in Ruby.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no block was given, if the block raises or breaks,
+    /// or if the Context has no free slots to hold the result.
+    pub fn yield_values<'ctx, A: ArgList, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        args: A,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_yield(args)?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Yield several values to the block, returning a heap-allocated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no block was given, or if the block raises or breaks.
+    pub fn yield_values_boxed<A: ArgList>(&self, args: A) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_yield(args)?))
+    }
+
+    /// Turn the block into a proc, as `&block` does in a Ruby signature.
+    ///
+    /// Returns `None` if no block was given. The proc can outlive the method
+    /// call, e.g. to store a callback.
+    pub fn to_proc_boxed(&self) -> Option<BoxValue<RProc>> {
+        if !self.is_given() {
+            return None;
+        }
+        // SAFETY: a block was given, so rb_block_proc returns it as a valid Proc
+        let proc = unsafe { RProc::from_value_unchecked(Value::from_raw(rb_sys::rb_block_proc())) };
+        Some(BoxValue::new(proc))
+    }
+
+    /// Yield to the block, catching any exception or jump.
+    fn call_yield<A: ArgList>(&self, args: A) -> Result<Value, Error> {
+        // The converted arguments live in a stack array for the duration of
+        // the call, so Ruby's GC can see them.
+        let args = args.into_arg_list();
+        let args = args.as_ref();
+        // Value is #[repr(transparent)] over VALUE, so the slice can be passed as argv
+        let argv = args.as_ptr() as *const rb_sys::VALUE;
+        let argc = args.len() as c_int;
+
+        // SAFETY: argv points to argc valid VALUEs
+        let raw = protect(|| unsafe { rb_sys::rb_yield_values2(argc, argv) })?;
+
+        // SAFETY: the block returned a valid VALUE
+        Ok(unsafe { Value::from_raw(raw) })
+    }
+}
+
+impl Default for Block<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use crate::convert::TryConvert;
+    use crate::test_util::eval;
+    use crate::types::{Module, RClass};
+    use rb_sys_test_helpers::ruby_test;
+
+    fn yield_sum(ctx: &Context, _rb_self: Value) -> Result<i64, Error> {
+        let block = ctx.block();
+        if !block.is_given() {
+            return Ok(-1);
+        }
+        let first = block.yield_values(ctx, (1i64, 2i64))?;
+        let first = i64::try_convert(first.get().clone())?;
+        let second = block.yield_value_boxed(10i64)?;
+        Ok(first + i64::try_convert(second.inner())?)
+    }
+
+    fn define_yield_sum() {
+        let ruby = unsafe { crate::Ruby::get() };
+        let class = ruby.define_class("TestBlockYield", ruby.class_object());
+        RClass::try_convert(class)
+            .unwrap()
+            .define_method("yield_sum", crate::method!(yield_sum, 0))
+            .unwrap();
+    }
+
+    #[ruby_test]
+    fn test_yield() {
+        define_yield_sum();
+        let result = eval("TestBlockYield.new.yield_sum { |a, b = 0| a + b }").unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 13);
+    }
+
+    #[ruby_test]
+    fn test_no_block_given() {
+        define_yield_sum();
+        let result = eval("TestBlockYield.new.yield_sum").unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), -1);
+    }
+
+    #[ruby_test]
+    fn test_yield_without_block_is_error() {
+        let block = Block::new();
+        assert!(!block.is_given());
+        assert!(block.yield_value_boxed(1i64).is_err());
+    }
+
+    fn store_block(ctx: &Context, _rb_self: Value) -> Result<Value, Error> {
+        match ctx.block().to_proc_boxed() {
+            Some(proc) => Ok(proc.as_value()),
+            None => Ok(Value::nil()),
+        }
+    }
+
+    #[ruby_test]
+    fn test_block_to_proc() {
+        let ruby = unsafe { crate::Ruby::get() };
+        let class = ruby.define_class("TestBlockToProc", ruby.class_object());
+        RClass::try_convert(class)
+            .unwrap()
+            .define_method("store_block", crate::method!(store_block, 0))
+            .unwrap();
+
+        let proc = eval("TestBlockToProc.new.store_block { |a| a * 2 }").unwrap();
+        let proc = RProc::try_convert(proc.inner()).unwrap();
+        let result = proc.call_boxed((21i64,)).unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 42);
+
+        let none = eval("TestBlockToProc.new.store_block").unwrap();
+        assert!(none.is_nil());
+    }
+
+    #[ruby_test]
+    fn test_block_exception_propagates() {
+        define_yield_sum();
+        let err = eval("TestBlockYield.new.yield_sum { |a| raise ArgumentError, 'from block' }")
+            .unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(err.message(), "from block");
+    }
+
+    #[ruby_test]
+    fn test_block_break() {
+        define_yield_sum();
+        let result = eval("TestBlockYield.new.yield_sum { |a| break 99 }").unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 99);
+    }
+}
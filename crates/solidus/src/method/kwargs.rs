@@ -0,0 +1,330 @@
+//! Keyword argument extraction.
+//!
+//! Ruby passes keyword arguments to C methods as a trailing `Hash` with
+//! `Symbol` keys. [`split_kwargs`] separates that hash from the positional
+//! arguments of a variadic method, and [`get_kwargs`] pulls the expected
+//! keywords out of it as typed Rust values, rejecting missing and unknown
+//! keywords with the same `ArgumentError` messages Ruby uses.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use solidus::prelude::*;
+//! use solidus::method::{KwArgs, get_kwargs, split_kwargs};
+//!
+//! // Ruby signature: def connect(host:, port: 80)
+//! fn connect<'ctx>(
+//!     ctx: &'ctx Context,
+//!     _rb_self: Value,
+//!     args: &[Value],
+//! ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+//!     let (args, kwargs) = split_kwargs(args);
+//!     solidus::method::check_arity(args.len(), 0, Some(0))?;
+//!
+//!     let KwArgs { required: (host,), optional: (port,) } =
+//!         get_kwargs::<(String,), (Option<i64>,)>(kwargs.as_ref(), &["host"], &["port"])?;
+//!
+//!     let url = format!("{}:{}", host, port.unwrap_or(80));
+//!     ctx.new_string(&url).map_err(Into::into)
+//! }
+//! ```
+
+use super::ScanArgsRequired;
+use crate::convert::TryConvert;
+use crate::error::{Error, protect};
+use crate::types::{RHash, RString, Symbol};
+use crate::value::{ReprValue, Value};
+
+/// Keyword arguments extracted by [`get_kwargs`].
+#[derive(Debug)]
+pub struct KwArgs<Req, Opt> {
+    /// Values of the required keywords, in the order they were named.
+    pub required: Req,
+    /// Values of the optional keywords, `None` when omitted or `nil`.
+    pub optional: Opt,
+}
+
+/// A tuple of optional keyword values.
+///
+/// Implemented for `()` and tuples of up to 8 `Option<T>` where `T` is
+/// [`TryConvert`].
+pub trait KwArgsOptional: Sized {
+    /// The number of keywords in the tuple.
+    const LEN: usize;
+
+    /// Convert exactly [`LEN`](Self::LEN) looked-up values.
+    fn from_values(values: &[Option<Value>]) -> Result<Self, Error>;
+}
+
+impl KwArgsOptional for () {
+    const LEN: usize = 0;
+
+    #[inline]
+    fn from_values(_values: &[Option<Value>]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_kwargs_optional_for_tuple {
+    ($len:literal; $($name:ident => $idx:tt),*) => {
+        impl<$($name: TryConvert),*> KwArgsOptional for ($(Option<$name>,)*) {
+            const LEN: usize = $len;
+
+            #[inline]
+            fn from_values(values: &[Option<Value>]) -> Result<Self, Error> {
+                Ok(($(
+                    match &values[$idx] {
+                        Some(value) => Some($name::try_convert(value.clone())?),
+                        None => None,
+                    },
+                )*))
+            }
+        }
+    };
+}
+
+impl_kwargs_optional_for_tuple!(1; A => 0);
+impl_kwargs_optional_for_tuple!(2; A => 0, B => 1);
+impl_kwargs_optional_for_tuple!(3; A => 0, B => 1, C => 2);
+impl_kwargs_optional_for_tuple!(4; A => 0, B => 1, C => 2, D => 3);
+impl_kwargs_optional_for_tuple!(5; A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_kwargs_optional_for_tuple!(6; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+impl_kwargs_optional_for_tuple!(7; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+impl_kwargs_optional_for_tuple!(8; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+/// Split the keyword hash off the arguments of a variadic method.
+///
+/// Returns the positional arguments and, if the caller passed keywords, the
+/// keyword hash. A trailing `Hash` passed positionally (without `**` or
+/// `key:` syntax) stays a positional argument, as in Ruby 3.
+///
+/// This must be called while the method is running, since it asks Ruby
+/// whether the current call was made with keywords.
+pub fn split_kwargs(args: &[Value]) -> (&[Value], Option<RHash>) {
+    // SAFETY: rb_keyword_given_p only inspects the current call frame
+    let keywords_given = unsafe { rb_sys::rb_keyword_given_p() } != 0;
+    if keywords_given {
+        if let Some((last, rest)) = args.split_last() {
+            if let Ok(hash) = RHash::try_convert(last.clone()) {
+                return (rest, Some(hash));
+            }
+        }
+    }
+    (args, None)
+}
+
+/// Extract typed keyword arguments from a keyword hash.
+///
+/// `required` and `optional` list the keyword names, matching the element
+/// types of `Req` and `Opt` in order. Pass `None` when the method was called
+/// without keywords.
+///
+/// An optional keyword explicitly passed as `nil` is treated as omitted.
+///
+/// # Errors
+///
+/// Returns an `ArgumentError` with Ruby's message (`missing keyword: :host`,
+/// `unknown keywords: :a, :b`) if a required keyword is missing or the hash
+/// contains keys that weren't listed, or the conversion error of the first
+/// value with the wrong type.
+///
+/// # Panics
+///
+/// Panics if the number of names doesn't match the length of `Req` or `Opt`.
+pub fn get_kwargs<Req, Opt>(
+    kwargs: Option<&RHash>,
+    required: &[&str],
+    optional: &[&str],
+) -> Result<KwArgs<Req, Opt>, Error>
+where
+    Req: ScanArgsRequired,
+    Opt: KwArgsOptional,
+{
+    assert_eq!(
+        required.len(),
+        Req::LEN,
+        "wrong number of required keyword names"
+    );
+    assert_eq!(
+        optional.len(),
+        Opt::LEN,
+        "wrong number of optional keyword names"
+    );
+
+    let lookup = |name: &str| -> Option<Value> {
+        let hash = kwargs?;
+        let key = Symbol::new(name);
+        // SAFETY: hash is a valid Hash and key a valid Symbol; Qundef marks a missing key
+        let value = unsafe {
+            Value::from_raw(rb_sys::rb_hash_lookup2(
+                hash.as_raw(),
+                key.as_raw(),
+                rb_sys::Qundef.into(),
+            ))
+        };
+        if value.is_undef() { None } else { Some(value) }
+    };
+
+    // The looked-up values stay reachable through the hash, so collecting
+    // them briefly is safe
+    let mut required_values = Vec::with_capacity(required.len());
+    let mut missing = Vec::new();
+    for name in required {
+        match lookup(name) {
+            Some(value) => required_values.push(value),
+            None => missing.push(format!(":{}", name)),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(keyword_error("missing", &missing));
+    }
+
+    let optional_values: Vec<Option<Value>> = optional
+        .iter()
+        .map(|name| lookup(name).filter(|value| !value.is_nil()))
+        .collect();
+
+    if let Some(hash) = kwargs {
+        let mut unknown = Vec::new();
+        hash.each(|key, _| {
+            let known = Symbol::try_convert(key.clone())
+                .and_then(|sym| sym.name())
+                .is_ok_and(|name| {
+                    required.contains(&name.as_str()) || optional.contains(&name.as_str())
+                });
+            if !known {
+                unknown.push(inspect(&key)?);
+            }
+            Ok(())
+        })?;
+        if !unknown.is_empty() {
+            return Err(keyword_error("unknown", &unknown));
+        }
+    }
+
+    Ok(KwArgs {
+        required: Req::from_slice(&required_values)?,
+        optional: Opt::from_values(&optional_values)?,
+    })
+}
+
+/// Build Ruby's `missing keyword`/`unknown keywords` error.
+fn keyword_error(kind: &str, keys: &[String]) -> Error {
+    let plural = if keys.len() == 1 { "" } else { "s" };
+    Error::argument(format!("{} keyword{}: {}", kind, plural, keys.join(", ")))
+}
+
+/// `key.inspect`, as Ruby shows keys in keyword errors.
+fn inspect(key: &Value) -> Result<String, Error> {
+    let raw = key.as_raw();
+    // SAFETY: raw is a valid VALUE; rb_inspect always returns a String
+    let inspected = protect(|| unsafe { Value::from_raw(rb_sys::rb_inspect(raw)) })?;
+    RString::try_convert(inspected)?.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_error_messages() {
+        let err = keyword_error("missing", &[":host".to_string()]);
+        assert_eq!(err.message(), "missing keyword: :host");
+
+        let err = keyword_error("unknown", &[":a".to_string(), ":b".to_string()]);
+        assert_eq!(err.message(), "unknown keywords: :a, :b");
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod ruby_tests {
+    use super::*;
+    use rb_sys_test_helpers::ruby_test;
+
+    fn kwargs(pairs: &[(&str, i64)]) -> crate::BoxValue<RHash> {
+        let hash = RHash::new_boxed();
+        for (key, value) in pairs {
+            hash.insert(Symbol::new(key), *value).unwrap();
+        }
+        hash
+    }
+
+    #[ruby_test]
+    fn test_get_kwargs() {
+        let hash = kwargs(&[("host", 1), ("port", 2)]);
+        let KwArgs { required, optional } = get_kwargs::<(i64,), (Option<i64>, Option<i64>)>(
+            Some(&*hash),
+            &["host"],
+            &["port", "timeout"],
+        )
+        .unwrap();
+        assert_eq!(required, (1,));
+        assert_eq!(optional, (Some(2), None));
+    }
+
+    #[ruby_test]
+    fn test_get_kwargs_without_hash() {
+        let KwArgs { optional, .. } =
+            get_kwargs::<(), (Option<i64>,)>(None, &[], &["port"]).unwrap();
+        assert_eq!(optional, (None,));
+
+        let err = get_kwargs::<(i64, i64), ()>(None, &["host", "port"], &[]).unwrap_err();
+        assert_eq!(err.message(), "missing keywords: :host, :port");
+    }
+
+    #[ruby_test]
+    fn test_get_kwargs_unknown() {
+        let hash = kwargs(&[("host", 1), ("colour", 2)]);
+        let err = get_kwargs::<(i64,), ()>(Some(&*hash), &["host"], &[]).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(err.message(), "unknown keyword: :colour");
+    }
+
+    #[ruby_test]
+    fn test_get_kwargs_wrong_type() {
+        let hash = RHash::new_boxed();
+        hash.insert(Symbol::new("host"), "not a number").unwrap();
+        assert!(get_kwargs::<(i64,), ()>(Some(&*hash), &["host"], &[]).is_err());
+    }
+
+    fn connect(_ctx: &crate::Context, _rb_self: Value, args: &[Value]) -> Result<i64, Error> {
+        let (args, kwargs) = split_kwargs(args);
+        crate::method::check_arity(args.len(), 0, Some(0))?;
+        let KwArgs {
+            required: (port,),
+            optional: (offset,),
+        } = get_kwargs::<(i64,), (Option<i64>,)>(kwargs.as_ref(), &["port"], &["offset"])?;
+        Ok(port + offset.unwrap_or(0))
+    }
+
+    #[ruby_test]
+    fn test_kwargs_from_ruby_call() {
+        use crate::Ruby;
+        use crate::types::{Module, RClass};
+
+        let ruby = unsafe { Ruby::get() };
+        let class = ruby.define_class("TestKwArgsConnect", ruby.class_object());
+        RClass::try_convert(class)
+            .unwrap()
+            .define_method("connect", crate::method!(connect, -1))
+            .unwrap();
+
+        let eval = |code: &str| Value::nil().funcall_boxed("eval", (code,));
+
+        let result = eval("TestKwArgsConnect.new.connect(port: 80, offset: 1)").unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 81);
+
+        let err = eval("TestKwArgsConnect.new.connect").unwrap_err();
+        assert_eq!(err.message(), "missing keyword: :port");
+
+        let err = eval("TestKwArgsConnect.new.connect(port: 80, host: 'x')").unwrap_err();
+        assert_eq!(err.message(), "unknown keyword: :host");
+
+        // A positional hash is not treated as keywords
+        let err = eval("TestKwArgsConnect.new.connect({port: 80})").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "wrong number of arguments (given 1, expected 0)"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Typed handles for method wrapper functions.
+//!
+//! Ruby's C API registers every method as an untyped `VALUE (*)(ANYARGS)`
+//! together with an arity, and calls it with as many arguments as the arity
+//! says. Passing a function with a different signature is undefined behaviour
+//! that only shows up when the method is called.
+//!
+//! [`MethodFn<ARITY>`] carries the arity in its type and can only be built
+//! from a function pointer whose signature matches it, so the registration
+//! functions (`define_method`, `define_singleton_method`,
+//! `define_module_function` and `Ruby::define_global_function`) no longer
+//! take a separate arity that could disagree.
+
+use std::os::raw::c_int;
+
+use rb_sys::VALUE;
+
+/// An `extern "C"` method wrapper taking `ARITY` arguments.
+///
+/// Created by the `method!` and `function!` macros and the `wrapper()`
+/// function generated by the attribute macros, or from a hand-written
+/// wrapper with [`MethodFn::new`]:
+///
+/// ```no_run
+/// use solidus::method::MethodFn;
+/// use solidus::rb_sys::VALUE;
+///
+/// unsafe extern "C" fn answer(_rb_self: VALUE) -> VALUE {
+///     // Fixnum 42
+///     (42 << 1) | 1
+/// }
+///
+/// let func = MethodFn::<0>::new(answer as unsafe extern "C" fn(VALUE) -> VALUE);
+/// assert_eq!(func.arity(), 0);
+/// ```
+///
+/// A function whose signature doesn't match the arity is rejected at
+/// compile time:
+///
+/// ```compile_fail
+/// use solidus::method::MethodFn;
+/// use solidus::rb_sys::VALUE;
+///
+/// unsafe extern "C" fn takes_one(_rb_self: VALUE, arg: VALUE) -> VALUE {
+///     arg
+/// }
+///
+/// // error: takes_one has arity 1
+/// let func = MethodFn::<2>::new(takes_one as unsafe extern "C" fn(VALUE, VALUE) -> VALUE);
+/// ```
+#[derive(Clone, Copy)]
+pub struct MethodFn<const ARITY: i32> {
+    func: unsafe extern "C" fn() -> VALUE,
+}
+
+impl<const ARITY: i32> MethodFn<ARITY> {
+    /// Wrap a function pointer with the signature Ruby uses for `ARITY`.
+    #[inline]
+    pub fn new<F: CFunc<ARITY>>(func: F) -> Self {
+        MethodFn { func: func.erase() }
+    }
+
+    /// The arity Ruby registers the method with.
+    #[inline]
+    pub const fn arity(&self) -> i32 {
+        ARITY
+    }
+
+    /// The function as the untyped pointer passed to `rb_define_method`.
+    #[inline]
+    pub fn as_raw(&self) -> unsafe extern "C" fn() -> VALUE {
+        self.func
+    }
+}
+
+impl<const ARITY: i32> std::fmt::Debug for MethodFn<ARITY> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MethodFn")
+            .field("arity", &ARITY)
+            .field("func", &(self.func as usize as *const ()))
+            .finish()
+    }
+}
+
+/// A function pointer type Ruby can call as a method of arity `ARITY`.
+///
+/// Implemented for:
+///
+/// - `unsafe extern "C" fn(VALUE, ...) -> VALUE` taking self plus `ARITY`
+///   arguments, for arities 0 to 15
+/// - `unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE` (`argc`,
+///   `argv`, self) for arity -1
+/// - `unsafe extern "C" fn(VALUE, VALUE) -> VALUE` (self, arguments array)
+///   for arity -2
+pub trait CFunc<const ARITY: i32>: Copy {
+    /// Erase the signature, for `rb_define_method`.
+    fn erase(self) -> unsafe extern "C" fn() -> VALUE;
+}
+
+macro_rules! impl_cfunc {
+    ($($arity:literal => ($($arg:ty),*);)*) => {
+        $(
+            impl CFunc<$arity> for unsafe extern "C" fn($($arg),*) -> VALUE {
+                #[inline]
+                fn erase(self) -> unsafe extern "C" fn() -> VALUE {
+                    // SAFETY: the function is registered with the arity
+                    // matching its signature, so Ruby calls it with the
+                    // right arguments
+                    unsafe {
+                        std::mem::transmute::<Self, unsafe extern "C" fn() -> VALUE>(self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_cfunc! {
+    -2 => (VALUE, VALUE);
+    -1 => (c_int, *const VALUE, VALUE);
+    0 => (VALUE);
+    1 => (VALUE, VALUE);
+    2 => (VALUE, VALUE, VALUE);
+    3 => (VALUE, VALUE, VALUE, VALUE);
+    4 => (VALUE, VALUE, VALUE, VALUE, VALUE);
+    5 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    6 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    7 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    8 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    9 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    10 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    11 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    12 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    13 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    14 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+    15 => (VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn self_only(rb_self: VALUE) -> VALUE {
+        rb_self
+    }
+
+    unsafe extern "C" fn variadic(_argc: c_int, _argv: *const VALUE, rb_self: VALUE) -> VALUE {
+        rb_self
+    }
+
+    unsafe extern "C" fn pair(rb_self: VALUE, _arg: VALUE) -> VALUE {
+        rb_self
+    }
+
+    #[test]
+    fn test_arity() {
+        let func = MethodFn::<0>::new(self_only as unsafe extern "C" fn(VALUE) -> VALUE);
+        assert_eq!(func.arity(), 0);
+        assert_eq!(func.as_raw() as usize, self_only as usize);
+
+        let func = MethodFn::<-1>::new(
+            variadic as unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE,
+        );
+        assert_eq!(func.arity(), -1);
+    }
+
+    #[test]
+    fn test_same_signature_for_arity_1_and_minus_2() {
+        let func = pair as unsafe extern "C" fn(VALUE, VALUE) -> VALUE;
+        assert_eq!(MethodFn::<1>::new(func).arity(), 1);
+        assert_eq!(MethodFn::<-2>::new(func).arity(), -2);
+    }
+}
@@ -11,6 +11,9 @@
 //! - [`MethodArg`] - Marker trait for types that can be method arguments
 //! - [`IntoReturnValue`] - Trait for types that can be returned from methods
 //! - `method!` - Macro for wrapping Rust functions as Ruby methods
+//! - [`scan_args`] - Parser for the arguments of variadic (arity `-1`) methods
+//! - [`get_kwargs`] - Typed extraction of keyword arguments
+//! - [`Block`] - Yielding to the block passed to a method
 //!
 //! # Example
 //!
@@ -29,18 +32,66 @@
 //! }
 //!
 //! // Register the method
-//! // class.define_method("concat", method!(concat, 1), 1)?;
+//! // class.define_method("concat", method!(concat, 1))?;
 //! ```
 
 mod args;
+mod block;
+mod kwargs;
+mod method_fn;
 mod return_slot;
 mod return_value;
+mod scan_args;
 
 pub use args::MethodArg;
+pub use block::Block;
+pub use kwargs::{KwArgs, KwArgsOptional, get_kwargs, split_kwargs};
+pub use method_fn::{CFunc, MethodFn};
 pub use return_slot::{ReturnWitness, WitnessedReturn};
 pub use return_value::IntoReturnValue;
 #[allow(deprecated)]
 pub use return_value::ReturnValue;
+pub use scan_args::{Args, ScanArgsOpt, ScanArgsRequired, ScanArgsSplat, check_arity, scan_args};
+
+use crate::error::{Error, PendingRaise};
+use crate::value::Value;
+
+/// Turn the outcome of a wrapped call into a return VALUE or a pending raise.
+///
+/// Used by the `method!`/`function!` wrappers and the attribute macros once
+/// `catch_unwind` has returned. The error (or panic payload) is consumed
+/// here, so when this returns `Err` the wrapper has nothing left to drop and
+/// can perform the raise directly.
+#[doc(hidden)]
+pub fn finish_call(
+    result: std::thread::Result<Result<rb_sys::VALUE, Error>>,
+) -> Result<rb_sys::VALUE, PendingRaise> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(error.into_raise()),
+        Err(panic) => Err(Error::from_panic(panic).into_raise()),
+    }
+}
+
+/// View the `argc`/`argv` pair of a variadic method as a slice.
+///
+/// # Safety
+///
+/// `argv` must point to `argc` valid VALUEs that outlive `'a`. Ruby passes
+/// arity `-1` methods a pointer into its VM stack, which stays in place (and
+/// visible to the GC) for the whole method call.
+#[doc(hidden)]
+pub unsafe fn args_from_raw<'a>(
+    argc: std::os::raw::c_int,
+    argv: *const rb_sys::VALUE,
+) -> &'a [Value] {
+    if argc <= 0 || argv.is_null() {
+        return &[];
+    }
+    // SAFETY: Value is #[repr(transparent)] over VALUE and the caller
+    // guarantees argv holds argc values
+    unsafe { std::slice::from_raw_parts(argv as *const Value, argc as usize) }
+}
 
 /// Generate an extern "C" wrapper for a Ruby method.
 ///
@@ -51,7 +102,8 @@ pub use return_value::ReturnValue;
 /// - Creating a `Context` for safe value creation
 /// - Type conversion of arguments via `TryConvert`
 /// - Stack pinning of heap-allocated arguments
-/// - Error propagation (converts `Err` to Ruby exceptions)
+/// - Error propagation (converts `Err` to Ruby exceptions, raised only after
+///   the `Context` and all arguments have been dropped)
 /// - Return value conversion via `IntoReturnValue`
 ///
 /// # Arity
@@ -59,6 +111,23 @@ pub use return_value::ReturnValue;
 /// The macro requires specifying the arity (number of arguments excluding self and context).
 /// Use `method!(function_name, arity)` where arity is 0-4.
 ///
+/// Two variadic arities are also supported, matching Ruby's C API:
+///
+/// - `-1` - the function receives all arguments as a `&[Value]` slice, to be
+///   parsed with [`scan_args`](crate::method::scan_args)
+/// - `-2` - the function receives all arguments as a pinned `RArray`
+///
+/// The macro evaluates to a [`MethodFn`] carrying the arity, so
+/// `define_method("m", method!(m, -1))` registers it with the right one and a
+/// function whose signature doesn't match the arity is a compile error.
+///
+/// # Context Capacity
+///
+/// The wrapper creates the [`Context`](crate::Context) the function asks for, so
+/// a function that needs more than the default 8 slots takes
+/// `ctx: &'ctx Context<'ctx, 32>`. A function that is generic over the capacity
+/// gets it from the macro instead: `method!(function_name, 1, slots = 32)`.
+///
 /// # Method Signature
 ///
 /// Methods must follow this signature pattern:
@@ -93,47 +162,52 @@ pub use return_value::ReturnValue;
 /// }
 ///
 /// // Register with Ruby
-/// // class.define_method("length", method!(length, 0), 0)?;
-/// // class.define_method("concat", method!(concat, 1), 1)?;
+/// // class.define_method("length", method!(length, 0))?;
+/// // class.define_method("concat", method!(concat, 1))?;
 /// ```
 #[macro_export]
 macro_rules! method {
     // Arity 0 - self only
-    ($func:path, 0) => {{
+    ($func:path, 0 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(rb_self: $crate::rb_sys::VALUE) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
                 // Create Context on wrapper's stack
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let self_value = unsafe { $crate::Value::from_raw(rb_self) };
                 let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
 
                 let result = $func(&ctx, self_converted);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<0>::new(
+            wrapper as unsafe extern "C" fn($crate::rb_sys::VALUE) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 1 - self + 1 argument
-    ($func:path, 1) => {{
+    ($func:path, 1 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             rb_self: $crate::rb_sys::VALUE,
             arg0: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let self_value = unsafe { $crate::Value::from_raw(rb_self) };
                 let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
@@ -144,22 +218,31 @@ macro_rules! method {
 
                 let result = $func(&ctx, self_converted, arg0_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<1>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 2 - self + 2 arguments
-    ($func:path, 2) => {{
+    ($func:path, 2 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             rb_self: $crate::rb_sys::VALUE,
@@ -167,7 +250,7 @@ macro_rules! method {
             arg1: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let self_value = unsafe { $crate::Value::from_raw(rb_self) };
                 let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
@@ -182,22 +265,32 @@ macro_rules! method {
 
                 let result = $func(&ctx, self_converted, arg0_pinned, arg1_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<2>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 3 - self + 3 arguments
-    ($func:path, 3) => {{
+    ($func:path, 3 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             rb_self: $crate::rb_sys::VALUE,
@@ -206,7 +299,7 @@ macro_rules! method {
             arg2: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let self_value = unsafe { $crate::Value::from_raw(rb_self) };
                 let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
@@ -225,22 +318,33 @@ macro_rules! method {
 
                 let result = $func(&ctx, self_converted, arg0_pinned, arg1_pinned, arg2_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<3>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 4 - self + 4 arguments
-    ($func:path, 4) => {{
+    ($func:path, 4 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             rb_self: $crate::rb_sys::VALUE,
@@ -250,7 +354,7 @@ macro_rules! method {
             arg3: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let self_value = unsafe { $crate::Value::from_raw(rb_self) };
                 let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
@@ -279,28 +383,126 @@ macro_rules! method {
                     arg2_pinned,
                     arg3_pinned,
                 );
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
 
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<4>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
+    }};
+
+    // Arity -1 - self + arguments as a slice
+    ($func:path, -1 $(, slots = $slots:expr)?) => {{
+        #[allow(unused_unsafe)]
+        unsafe extern "C" fn wrapper(
+            argc: ::std::os::raw::c_int,
+            argv: *const $crate::rb_sys::VALUE,
+            rb_self: $crate::rb_sys::VALUE,
+        ) -> $crate::rb_sys::VALUE {
+            let result = ::std::panic::catch_unwind(|| {
+                let ctx = $crate::__wrapper_context!($($slots)?);
+
+                let self_value = unsafe { $crate::Value::from_raw(rb_self) };
+                let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
+
+                // SAFETY: Ruby passes argc VALUEs in argv, which live on its
+                // VM stack for the duration of the call
+                let args = unsafe { $crate::method::args_from_raw(argc, argv) };
+
+                let result = $func(&ctx, self_converted, args);
+
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
+                use $crate::method::IntoReturnValue;
+                result.into_return_value()
+            });
+
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
+            }
+        }
+
+        $crate::method::MethodFn::<-1>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    ::std::os::raw::c_int,
+                    *const $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
+    }};
+
+    // Arity -2 - self + arguments as an array
+    ($func:path, -2 $(, slots = $slots:expr)?) => {{
+        #[allow(unused_unsafe)]
+        unsafe extern "C" fn wrapper(
+            rb_self: $crate::rb_sys::VALUE,
+            args: $crate::rb_sys::VALUE,
+        ) -> $crate::rb_sys::VALUE {
+            let result = ::std::panic::catch_unwind(|| {
+                let ctx = $crate::__wrapper_context!($($slots)?);
+
+                let self_value = unsafe { $crate::Value::from_raw(rb_self) };
+                let self_converted = $crate::convert::TryConvert::try_convert(self_value)?;
+
+                let args_value = unsafe { $crate::Value::from_raw(args) };
+                let args_converted: $crate::types::RArray =
+                    $crate::convert::TryConvert::try_convert(args_value)?;
+                $crate::pin_on_stack!(args_pinned = $crate::value::NewValue::new(args_converted));
+
+                let result = $func(&ctx, self_converted, args_pinned);
+
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
+                use $crate::method::IntoReturnValue;
+                result.into_return_value()
+            });
+
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
+            }
+        }
+
+        $crate::method::MethodFn::<-2>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arities 5-15: Follow the same pattern
-    ($func:path, $arity:literal) => {
+    ($func:path, $arity:literal $(, slots = $slots:expr)?) => {
         compile_error!(concat!(
             "method! arity ",
             stringify!($arity),
             " not yet implemented. ",
-            "Currently supported arities: 0-4. ",
+            "Currently supported arities: -2, -1 and 0-4. ",
             "To add arity ",
             stringify!($arity),
             ", extend the method! macro in ",
@@ -322,13 +524,17 @@ macro_rules! method {
 /// - Creating a `Context` for safe value creation
 /// - Type conversion of arguments via `TryConvert`
 /// - Stack pinning of heap-allocated arguments
-/// - Error propagation (converts `Err` to Ruby exceptions)
+/// - Error propagation (converts `Err` to Ruby exceptions, raised only after
+///   the `Context` and all arguments have been dropped)
 /// - Return value conversion via `IntoReturnValue`
 ///
 /// # Arity
 ///
 /// The macro requires specifying the arity (number of arguments, excluding context).
-/// Use `function!(function_name, arity)` where arity is 0-4.
+/// Use `function!(function_name, arity)` where arity is 0-4, or one of the
+/// variadic arities `-1` (arguments as `&[Value]`) and `-2` (arguments as a
+/// pinned `RArray`). See [`method!`](crate::method!) for details, and for
+/// choosing the `Context` capacity with `function!(function_name, 1, slots = 32)`.
 ///
 /// # Function Signature
 ///
@@ -360,43 +566,48 @@ macro_rules! method {
 /// }
 ///
 /// // Register with Ruby
-/// // ruby.define_global_function("greet", function!(greet, 0), 0)?;
-/// // ruby.define_global_function("greet_name", function!(greet_name, 1), 1)?;
+/// // ruby.define_global_function("greet", function!(greet, 0))?;
+/// // ruby.define_global_function("greet_name", function!(greet_name, 1))?;
 /// ```
 #[macro_export]
 macro_rules! function {
     // Arity 0 - no arguments (but still receives self from Ruby)
-    ($func:path, 0) => {{
+    ($func:path, 0 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(_rb_self: $crate::rb_sys::VALUE) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let result = $func(&ctx);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<0>::new(
+            wrapper as unsafe extern "C" fn($crate::rb_sys::VALUE) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 1 - 1 argument (plus self from Ruby)
-    ($func:path, 1) => {{
+    ($func:path, 1 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             _rb_self: $crate::rb_sys::VALUE,
             arg0: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let arg0_value = unsafe { $crate::Value::from_raw(arg0) };
                 let arg0_converted = $crate::convert::TryConvert::try_convert(arg0_value)?;
@@ -404,22 +615,31 @@ macro_rules! function {
 
                 let result = $func(&ctx, arg0_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<1>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 2 - 2 arguments (plus self from Ruby)
-    ($func:path, 2) => {{
+    ($func:path, 2 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             _rb_self: $crate::rb_sys::VALUE,
@@ -427,7 +647,7 @@ macro_rules! function {
             arg1: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let arg0_value = unsafe { $crate::Value::from_raw(arg0) };
                 let arg0_converted = $crate::convert::TryConvert::try_convert(arg0_value)?;
@@ -439,22 +659,32 @@ macro_rules! function {
 
                 let result = $func(&ctx, arg0_pinned, arg1_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<2>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 3 - 3 arguments (plus self from Ruby)
-    ($func:path, 3) => {{
+    ($func:path, 3 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             _rb_self: $crate::rb_sys::VALUE,
@@ -463,7 +693,7 @@ macro_rules! function {
             arg2: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let arg0_value = unsafe { $crate::Value::from_raw(arg0) };
                 let arg0_converted = $crate::convert::TryConvert::try_convert(arg0_value)?;
@@ -479,22 +709,33 @@ macro_rules! function {
 
                 let result = $func(&ctx, arg0_pinned, arg1_pinned, arg2_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<3>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arity 4 - 4 arguments (plus self from Ruby)
-    ($func:path, 4) => {{
+    ($func:path, 4 $(, slots = $slots:expr)?) => {{
         #[allow(unused_unsafe)]
         unsafe extern "C" fn wrapper(
             _rb_self: $crate::rb_sys::VALUE,
@@ -504,7 +745,7 @@ macro_rules! function {
             arg3: $crate::rb_sys::VALUE,
         ) -> $crate::rb_sys::VALUE {
             let result = ::std::panic::catch_unwind(|| {
-                let ctx = $crate::context::Context::<8>::new();
+                let ctx = $crate::__wrapper_context!($($slots)?);
 
                 let arg0_value = unsafe { $crate::Value::from_raw(arg0) };
                 let arg0_converted = $crate::convert::TryConvert::try_convert(arg0_value)?;
@@ -524,27 +765,120 @@ macro_rules! function {
 
                 let result = $func(&ctx, arg0_pinned, arg1_pinned, arg2_pinned, arg3_pinned);
 
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
+                use $crate::method::IntoReturnValue;
+                result.into_return_value()
+            });
+
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
+            }
+        }
+
+        $crate::method::MethodFn::<4>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
+    }};
+
+    // Arity -1 - arguments as a slice (plus self from Ruby)
+    ($func:path, -1 $(, slots = $slots:expr)?) => {{
+        #[allow(unused_unsafe)]
+        unsafe extern "C" fn wrapper(
+            argc: ::std::os::raw::c_int,
+            argv: *const $crate::rb_sys::VALUE,
+            _rb_self: $crate::rb_sys::VALUE,
+        ) -> $crate::rb_sys::VALUE {
+            let result = ::std::panic::catch_unwind(|| {
+                let ctx = $crate::__wrapper_context!($($slots)?);
+
+                // SAFETY: Ruby passes argc VALUEs in argv, which live on its
+                // VM stack for the duration of the call
+                let args = unsafe { $crate::method::args_from_raw(argc, argv) };
+
+                let result = $func(&ctx, args);
+
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
+                use $crate::method::IntoReturnValue;
+                result.into_return_value()
+            });
+
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
+            }
+        }
+
+        $crate::method::MethodFn::<-1>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    ::std::os::raw::c_int,
+                    *const $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
+    }};
+
+    // Arity -2 - arguments as an array (plus self from Ruby)
+    ($func:path, -2 $(, slots = $slots:expr)?) => {{
+        #[allow(unused_unsafe)]
+        unsafe extern "C" fn wrapper(
+            _rb_self: $crate::rb_sys::VALUE,
+            args: $crate::rb_sys::VALUE,
+        ) -> $crate::rb_sys::VALUE {
+            let result = ::std::panic::catch_unwind(|| {
+                let ctx = $crate::__wrapper_context!($($slots)?);
+
+                let args_value = unsafe { $crate::Value::from_raw(args) };
+                let args_converted: $crate::types::RArray =
+                    $crate::convert::TryConvert::try_convert(args_value)?;
+                $crate::pin_on_stack!(args_pinned = $crate::value::NewValue::new(args_converted));
+
+                let result = $func(&ctx, args_pinned);
+
+                $crate::__record_slot_usage!(stringify!($func), &ctx);
+
                 use $crate::method::IntoReturnValue;
                 result.into_return_value()
             });
 
-            match result {
-                Ok(Ok(value)) => value,
-                Ok(Err(error)) => error.raise(),
-                Err(panic) => $crate::Error::from_panic(panic).raise(),
+            // Everything created by the call has been dropped by now, so
+            // raising from here cannot leak Rust state
+            match $crate::method::finish_call(result) {
+                Ok(value) => value,
+                Err(pending) => unsafe { pending.perform() },
             }
         }
 
-        unsafe { ::std::mem::transmute(wrapper as usize) }
+        $crate::method::MethodFn::<-2>::new(
+            wrapper
+                as unsafe extern "C" fn(
+                    $crate::rb_sys::VALUE,
+                    $crate::rb_sys::VALUE,
+                ) -> $crate::rb_sys::VALUE,
+        )
     }};
 
     // Arities 5-15: Follow the same pattern
-    ($func:path, $arity:literal) => {
+    ($func:path, $arity:literal $(, slots = $slots:expr)?) => {
         compile_error!(concat!(
             "function! arity ",
             stringify!($arity),
             " not yet implemented. ",
-            "Currently supported arities: 0-4. ",
+            "Currently supported arities: -2, -1 and 0-4. ",
             "To add arity ",
             stringify!($arity),
             ", extend the function! macro in ",
@@ -604,4 +938,10 @@ mod tests {
         let back = test.into_value();
         assert_eq!(back.as_raw(), 42 as rb_sys::VALUE);
     }
+
+    #[test]
+    fn test_finish_call_ok() {
+        let raw = 42 as rb_sys::VALUE;
+        assert_eq!(super::finish_call(Ok(Ok(raw))), Ok(raw));
+    }
 }
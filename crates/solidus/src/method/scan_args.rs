@@ -0,0 +1,315 @@
+//! Argument parsing for variadic methods.
+//!
+//! Methods registered with arity `-1` receive their arguments as a `&[Value]`
+//! slice. [`scan_args`] splits that slice into required, optional, splat and
+//! trailing arguments, converting each into a typed Rust value, much like
+//! `rb_scan_args` does for C extensions.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use solidus::prelude::*;
+//! use solidus::method::Args;
+//!
+//! // Ruby signature: def greet(name, greeting = nil, *rest)
+//! fn greet<'ctx>(
+//!     ctx: &'ctx Context,
+//!     _rb_self: Value,
+//!     args: &[Value],
+//! ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+//!     let Args { required: (name,), optional: (greeting,), splat, .. } =
+//!         scan_args::<(String,), (Option<String>,), &[Value], ()>(args)?;
+//!
+//!     let greeting = greeting.unwrap_or_else(|| "Hello".to_string());
+//!     let msg = format!("{}, {}! (+{} more)", greeting, name, splat.len());
+//!     ctx.new_string(&msg).map_err(Into::into)
+//! }
+//! ```
+
+use crate::convert::TryConvert;
+use crate::error::Error;
+use crate::value::Value;
+
+/// Arguments parsed by [`scan_args`].
+///
+/// The fields are in the same order as Ruby's parameter list:
+/// `def m(required, optional = nil, *splat, trailing)`.
+#[derive(Debug)]
+pub struct Args<Req, Opt, Splat, Trail> {
+    /// Leading mandatory arguments.
+    pub required: Req,
+    /// Optional arguments, `None` when not passed.
+    pub optional: Opt,
+    /// Remaining arguments, if the method takes a splat.
+    pub splat: Splat,
+    /// Mandatory arguments after the splat.
+    pub trailing: Trail,
+}
+
+/// A tuple of mandatory arguments.
+///
+/// Implemented for `()` and tuples of up to 8 [`TryConvert`] types.
+pub trait ScanArgsRequired: Sized {
+    /// The number of arguments in the tuple.
+    const LEN: usize;
+
+    /// Convert exactly [`LEN`](Self::LEN) arguments.
+    fn from_slice(args: &[Value]) -> Result<Self, Error>;
+}
+
+/// A tuple of optional arguments.
+///
+/// Implemented for `()` and tuples of up to 8 `Option<T>` where `T` is
+/// [`TryConvert`]. Arguments that were not passed become `None`.
+pub trait ScanArgsOpt: Sized {
+    /// The maximum number of arguments in the tuple.
+    const LEN: usize;
+
+    /// Convert up to [`LEN`](Self::LEN) arguments.
+    fn from_slice(args: &[Value]) -> Result<Self, Error>;
+}
+
+/// Storage for splat (`*rest`) arguments.
+///
+/// Use `()` when the method takes no splat, or `&[Value]` to borrow the
+/// remaining arguments directly from Ruby's argument list.
+pub trait ScanArgsSplat<'a>: Sized {
+    /// Whether any number of extra arguments is accepted.
+    const ACCEPTS_REST: bool;
+
+    /// Collect the remaining arguments.
+    fn from_slice(args: &'a [Value]) -> Result<Self, Error>;
+}
+
+impl ScanArgsRequired for () {
+    const LEN: usize = 0;
+
+    #[inline]
+    fn from_slice(_args: &[Value]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl ScanArgsOpt for () {
+    const LEN: usize = 0;
+
+    #[inline]
+    fn from_slice(_args: &[Value]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl ScanArgsSplat<'_> for () {
+    const ACCEPTS_REST: bool = false;
+
+    #[inline]
+    fn from_slice(_args: &[Value]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ScanArgsSplat<'a> for &'a [Value] {
+    const ACCEPTS_REST: bool = true;
+
+    #[inline]
+    fn from_slice(args: &'a [Value]) -> Result<Self, Error> {
+        Ok(args)
+    }
+}
+
+macro_rules! impl_scan_args_for_tuple {
+    ($len:literal; $($name:ident => $idx:tt),*) => {
+        impl<$($name: TryConvert),*> ScanArgsRequired for ($($name,)*) {
+            const LEN: usize = $len;
+
+            #[inline]
+            fn from_slice(args: &[Value]) -> Result<Self, Error> {
+                Ok(($($name::try_convert(args[$idx].clone())?,)*))
+            }
+        }
+
+        impl<$($name: TryConvert),*> ScanArgsOpt for ($(Option<$name>,)*) {
+            const LEN: usize = $len;
+
+            #[inline]
+            fn from_slice(args: &[Value]) -> Result<Self, Error> {
+                Ok(($(
+                    match args.get($idx) {
+                        Some(arg) => Some($name::try_convert(arg.clone())?),
+                        None => None,
+                    },
+                )*))
+            }
+        }
+    };
+}
+
+impl_scan_args_for_tuple!(1; A => 0);
+impl_scan_args_for_tuple!(2; A => 0, B => 1);
+impl_scan_args_for_tuple!(3; A => 0, B => 1, C => 2);
+impl_scan_args_for_tuple!(4; A => 0, B => 1, C => 2, D => 3);
+impl_scan_args_for_tuple!(5; A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_scan_args_for_tuple!(6; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+impl_scan_args_for_tuple!(7; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+impl_scan_args_for_tuple!(8; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+/// Check an argument count against a method's arity.
+///
+/// `max` is `None` when the method accepts any number of extra arguments.
+/// On mismatch, returns the same `ArgumentError` Ruby raises, e.g.
+/// `wrong number of arguments (given 3, expected 1..2)`.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::method::check_arity;
+///
+/// assert!(check_arity(1, 1, Some(2)).is_ok());
+/// assert_eq!(
+///     check_arity(3, 1, Some(2)).unwrap_err().message(),
+///     "wrong number of arguments (given 3, expected 1..2)"
+/// );
+/// ```
+pub fn check_arity(given: usize, min: usize, max: Option<usize>) -> Result<(), Error> {
+    let in_range = given >= min && max.is_none_or(|max| given <= max);
+    if in_range {
+        return Ok(());
+    }
+
+    let expected = match max {
+        Some(max) if max == min => format!("{}", min),
+        Some(max) => format!("{}..{}", min, max),
+        None => format!("{}+", min),
+    };
+    Err(Error::argument(format!(
+        "wrong number of arguments (given {}, expected {})",
+        given, expected
+    )))
+}
+
+/// Parse a variadic argument list into typed Rust values.
+///
+/// The type parameters describe the method's Ruby signature:
+///
+/// - `Req` - leading mandatory arguments, e.g. `(String, i64)`
+/// - `Opt` - optional arguments, e.g. `(Option<bool>,)`
+/// - `Splat` - `&[Value]` to accept any number of extra arguments, or `()`
+/// - `Trail` - mandatory arguments after the splat
+///
+/// Optional arguments are filled from left to right before the splat
+/// receives anything, matching Ruby's own parameter binding.
+///
+/// # Errors
+///
+/// Returns an `ArgumentError` with Ruby's message if the number of
+/// arguments doesn't match, or the conversion error of the first argument
+/// that has the wrong type.
+pub fn scan_args<'a, Req, Opt, Splat, Trail>(
+    args: &'a [Value],
+) -> Result<Args<Req, Opt, Splat, Trail>, Error>
+where
+    Req: ScanArgsRequired,
+    Opt: ScanArgsOpt,
+    Splat: ScanArgsSplat<'a>,
+    Trail: ScanArgsRequired,
+{
+    let min = Req::LEN + Trail::LEN;
+    let max = if Splat::ACCEPTS_REST {
+        None
+    } else {
+        Some(min + Opt::LEN)
+    };
+    check_arity(args.len(), min, max)?;
+
+    let optional_len = (args.len() - min).min(Opt::LEN);
+    let splat_start = Req::LEN + optional_len;
+    let trailing_start = args.len() - Trail::LEN;
+
+    Ok(Args {
+        required: Req::from_slice(&args[..Req::LEN])?,
+        optional: Opt::from_slice(&args[Req::LEN..splat_start])?,
+        splat: Splat::from_slice(&args[splat_start..trailing_start])?,
+        trailing: Trail::from_slice(&args[trailing_start..])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(n: usize) -> Vec<Value> {
+        (0..n)
+            .map(|i| {
+                if i % 2 == 0 {
+                    Value::r#true()
+                } else {
+                    Value::nil()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_arity_messages() {
+        assert!(check_arity(1, 1, Some(1)).is_ok());
+        assert_eq!(
+            check_arity(0, 1, Some(1)).unwrap_err().message(),
+            "wrong number of arguments (given 0, expected 1)"
+        );
+        assert_eq!(
+            check_arity(3, 1, Some(2)).unwrap_err().message(),
+            "wrong number of arguments (given 3, expected 1..2)"
+        );
+        assert_eq!(
+            check_arity(0, 2, None).unwrap_err().message(),
+            "wrong number of arguments (given 0, expected 2+)"
+        );
+        assert!(check_arity(10, 2, None).is_ok());
+    }
+
+    #[test]
+    fn test_required_only() {
+        let args = values(2);
+        let parsed = scan_args::<(bool, bool), (), (), ()>(&args).unwrap();
+        assert_eq!(parsed.required, (true, false));
+
+        let args = values(3);
+        assert!(scan_args::<(bool, bool), (), (), ()>(&args).is_err());
+    }
+
+    #[test]
+    fn test_optional_filled_left_to_right() {
+        let args = values(2);
+        let parsed = scan_args::<(bool,), (Option<bool>, Option<bool>), (), ()>(&args).unwrap();
+        assert!(parsed.required.0);
+        assert_eq!(parsed.optional, (Some(false), None));
+    }
+
+    #[test]
+    fn test_splat_and_trailing() {
+        let args = values(5);
+        let parsed = scan_args::<(bool,), (Option<bool>,), &[Value], (bool,)>(&args).unwrap();
+        assert!(parsed.required.0);
+        assert_eq!(parsed.optional, (Some(false),));
+        assert_eq!(parsed.splat.len(), 2);
+        assert!(parsed.trailing.0);
+    }
+
+    #[test]
+    fn test_splat_empty_when_optional_unfilled() {
+        let args = values(1);
+        let parsed = scan_args::<(), (Option<bool>, Option<bool>), &[Value], ()>(&args).unwrap();
+        assert_eq!(parsed.optional, (Some(true), None));
+        assert!(parsed.splat.is_empty());
+    }
+
+    #[test]
+    fn test_too_few_with_splat() {
+        let args = values(1);
+        let err = scan_args::<(bool,), (), &[Value], (bool,)>(&args).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "wrong number of arguments (given 1, expected 2+)"
+        );
+    }
+}
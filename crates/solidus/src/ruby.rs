@@ -322,8 +322,8 @@ impl Ruby {
     /// # Arguments
     ///
     /// * `name` - The function name
-    /// * `func` - A function pointer generated by the `function!` macro
-    /// * `arity` - The number of arguments (-1 for variadic, 0-15 for fixed)
+    /// * `func` - A function generated by the `function!` macro, which carries its
+    ///   arity
     ///
     /// # Example
     ///
@@ -339,14 +339,13 @@ impl Ruby {
     /// }
     ///
     /// let ruby = unsafe { Ruby::get() };
-    /// ruby.define_global_function("greet", function!(greet, 0), 0).unwrap();
+    /// ruby.define_global_function("greet", function!(greet, 0)).unwrap();
     /// // Now `greet` can be called from Ruby without qualification
     /// ```
-    pub fn define_global_function(
+    pub fn define_global_function<const ARITY: i32>(
         &self,
         name: &str,
-        func: unsafe extern "C" fn() -> rb_sys::VALUE,
-        arity: i32,
+        func: crate::method::MethodFn<ARITY>,
     ) -> Result<(), crate::error::Error> {
         use crate::error::Error;
 
@@ -357,7 +356,7 @@ impl Ruby {
         // SAFETY: c_name is a valid C string
         // rb_define_global_function registers the function pointer with Ruby's Kernel module
         unsafe {
-            rb_sys::rb_define_global_function(c_name.as_ptr(), Some(func), arity);
+            rb_sys::rb_define_global_function(c_name.as_ptr(), Some(func.as_raw()), ARITY);
         }
 
         Ok(())
@@ -428,7 +427,6 @@ mod ruby_tests {
         ruby.define_global_function(
             "solidus_test_global_0",
             function!(test_global_func_arity_0, 0),
-            0,
         )
         .unwrap();
     }
@@ -441,7 +439,6 @@ mod ruby_tests {
         ruby.define_global_function(
             "solidus_test_global_1",
             function!(test_global_func_arity_1, 1),
-            1,
         )
         .unwrap();
     }
@@ -454,7 +451,6 @@ mod ruby_tests {
         ruby.define_global_function(
             "solidus_test_global_2",
             function!(test_global_func_arity_2, 2),
-            2,
         )
         .unwrap();
     }
@@ -464,7 +460,7 @@ mod ruby_tests {
         let ruby = unsafe { Ruby::get() };
 
         let result =
-            ruby.define_global_function("test\0func", function!(test_global_func_arity_0, 0), 0);
+            ruby.define_global_function("test\0func", function!(test_global_func_arity_0, 0));
 
         assert!(result.is_err());
     }
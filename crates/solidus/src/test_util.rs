@@ -0,0 +1,9 @@
+//! Helpers shared by tests that run Ruby code.
+
+use crate::error::Error;
+use crate::value::{BoxValue, Value};
+
+/// Evaluate Ruby code at the top level and return its result.
+pub(crate) fn eval(code: &str) -> Result<BoxValue<Value>, Error> {
+    Value::nil().funcall_boxed("eval", (code,))
+}
@@ -0,0 +1,272 @@
+//! Allocator-based construction of wrapped types.
+//!
+//! [`wrap`](super::wrap) wraps a value that Rust has already built. Ruby's own
+//! `Class#new` works differently: it first *allocates* an object, then calls
+//! `initialize` on it with the arguments. Registering an allocator lets
+//! wrapped classes be created with `MyClass.new(...)`, subclassed in Ruby, and
+//! copied with `dup`/`clone`:
+//!
+//! - [`define_alloc_func`] allocates objects holding `T::default()`
+//! - [`define_initialize`] defines `initialize` from an [`Initialize`] impl
+//! - [`define_initialize_copy`] defines `initialize_copy` from a `Clone` impl,
+//!   which `dup` and `clone` call on the newly allocated copy
+//!
+//! # Example
+//!
+//! ```no_run
+//! use solidus::prelude::*;
+//! use solidus::typed_data::{Initialize, define_alloc_func, define_initialize, define_initialize_copy};
+//!
+//! #[solidus::wrap(class = "Point")]
+//! #[derive(Default, Clone)]
+//! struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! impl Initialize for Point {
+//!     // Point.new(x, y = 0.0)
+//!     fn initialize(&mut self, args: &[Value]) -> Result<(), Error> {
+//!         let args = scan_args::<(f64,), (Option<f64>,), (), ()>(args)?;
+//!         self.x = args.required.0;
+//!         self.y = args.optional.0.unwrap_or(0.0);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[solidus::init]
+//! fn init(ruby: &Ruby) -> Result<(), Error> {
+//!     let class = RClass::try_convert(ruby.define_class("Point", ruby.class_object()))?;
+//!     define_alloc_func::<Point>(&class)?;
+//!     define_initialize::<Point>(&class)?;
+//!     define_initialize_copy::<Point>(&class)?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::os::raw::c_int;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::error::{Error, ExceptionClass, protect};
+use crate::method::{MethodFn, args_from_raw, finish_call};
+use crate::types::{Module, RClass};
+use crate::value::{ReprValue, Value};
+
+use super::wrap::wrap_raw;
+use super::{TypedData, get, get_mut};
+
+/// A wrapped type that can be initialized from Ruby's `initialize`.
+///
+/// Together with [`define_alloc_func`], this makes `Class#new` work: Ruby
+/// allocates the object with the default value, then passes the arguments of
+/// `new` to [`initialize`](Self::initialize).
+pub trait Initialize: TypedData {
+    /// Initialize a freshly allocated value from the arguments of `new`.
+    ///
+    /// `args` are all positional arguments; use
+    /// [`scan_args`](crate::method::scan_args) to check and convert them.
+    fn initialize(&mut self, args: &[Value]) -> Result<(), Error>;
+}
+
+/// Register an allocator for `class` that wraps `T::default()`.
+///
+/// After this, `class.allocate` and `class.new` create wrapped `T`s, and so
+/// do subclasses defined in Ruby, which inherit the allocator.
+///
+/// # Errors
+///
+/// Returns an error if `class` is a singleton class.
+pub fn define_alloc_func<T: TypedData + Default>(class: &RClass) -> Result<(), Error> {
+    let class = class.as_raw();
+    // SAFETY: class is a valid class and alloc_default::<T> matches rb_alloc_func_t
+    protect(|| unsafe { rb_sys::rb_define_alloc_func(class, Some(alloc_default::<T>)) })
+}
+
+/// Define `initialize` on `class` to call [`Initialize::initialize`].
+///
+/// The method takes any number of arguments, so `T` is responsible for
+/// rejecting the wrong number of them.
+///
+/// # Errors
+///
+/// Returns an error if the method can't be defined.
+pub fn define_initialize<T: Initialize>(class: &RClass) -> Result<(), Error> {
+    let func = MethodFn::<-1>::new(
+        initialize::<T>
+            as unsafe extern "C" fn(c_int, *const rb_sys::VALUE, rb_sys::VALUE) -> rb_sys::VALUE,
+    );
+    class.clone().define_method("initialize", func)
+}
+
+/// Define `initialize_copy` on `class` to clone the wrapped `T`.
+///
+/// `dup` and `clone` allocate the copy with the class's allocator and then
+/// call `initialize_copy`, so this needs [`define_alloc_func`] as well.
+///
+/// # Errors
+///
+/// Returns an error if the method can't be defined.
+pub fn define_initialize_copy<T: TypedData + Clone>(class: &RClass) -> Result<(), Error> {
+    let func = MethodFn::<1>::new(
+        initialize_copy::<T> as unsafe extern "C" fn(rb_sys::VALUE, rb_sys::VALUE) -> rb_sys::VALUE,
+    );
+    class.clone().define_method("initialize_copy", func)
+}
+
+/// Allocator registered by [`define_alloc_func`].
+unsafe extern "C" fn alloc_default<T: TypedData + Default>(klass: rb_sys::VALUE) -> rb_sys::VALUE {
+    let result = catch_unwind(|| {
+        // SAFETY: klass is the class being allocated
+        Ok(unsafe { wrap_raw(klass, T::default()) })
+    });
+
+    match finish_call(result) {
+        Ok(value) => value,
+        // SAFETY: nothing is left on this frame that needs dropping
+        Err(pending) => unsafe { pending.perform() },
+    }
+}
+
+/// `initialize` defined by [`define_initialize`].
+unsafe extern "C" fn initialize<T: Initialize>(
+    argc: c_int,
+    argv: *const rb_sys::VALUE,
+    rb_self: rb_sys::VALUE,
+) -> rb_sys::VALUE {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: Ruby passes a valid receiver and argc arguments
+        let rb_self = unsafe { Value::from_raw(rb_self) };
+        let args = unsafe { args_from_raw(argc, argv) };
+        get_mut::<T>(&rb_self)?.initialize(args)?;
+        Ok(Value::nil().as_raw())
+    }));
+
+    // Everything created by the call has been dropped by now, so raising
+    // can't leak it.
+    match finish_call(result) {
+        Ok(value) => value,
+        // SAFETY: nothing is left on this frame that needs dropping
+        Err(pending) => unsafe { pending.perform() },
+    }
+}
+
+/// `initialize_copy` defined by [`define_initialize_copy`].
+unsafe extern "C" fn initialize_copy<T: TypedData + Clone>(
+    rb_self: rb_sys::VALUE,
+    orig: rb_sys::VALUE,
+) -> rb_sys::VALUE {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        if rb_self == orig {
+            return Ok(rb_self);
+        }
+        // SAFETY: Ruby passes a valid receiver and argument
+        let (rb_self, orig) = unsafe { (Value::from_raw(rb_self), Value::from_raw(orig)) };
+
+        // SAFETY: rb_self is a valid VALUE
+        if unsafe { Value::from_raw(rb_sys::rb_obj_frozen_p(rb_self.as_raw())) }.is_truthy() {
+            return Err(Error::new(
+                ExceptionClass::FrozenError,
+                format!("can't modify frozen {}", T::class_name()),
+            ));
+        }
+
+        let copy = get::<T>(&orig)?.clone();
+        *get_mut::<T>(&rb_self)? = copy;
+        Ok(rb_self.as_raw())
+    }));
+
+    // Everything created by the call has been dropped by now, so raising
+    // can't leak it.
+    match finish_call(result) {
+        Ok(value) => value,
+        // SAFETY: nothing is left on this frame that needs dropping
+        Err(pending) => unsafe { pending.perform() },
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use crate::convert::TryConvert;
+    use crate::method::scan_args;
+    use crate::test_util::eval;
+    use crate::typed_data::{DataType, DataTypeBuilder};
+    use rb_sys_test_helpers::ruby_test;
+
+    #[derive(Default, Clone)]
+    struct Counter {
+        count: i64,
+        step: i64,
+    }
+
+    impl TypedData for Counter {
+        fn class_name() -> &'static str {
+            "TestAllocCounter"
+        }
+        fn data_type() -> &'static DataType {
+            static DT: std::sync::OnceLock<DataType> = std::sync::OnceLock::new();
+            DT.get_or_init(|| DataTypeBuilder::<Counter>::new("TestAllocCounter").build())
+        }
+    }
+
+    impl Initialize for Counter {
+        fn initialize(&mut self, args: &[Value]) -> Result<(), Error> {
+            let args = scan_args::<(i64,), (Option<i64>,), (), ()>(args)?;
+            self.count = args.required.0;
+            self.step = args.optional.0.unwrap_or(1);
+            Ok(())
+        }
+    }
+
+    fn define_counter() {
+        let ruby = unsafe { crate::Ruby::get() };
+        let class = RClass::try_convert(ruby.define_class("TestAllocCounter", ruby.class_object()))
+            .unwrap();
+        define_alloc_func::<Counter>(&class).unwrap();
+        define_initialize::<Counter>(&class).unwrap();
+        define_initialize_copy::<Counter>(&class).unwrap();
+    }
+
+    #[ruby_test]
+    fn test_new_calls_initialize() {
+        define_counter();
+        let counter = eval("TestAllocCounter.new(5, 2)").unwrap();
+        let counter = get::<Counter>(&counter).unwrap();
+        assert_eq!(counter.count, 5);
+        assert_eq!(counter.step, 2);
+
+        let err = eval("TestAllocCounter.new").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "wrong number of arguments (given 0, expected 1..2)"
+        );
+    }
+
+    #[ruby_test]
+    fn test_allocate_uses_default() {
+        define_counter();
+        let counter = eval("TestAllocCounter.allocate").unwrap();
+        assert_eq!(get::<Counter>(&counter).unwrap().count, 0);
+    }
+
+    #[ruby_test]
+    fn test_subclass() {
+        define_counter();
+        let counter =
+            eval("class TestAllocSub < TestAllocCounter; end; TestAllocSub.new(3)").unwrap();
+        assert_eq!(get::<Counter>(&counter).unwrap().count, 3);
+    }
+
+    #[ruby_test]
+    fn test_dup_and_clone() {
+        define_counter();
+        let original = eval("$alloc_counter = TestAllocCounter.new(7)").unwrap();
+        let copy = eval("$alloc_counter.dup").unwrap();
+        let frozen = eval("$alloc_counter.freeze.clone").unwrap();
+
+        get_mut::<Counter>(&copy).unwrap().count = 8;
+        assert_eq!(get::<Counter>(&original).unwrap().count, 7);
+        assert_eq!(get::<Counter>(&frozen).unwrap().count, 7);
+        assert!(eval("$alloc_counter.clone.frozen?").unwrap().is_truthy());
+    }
+}
@@ -0,0 +1,188 @@
+//! Runtime borrow checking for wrapped data.
+//!
+//! Ruby code can pass the same object to a method more than once (think
+//! `a.merge!(a)`), so handing out `&mut T` for a wrapped object without any
+//! check could create two aliasing mutable references. Like `RefCell`, every
+//! wrapped value carries a borrow flag: [`get`](super::get) returns a
+//! [`TypedRef`] guard and [`get_mut`](super::get_mut) a [`TypedRefMut`]
+//! guard, and a conflicting borrow is reported as a Ruby `RuntimeError`
+//! instead of undefined behaviour.
+
+use std::cell::{Cell, UnsafeCell};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::error::Error;
+
+use super::TypedData;
+
+/// The heap allocation behind a wrapped object: the value and its borrow flag.
+///
+/// The flag counts shared borrows, or is `-1` while the value is mutably
+/// borrowed.
+pub(crate) struct DataCell<T> {
+    borrow: Cell<isize>,
+    value: UnsafeCell<T>,
+}
+
+impl<T> DataCell<T> {
+    /// Create an unborrowed cell.
+    pub(crate) fn new(value: T) -> Self {
+        DataCell {
+            borrow: Cell::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// A raw pointer to the value, for the GC callbacks.
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.value.get()
+    }
+}
+
+impl<T: TypedData> DataCell<T> {
+    /// Borrow the value immutably.
+    pub(crate) fn try_borrow(&self) -> Result<TypedRef<'_, T>, Error> {
+        let borrow = self.borrow.get();
+        if borrow < 0 {
+            return Err(Error::runtime(format!(
+                "{} is already mutably borrowed",
+                T::class_name()
+            )));
+        }
+        self.borrow.set(borrow + 1);
+        Ok(TypedRef { cell: self })
+    }
+
+    /// Borrow the value mutably.
+    pub(crate) fn try_borrow_mut(&self) -> Result<TypedRefMut<'_, T>, Error> {
+        if self.borrow.get() != 0 {
+            return Err(Error::runtime(format!(
+                "{} is already borrowed",
+                T::class_name()
+            )));
+        }
+        self.borrow.set(-1);
+        Ok(TypedRefMut { cell: self })
+    }
+}
+
+/// A shared borrow of a wrapped value, returned by [`get`](super::get).
+///
+/// While any `TypedRef` to an object is alive, [`get_mut`](super::get_mut)
+/// on the same object fails.
+pub struct TypedRef<'a, T> {
+    cell: &'a DataCell<T>,
+}
+
+impl<T> Deref for TypedRef<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // SAFETY: the borrow flag guarantees no mutable borrow exists
+        unsafe { &*self.cell.as_ptr() }
+    }
+}
+
+impl<T> Drop for TypedRef<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.borrow.set(self.cell.borrow.get() - 1);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TypedRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A mutable borrow of a wrapped value, returned by [`get_mut`](super::get_mut).
+///
+/// While a `TypedRefMut` to an object is alive, any other borrow of the same
+/// object fails.
+pub struct TypedRefMut<'a, T> {
+    cell: &'a DataCell<T>,
+}
+
+impl<T> Deref for TypedRefMut<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // SAFETY: the borrow flag guarantees this is the only borrow
+        unsafe { &*self.cell.as_ptr() }
+    }
+}
+
+impl<T> DerefMut for TypedRefMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the borrow flag guarantees this is the only borrow
+        unsafe { &mut *self.cell.as_ptr() }
+    }
+}
+
+impl<T> Drop for TypedRefMut<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.borrow.set(0);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TypedRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed_data::{DataType, DataTypeBuilder};
+
+    #[derive(Debug)]
+    struct Counter(i64);
+
+    impl TypedData for Counter {
+        fn class_name() -> &'static str {
+            "Counter"
+        }
+        fn data_type() -> &'static DataType {
+            static DT: std::sync::OnceLock<DataType> = std::sync::OnceLock::new();
+            DT.get_or_init(|| DataTypeBuilder::<Counter>::new("Counter").build())
+        }
+    }
+
+    #[test]
+    fn test_shared_borrows() {
+        let cell = DataCell::new(Counter(1));
+        let a = cell.try_borrow().unwrap();
+        let b = cell.try_borrow().unwrap();
+        assert_eq!(a.0 + b.0, 2);
+        assert_eq!(
+            cell.try_borrow_mut().unwrap_err().message(),
+            "Counter is already borrowed"
+        );
+        drop(a);
+        assert!(cell.try_borrow_mut().is_err());
+        drop(b);
+        assert!(cell.try_borrow_mut().is_ok());
+    }
+
+    #[test]
+    fn test_mutable_borrow() {
+        let cell = DataCell::new(Counter(1));
+        {
+            let mut counter = cell.try_borrow_mut().unwrap();
+            counter.0 += 1;
+            assert_eq!(
+                cell.try_borrow().unwrap_err().message(),
+                "Counter is already mutably borrowed"
+            );
+            assert!(cell.try_borrow_mut().is_err());
+        }
+        assert_eq!(cell.try_borrow().unwrap().0, 2);
+    }
+}
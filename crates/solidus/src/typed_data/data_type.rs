@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use std::os::raw::{c_ulong, c_void};
 use std::ptr;
 
+use super::borrow::DataCell;
 use super::traits::{DataTypeFunctions, TypedData};
 
 /// Describes a Rust type to Ruby's TypedData system.
@@ -240,11 +241,11 @@ impl<T: DataTypeFunctions> DataTypeBuilder<T> {
 /// # Safety
 ///
 /// This is called by Ruby's GC. The `data` pointer must be a valid pointer
-/// to a `T` that was allocated by `Box::into_raw`.
+/// to a `DataCell<T>` that was allocated by `Box::into_raw`.
 unsafe extern "C" fn free_callback<T>(data: *mut c_void) {
     if !data.is_null() {
-        // SAFETY: data was created by Box::into_raw in wrap()
-        let _ = unsafe { Box::from_raw(data as *mut T) };
+        // SAFETY: data was created by Box::into_raw in wrap_raw()
+        let _ = unsafe { Box::from_raw(data as *mut DataCell<T>) };
     }
 }
 
@@ -252,10 +253,12 @@ unsafe extern "C" fn free_callback<T>(data: *mut c_void) {
 ///
 /// # Safety
 ///
-/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a `T`.
+/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a
+/// `DataCell<T>`. The value is read regardless of its borrow flag: the GC can
+/// run while Rust code holds a borrow, and marking only reads Ruby values.
 unsafe extern "C" fn mark_callback<T: DataTypeFunctions>(data: *mut c_void) {
     if !data.is_null() {
-        let value = unsafe { &*(data as *const T) };
+        let value = unsafe { &*(*(data as *const DataCell<T>)).as_ptr() };
         let marker = super::Marker::new();
         value.mark(&marker);
     }
@@ -265,10 +268,11 @@ unsafe extern "C" fn mark_callback<T: DataTypeFunctions>(data: *mut c_void) {
 ///
 /// # Safety
 ///
-/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a `T`.
+/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a
+/// `DataCell<T>`.
 unsafe extern "C" fn compact_callback<T: DataTypeFunctions>(data: *mut c_void) {
     if !data.is_null() {
-        let value = unsafe { &mut *(data as *mut T) };
+        let value = unsafe { &mut *(*(data as *const DataCell<T>)).as_ptr() };
         let compactor = super::Compactor::new();
         value.compact(&compactor);
     }
@@ -278,10 +282,11 @@ unsafe extern "C" fn compact_callback<T: DataTypeFunctions>(data: *mut c_void) {
 ///
 /// # Safety
 ///
-/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a `T`.
+/// This is called by Ruby's GC. The `data` pointer must be a valid pointer to a
+/// `DataCell<T>`.
 unsafe extern "C" fn size_callback<T: DataTypeFunctions>(data: *const c_void) -> c_ulong {
     if !data.is_null() {
-        let value = unsafe { &*(data as *const T) };
+        let value = unsafe { &*(*(data as *const DataCell<T>)).as_ptr() };
         value.size() as c_ulong
     } else {
         0
@@ -3,6 +3,11 @@
 //! This module provides the infrastructure for wrapping arbitrary Rust types
 //! as Ruby objects with proper garbage collection integration.
 //!
+//! Objects are either created from Rust with [`wrap`], or from Ruby with
+//! `MyClass.new` once an allocator is registered (see [`define_alloc_func`]).
+//! The wrapped value is borrowed with [`get`] and [`get_mut`], which check
+//! borrows at runtime like `RefCell`.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -21,12 +26,17 @@
 //! }
 //! ```
 
+mod alloc;
+mod borrow;
 mod data_type;
 mod marker;
 mod traits;
 mod wrap;
 
+pub use alloc::{Initialize, define_alloc_func, define_initialize, define_initialize_copy};
+pub use borrow::{TypedRef, TypedRefMut};
 pub use data_type::{DataType, DataTypeBuilder};
 pub use marker::{Compactor, Marker};
 pub use traits::{DataTypeFunctions, TypedData};
+pub(crate) use wrap::wrap_raw;
 pub use wrap::{get, get_mut, wrap};
@@ -37,7 +37,10 @@ use super::{Compactor, DataType, Marker};
 ///
 /// The `'static` bound is required because wrapped values are stored in Ruby objects
 /// and can live indefinitely (until the Ruby object is garbage collected).
-pub trait TypedData: Sized + Send + 'static {
+///
+/// Wrapped types don't need to be `Send`: Ruby only touches them while holding
+/// the GVL, so they may contain `BoxValue`s and other Ruby values.
+pub trait TypedData: Sized + 'static {
     /// The Ruby class name for this type.
     ///
     /// This is used for error messages and debugging.
@@ -7,6 +7,7 @@ use crate::types::RClass;
 use crate::value::Value;
 
 use super::TypedData;
+use super::borrow::{DataCell, TypedRef, TypedRefMut};
 
 /// Wrap a Rust value in a Ruby object.
 ///
@@ -43,28 +44,61 @@ use super::TypedData;
 /// The wrapped value will be freed when the Ruby object is collected. Do not
 /// attempt to access the value after the Ruby object has been collected.
 pub fn wrap<T: TypedData>(_ruby: &Ruby, class: &RClass, value: T) -> Result<Value, Error> {
-    // Allocate on heap
-    let boxed = Box::new(value);
+    // SAFETY: class is a valid Ruby class
+    let raw = unsafe { wrap_raw(class.as_value().as_raw(), value) };
+
+    // SAFETY: rb_data_typed_object_wrap returns a valid Ruby VALUE
+    Ok(unsafe { Value::from_raw(raw) })
+}
+
+/// Move `value` to the heap and wrap it in a new object of class `klass`.
+///
+/// # Safety
+///
+/// `klass` must be a valid class, or 0 for a hidden object.
+pub(crate) unsafe fn wrap_raw<T: TypedData>(klass: rb_sys::VALUE, value: T) -> rb_sys::VALUE {
+    // Allocate on heap, next to the borrow flag
+    let boxed = Box::new(DataCell::new(value));
     let ptr = Box::into_raw(boxed) as *mut std::ffi::c_void;
 
-    // Get the data type descriptor
+    // SAFETY: ptr is a valid heap pointer and T's data type describes it,
+    // freeing it with Box::from_raw
+    unsafe { rb_sys::rb_data_typed_object_wrap(klass, ptr, T::data_type().as_raw()) }
+}
+
+/// Find the cell of the `T` wrapped in `value`.
+///
+/// Mismatches are reported with the same message as Ruby's own type checks.
+fn data_cell<T: TypedData>(value: &Value) -> Result<&DataCell<T>, Error> {
     let data_type = T::data_type();
 
-    // Create the Ruby object
-    // SAFETY: class is a valid Ruby class, ptr is a valid heap pointer,
-    // data_type describes T correctly
-    let raw = unsafe {
-        rb_sys::rb_data_typed_object_wrap(class.as_value().as_raw(), ptr, data_type.as_raw())
-    };
+    // rb_check_typeddata raises on a mismatch, so check the type first
+    // SAFETY: rb_typeddata_is_kind_of accepts any VALUE
+    if unsafe { rb_sys::rb_typeddata_is_kind_of(value.as_raw(), data_type.as_raw()) } == 0 {
+        return Err(Error::type_error(format!(
+            "wrong argument type {} (expected {})",
+            value.class_name().unwrap_or_else(|_| "unknown".to_string()),
+            T::class_name()
+        )));
+    }
+
+    // SAFETY: value is typed data of T's data type, so this can't raise
+    let ptr = unsafe { rb_sys::rb_check_typeddata(value.as_raw(), data_type.as_raw()) };
+    if ptr.is_null() {
+        return Err(Error::type_error(format!(
+            "uninitialized {}",
+            T::class_name()
+        )));
+    }
 
-    // SAFETY: rb_data_typed_object_wrap returns a valid Ruby VALUE
-    Ok(unsafe { Value::from_raw(raw) })
+    // SAFETY: the data type matched, so this is a DataCell<T> stored by wrap_raw
+    Ok(unsafe { &*(ptr as *const DataCell<T>) })
 }
 
 /// Get a reference to the wrapped Rust value.
 ///
-/// This extracts a reference to the Rust value wrapped in a Ruby object.
-/// The reference is valid as long as the Ruby object is not collected.
+/// This borrows the Rust value wrapped in a Ruby object. The returned
+/// [`TypedRef`] derefs to `&T` and releases the borrow when dropped.
 ///
 /// # Arguments
 ///
@@ -72,8 +106,8 @@ pub fn wrap<T: TypedData>(_ruby: &Ruby, class: &RClass, value: T) -> Result<Valu
 ///
 /// # Returns
 ///
-/// A reference to the wrapped value, or an error if the value is not a
-/// wrapped `T`.
+/// A shared borrow of the wrapped value, or an error if the value is not a
+/// wrapped `T` or is currently mutably borrowed.
 ///
 /// # Example
 ///
@@ -92,7 +126,7 @@ pub fn wrap<T: TypedData>(_ruby: &Ruby, class: &RClass, value: T) -> Result<Valu
 /// }
 ///
 /// fn point_x(rb_self: Value) -> Result<f64, Error> {
-///     let point: &Point = get(&rb_self)?;
+///     let point = get::<Point>(&rb_self)?;
 ///     Ok(point.x)
 /// }
 /// ```
@@ -100,59 +134,36 @@ pub fn wrap<T: TypedData>(_ruby: &Ruby, class: &RClass, value: T) -> Result<Valu
 /// # Errors
 ///
 /// Returns an error if:
-/// - The value is not a TypedData object
-/// - The value wraps a different type than `T`
-pub fn get<T: TypedData>(value: &Value) -> Result<&T, Error> {
-    let data_type = T::data_type();
-
-    // SAFETY: rb_check_typeddata returns NULL if type doesn't match,
-    // otherwise returns the data pointer
-    let ptr = unsafe { rb_sys::rb_check_typeddata(value.as_raw(), data_type.as_raw()) };
-
-    if ptr.is_null() {
-        return Err(Error::type_error(format!(
-            "expected {}, got {}",
-            T::class_name(),
-            value.class_name().unwrap_or_else(|_| "unknown".to_string())
-        )));
-    }
-
-    // SAFETY: rb_check_typeddata verified this is a T
-    let reference = unsafe { &*(ptr as *const T) };
-    Ok(reference)
+/// - The value is not a TypedData object (`TypeError`)
+/// - The value wraps a different type than `T` (`TypeError`)
+/// - The value is mutably borrowed by [`get_mut`] (`RuntimeError`)
+pub fn get<T: TypedData>(value: &Value) -> Result<TypedRef<'_, T>, Error> {
+    data_cell::<T>(value)?.try_borrow()
 }
 
 /// Get a mutable reference to the wrapped Rust value.
 ///
-/// This extracts a mutable reference to the Rust value wrapped in a Ruby object.
-///
-/// # Why is this not marked `unsafe`?
+/// This mutably borrows the Rust value wrapped in a Ruby object. The returned
+/// [`TypedRefMut`] derefs to `&mut T` and releases the borrow when dropped.
 ///
-/// While this function returns a mutable reference that could theoretically alias
-/// with other references, Ruby's GVL (Global VM Lock) ensures single-threaded access
-/// to Ruby objects. Since Ruby code cannot run in parallel, aliasing cannot occur in
-/// practice. However, users must still ensure they don't create aliasing within their
-/// own Rust code.
+/// The borrow is checked at runtime, like `RefCell::borrow_mut`: Ruby code can
+/// hand the same object to a method twice (`a.merge!(a)`), and borrowing it
+/// mutably while another borrow is alive returns an error instead of creating
+/// aliasing references.
 ///
-/// # Safety Warning
-///
-/// This function does NOT provide any aliasing guarantees within your Rust code.
-/// If you call this while another reference to the same data exists in your Rust code,
-/// you will have undefined behavior. For safe mutation, use `RefCell<T>` inside your
-/// wrapped type:
+/// # Example
 ///
 /// ```no_run
-/// use std::cell::RefCell;
+/// use solidus::prelude::*;
+/// use solidus::typed_data::get_mut;
 ///
 /// #[solidus::wrap(class = "Counter")]
-/// struct Counter(RefCell<i64>);
+/// struct Counter { count: i64 }
 ///
-/// impl Counter {
-///     fn increment(&self) -> i64 {
-///         let mut val = self.0.borrow_mut();
-///         *val += 1;
-///         *val
-///     }
+/// fn increment(rb_self: Value) -> Result<i64, Error> {
+///     let mut counter = get_mut::<Counter>(&rb_self)?;
+///     counter.count += 1;
+///     Ok(counter.count)
 /// }
 /// ```
 ///
@@ -162,41 +173,27 @@ pub fn get<T: TypedData>(value: &Value) -> Result<&T, Error> {
 ///
 /// # Returns
 ///
-/// A mutable reference to the wrapped value, or an error if the value is not
-/// a wrapped `T`.
+/// A mutable borrow of the wrapped value, or an error if the value is not
+/// a wrapped `T` or is currently borrowed.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The value is not a TypedData object
-/// - The value wraps a different type than `T`
-#[allow(clippy::mut_from_ref)]
-pub fn get_mut<T: TypedData>(value: &Value) -> Result<&mut T, Error> {
-    let data_type = T::data_type();
-
-    let ptr = unsafe { rb_sys::rb_check_typeddata(value.as_raw(), data_type.as_raw()) };
-
-    if ptr.is_null() {
-        return Err(Error::type_error(format!(
-            "expected {}, got {}",
-            T::class_name(),
-            value.class_name().unwrap_or_else(|_| "unknown".to_string())
-        )));
-    }
-
-    // SAFETY: rb_check_typeddata verified this is a T
-    // WARNING: Caller must ensure no aliasing
-    let reference = unsafe { &mut *(ptr as *mut T) };
-    Ok(reference)
+/// - The value is not a TypedData object (`TypeError`)
+/// - The value wraps a different type than `T` (`TypeError`)
+/// - The value is already borrowed by [`get`] or [`get_mut`] (`RuntimeError`)
+pub fn get_mut<T: TypedData>(value: &Value) -> Result<TypedRefMut<'_, T>, Error> {
+    data_cell::<T>(value)?.try_borrow_mut()
 }
 
 #[cfg(all(test, any(feature = "link-ruby", feature = "embed")))]
 mod ruby_tests {
     use super::*;
-    use crate::convert::TryConvert;
+    use crate::convert::{IntoValue, TryConvert};
     use crate::typed_data::{DataType, DataTypeBuilder, TypedData};
     use rb_sys_test_helpers::ruby_test;
 
+    #[derive(Debug)]
     struct TestPoint {
         x: f64,
         y: f64,
@@ -222,7 +219,7 @@ mod ruby_tests {
         let point = TestPoint { x: 1.0, y: 2.0 };
         let wrapped = wrap(&ruby, &object_class, point).unwrap();
 
-        let retrieved: &TestPoint = get(&wrapped).unwrap();
+        let retrieved = get::<TestPoint>(&wrapped).unwrap();
         assert_eq!(retrieved.x, 1.0);
         assert_eq!(retrieved.y, 2.0);
     }
@@ -237,16 +234,17 @@ mod ruby_tests {
         let point = TestPoint { x: 1.0, y: 2.0 };
         let wrapped = wrap(&ruby, &object_class, point).unwrap();
 
-        let retrieved: &mut TestPoint = get_mut(&wrapped).unwrap();
+        let mut retrieved = get_mut::<TestPoint>(&wrapped).unwrap();
         assert_eq!(retrieved.x, 1.0);
         assert_eq!(retrieved.y, 2.0);
 
         // Mutate the value
         retrieved.x = 3.0;
         retrieved.y = 4.0;
+        drop(retrieved);
 
         // Verify mutation
-        let retrieved2: &TestPoint = get(&wrapped).unwrap();
+        let retrieved2 = get::<TestPoint>(&wrapped).unwrap();
         assert_eq!(retrieved2.x, 3.0);
         assert_eq!(retrieved2.y, 4.0);
     }
@@ -266,12 +264,50 @@ mod ruby_tests {
         let wrapped2 = wrap(&ruby, &object_class, point2).unwrap();
 
         // Retrieve and verify both
-        let retrieved1: &TestPoint = get(&wrapped1).unwrap();
+        let retrieved1 = get::<TestPoint>(&wrapped1).unwrap();
         assert_eq!(retrieved1.x, 1.0);
         assert_eq!(retrieved1.y, 2.0);
 
-        let retrieved2: &TestPoint = get(&wrapped2).unwrap();
+        let retrieved2 = get::<TestPoint>(&wrapped2).unwrap();
         assert_eq!(retrieved2.x, 3.0);
         assert_eq!(retrieved2.y, 4.0);
     }
+
+    #[ruby_test]
+    fn test_conflicting_borrows() {
+        // SAFETY: Ruby is initialized by rb_sys_test_helpers
+        let ruby = unsafe { Ruby::get() };
+        let object_class = RClass::try_convert(ruby.class_object()).unwrap();
+        let wrapped = wrap(&ruby, &object_class, TestPoint { x: 1.0, y: 2.0 }).unwrap();
+
+        let shared = get::<TestPoint>(&wrapped).unwrap();
+        let err = get_mut::<TestPoint>(&wrapped).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::RuntimeError.as_value()));
+        assert_eq!(err.message(), "TestPoint is already borrowed");
+        assert_eq!(get::<TestPoint>(&wrapped).unwrap().x, shared.x);
+        drop(shared);
+
+        let exclusive = get_mut::<TestPoint>(&wrapped).unwrap();
+        assert!(get::<TestPoint>(&wrapped).is_err());
+        assert!(get_mut::<TestPoint>(&wrapped).is_err());
+        drop(exclusive);
+        assert!(get_mut::<TestPoint>(&wrapped).is_ok());
+    }
+
+    #[ruby_test]
+    fn test_get_wrong_type() {
+        let err = get::<TestPoint>(&42i64.into_value()).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::TypeError.as_value()));
+        assert_eq!(
+            err.message(),
+            "wrong argument type Integer (expected TestPoint)"
+        );
+
+        let string = Value::nil().funcall_boxed("eval", ("'point'",)).unwrap();
+        let err = get_mut::<TestPoint>(&string).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "wrong argument type String (expected TestPoint)"
+        );
+    }
 }
@@ -1,8 +1,13 @@
 //! Ruby Array type.
 
+use std::cmp::Ordering;
+use std::pin::Pin;
+
+use crate::context::Context;
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::Error;
-use crate::value::{BoxValue, ReprValue, Value};
+use crate::error::{Error, ExceptionClass, protect};
+use crate::types::RString;
+use crate::value::{BoxValue, ReprValue, StackPinned, Value};
 
 /// Ruby Array (heap allocated).
 ///
@@ -15,15 +20,18 @@ use crate::value::{BoxValue, ReprValue, Value};
 /// # Example
 ///
 /// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// use solidus::types::RArray;
 ///
 /// // For heap storage, use new_boxed()
 /// let mut arr = RArray::new_boxed();
-/// arr.push(42i64);
-/// arr.push("hello");
+/// arr.push(42i64)?;
+/// arr.push("hello")?;
 /// assert_eq!(arr.len(), 2);
 ///
 /// // For stack-pinned arrays in methods, use Context::new_array()
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Clone, Debug)]
 #[repr(transparent)]
@@ -130,12 +138,15 @@ impl RArray {
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(1);
-    /// arr.push(2);
+    /// arr.push(1)?;
+    /// arr.push(2)?;
     /// assert_eq!(arr.len(), 2);
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     pub fn len(&self) -> usize {
@@ -148,13 +159,16 @@ impl RArray {
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
     /// assert!(arr.is_empty());
     ///
-    /// arr.push(1);
+    /// arr.push(1)?;
     /// assert!(!arr.is_empty());
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -165,52 +179,61 @@ impl RArray {
     ///
     /// This modifies the array in place.
     ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(42i64);
-    /// arr.push("hello");
+    /// arr.push(42i64)?;
+    /// arr.push("hello")?;
     /// assert_eq!(arr.len(), 2);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn push<T: IntoValue>(&self, value: T) {
-        let val = value.into_value();
-        // SAFETY: self.0 is a valid Ruby array, val is a valid VALUE
-        unsafe {
-            rb_sys::rb_ary_push(self.0.as_raw(), val.as_raw());
-        }
+    pub fn push<T: IntoValue>(&self, value: T) -> Result<(), Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array, val is a valid VALUE
+        protect(|| unsafe { rb_sys::rb_ary_push(ary, val) })?;
+        Ok(())
     }
 
     /// Remove and return the last element of the array.
     ///
     /// Returns `None` if the array is empty.
     ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(1);
-    /// arr.push(2);
+    /// arr.push(1)?;
+    /// arr.push(2)?;
     ///
-    /// let val = arr.pop().unwrap();
+    /// let val = arr.pop()?.unwrap();
     /// assert_eq!(arr.len(), 1);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn pop(&self) -> Option<Value> {
-        if self.is_empty() {
-            return None;
-        }
-
-        // SAFETY: self.0 is a valid Ruby array
-        let val = unsafe { rb_sys::rb_ary_pop(self.0.as_raw()) };
-        // SAFETY: rb_ary_pop returns a valid VALUE (or nil if empty)
-        let value = unsafe { Value::from_raw(val) };
-
-        // rb_ary_pop returns nil if the array was empty
-        if value.is_nil() { None } else { Some(value) }
+    pub fn pop(&self) -> Result<Option<Value>, Error> {
+        let was_empty = self.is_empty();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        let val = protect(|| unsafe { rb_sys::rb_ary_pop(ary) })?;
+        // SAFETY: rb_ary_pop returns a valid VALUE
+        Ok((!was_empty).then(|| unsafe { Value::from_raw(val) }))
     }
 
     /// Get the element at the specified index.
@@ -221,15 +244,18 @@ impl RArray {
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(10);
-    /// arr.push(20);
-    /// arr.push(30);
+    /// arr.push(10)?;
+    /// arr.push(20)?;
+    /// arr.push(30)?;
     ///
     /// let val = arr.entry(1);
     /// let val_neg = arr.entry(-1); // Last element
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn entry(&self, index: isize) -> Value {
         // SAFETY: self.0 is a valid Ruby array, rb_ary_entry handles bounds checking
@@ -243,22 +269,308 @@ impl RArray {
     /// If the index is out of bounds, the array will be extended with `nil` values.
     /// Negative indices count from the end of the array (-1 is the last element).
     ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen, or an `IndexError` if
+    /// a negative index points before the start of the array.
+    ///
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RArray;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.store(0, 42);
-    /// arr.store(1, "hello");
-    /// arr.store(-1, "world"); // Replaces last element
+    /// arr.store(0, 42)?;
+    /// arr.store(1, "hello")?;
+    /// arr.store(-1, "world")?; // Replaces last element
+    /// assert!(arr.store(-3, 0).is_err());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn store<T: IntoValue>(&self, index: isize, value: T) {
-        let val = value.into_value();
-        // SAFETY: self.0 is a valid Ruby array, val is a valid VALUE
-        unsafe {
-            rb_sys::rb_ary_store(self.0.as_raw(), index as _, val.as_raw());
+    pub fn store<T: IntoValue>(&self, index: isize, value: T) -> Result<(), Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array, val is a valid VALUE
+        protect(|| unsafe { rb_sys::rb_ary_store(ary, index as _, val) })
+    }
+
+    /// Remove and return the first element of the array.
+    ///
+    /// Returns `None` if the array is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2]);
+    /// let val = arr.shift()?.unwrap();
+    /// assert_eq!(i64::try_convert(val)?, 1);
+    /// assert_eq!(arr.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shift(&self) -> Result<Option<Value>, Error> {
+        let was_empty = self.is_empty();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        let val = protect(|| unsafe { rb_sys::rb_ary_shift(ary) })?;
+        // SAFETY: rb_ary_shift returns a valid VALUE
+        Ok((!was_empty).then(|| unsafe { Value::from_raw(val) }))
+    }
+
+    /// Insert a value at the start of the array.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[2i64, 3]);
+    /// arr.unshift(1i64)?;
+    /// assert_eq!(arr.to_vec::<i64>()?, vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unshift<T: IntoValue>(&self, value: T) -> Result<(), Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array, val is a valid VALUE
+        protect(|| unsafe {
+            rb_sys::rb_ary_unshift(ary, val);
+        })
+    }
+
+    /// Insert a value before the element at `index`.
+    ///
+    /// Negative indices count from the end of the array, and insert after
+    /// that element (-1 appends). An index past the end extends the array
+    /// with `nil` values, like `Array#insert`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndexError` if a negative index is before the start of
+    /// the array, or a `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 3]);
+    /// arr.insert(1, 2i64)?;
+    /// assert_eq!(arr.to_vec::<i64>()?, vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert<T: IntoValue>(&self, index: isize, value: T) -> Result<(), Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        protect(|| unsafe { rb_sys::rb_ary_modify(ary) })?;
+
+        let len = self.len() as isize;
+        let pos = if index < 0 { index + len + 1 } else { index };
+        if pos < 0 {
+            return Err(Error::new(
+                ExceptionClass::IndexError,
+                format!("index {} too small for array; minimum: -{}", index, len + 1),
+            ));
         }
+
+        // SAFETY: ary is a valid, modifiable Ruby array and val is a valid
+        // VALUE. The elements from pos onwards move up one place, last first.
+        protect(|| unsafe {
+            for i in (pos..len).rev() {
+                rb_sys::rb_ary_store(ary, (i + 1) as _, rb_sys::rb_ary_entry(ary, i as _));
+            }
+            rb_sys::rb_ary_store(ary, pos as _, val);
+        })
+    }
+
+    /// Remove and return the element at `index`.
+    ///
+    /// Negative indices count from the end of the array. Returns `None` if
+    /// the index is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    /// let val = arr.delete_at(1)?.unwrap();
+    /// assert_eq!(i64::try_convert(val)?, 2);
+    /// assert!(arr.delete_at(5)?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_at(&self, index: isize) -> Result<Option<Value>, Error> {
+        let len = self.len() as isize;
+        let in_bounds = if index < 0 {
+            index + len >= 0
+        } else {
+            index < len
+        };
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        let val = protect(|| unsafe { rb_sys::rb_ary_delete_at(ary, index as _) })?;
+        // SAFETY: rb_ary_delete_at returns a valid VALUE
+        Ok(in_bounds.then(|| unsafe { Value::from_raw(val) }))
+    }
+
+    /// Remove all elements from the array.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    pub fn clear(&self) -> Result<(), Error> {
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        protect(|| unsafe {
+            rb_sys::rb_ary_clear(ary);
+        })
+    }
+
+    /// Append the elements of `other` to this array.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if this array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2]);
+    /// let more = RArray::from_slice_boxed(&[3i64, 4]);
+    /// arr.concat(&more)?;
+    /// assert_eq!(arr.to_vec::<i64>()?, vec![1, 2, 3, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn concat(&self, other: &RArray) -> Result<(), Error> {
+        let (ary, other) = (self.0.as_raw(), other.as_raw());
+        // SAFETY: both are valid Ruby arrays
+        protect(|| unsafe {
+            rb_sys::rb_ary_concat(ary, other);
+        })
+    }
+
+    /// Reverse the array in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    pub fn reverse(&self) -> Result<(), Error> {
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        protect(|| unsafe {
+            rb_sys::rb_ary_reverse(ary);
+        })
+    }
+
+    /// Sort the array in place, comparing elements with Ruby's `<=>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if two elements can't be compared, or a
+    /// `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[3i64, 1, 2]);
+    /// arr.sort()?;
+    /// assert_eq!(arr.to_vec::<i64>()?, vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sort(&self) -> Result<(), Error> {
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array
+        protect(|| unsafe {
+            rb_sys::rb_ary_sort_bang(ary);
+        })
+    }
+
+    /// Sort the array in place with a Rust comparator.
+    ///
+    /// The sort is stable. The elements are sorted in a copy of the array,
+    /// which then replaces its contents, so the array is unchanged if
+    /// `compare` panics.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 3, 2]);
+    /// // Descending
+    /// arr.sort_by(|a, b| {
+    ///     let (a, b) = (i64::try_convert(a.clone()).unwrap(), i64::try_convert(b.clone()).unwrap());
+    ///     b.cmp(&a)
+    /// })?;
+    /// assert_eq!(arr.to_vec::<i64>()?, vec![3, 2, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sort_by<F>(&self, mut compare: F) -> Result<(), Error>
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        let ary = self.0.as_raw();
+        // Fail early rather than after sorting
+        // SAFETY: ary is a valid Ruby array
+        protect(|| unsafe { rb_sys::rb_ary_modify(ary) })?;
+
+        // The copy keeps the elements alive while `compare` runs, even if it
+        // modifies the array
+        // SAFETY: ary is a valid Ruby array, and the copy is boxed right away
+        let copy = BoxValue::new(unsafe {
+            RArray::from_value_unchecked(Value::from_raw(rb_sys::rb_ary_dup(ary)))
+        });
+        let mut elements: Vec<Value> = copy.iter().collect();
+        elements.sort_by(|a, b| compare(a, b));
+
+        // Value is #[repr(transparent)] over VALUE
+        let ptr = elements.as_ptr() as *const rb_sys::VALUE;
+        let len = elements.len();
+        // SAFETY: ptr points to len valid VALUEs, all kept alive by the copy
+        protect(|| unsafe {
+            let sorted = rb_sys::rb_ary_new_from_values(len as _, ptr);
+            rb_sys::rb_ary_replace(ary, sorted);
+        })
     }
 
     /// Iterate over the array elements.
@@ -266,12 +578,7 @@ impl RArray {
     /// The closure is called for each element in the array. If the closure
     /// returns an error, iteration stops and the error is returned.
     ///
-    /// # Why not Iterator?
-    ///
-    /// We don't implement Rust's `Iterator` trait because it would be unsafe.
-    /// Between iterator calls, Ruby code could run (if the closure calls back
-    /// into Ruby), potentially triggering GC which could modify or move the array.
-    /// By using a closure, we maintain control over when Ruby code can execute.
+    /// This is [`iter`](Self::iter) for closures that can fail.
     ///
     /// # Example
     ///
@@ -281,9 +588,9 @@ impl RArray {
     /// use solidus::convert::TryConvert;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(1);
-    /// arr.push(2);
-    /// arr.push(3);
+    /// arr.push(1)?;
+    /// arr.push(2)?;
+    /// arr.push(3)?;
     ///
     /// let mut sum = 0i64;
     /// arr.each(|val| {
@@ -299,14 +606,44 @@ impl RArray {
     where
         F: FnMut(Value) -> Result<(), Error>,
     {
-        let len = self.len();
-        for i in 0..len {
-            let val = self.entry(i as isize);
+        for val in self {
             f(val)?;
         }
         Ok(())
     }
 
+    /// Iterate over the array elements.
+    ///
+    /// Elements are read from the array one at a time, without copying it.
+    /// Like Ruby's `Array#each`, the iterator sees changes made to the array
+    /// while iterating: it stops at the array's current end, so elements
+    /// pushed during iteration are visited and removed ones are not.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    ///
+    /// let mut sum = 0i64;
+    /// for val in arr.iter() {
+    ///     sum += i64::try_convert(val)?;
+    /// }
+    /// assert_eq!(sum, 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> ArrayIter<'_> {
+        ArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+
     /// Create a Ruby array from a Rust slice.
     ///
     /// # Safety
@@ -330,7 +667,10 @@ impl RArray {
         // SAFETY: Caller ensures the returned value is properly handled
         let arr = unsafe { Self::with_capacity(slice.len()) };
         for &item in slice {
-            arr.push(item);
+            // SAFETY: the array is new, so it is not frozen and push cannot raise
+            unsafe {
+                rb_sys::rb_ary_push(arr.as_raw(), item.into_value().as_raw());
+            }
         }
         arr
     }
@@ -365,9 +705,9 @@ impl RArray {
     /// use solidus::convert::TryConvert;
     ///
     /// let mut arr = RArray::new_boxed();
-    /// arr.push(1);
-    /// arr.push(2);
-    /// arr.push(3);
+    /// arr.push(1)?;
+    /// arr.push(2)?;
+    /// arr.push(3)?;
     ///
     /// let vec: Vec<i64> = arr.to_vec()?;
     /// assert_eq!(vec, vec![1, 2, 3]);
@@ -383,6 +723,340 @@ impl RArray {
         }
         Ok(vec)
     }
+
+    /// Check whether the array contains `value`, comparing with Ruby's `==`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a call to `==` raises.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    /// assert!(arr.includes(2i64)?);
+    /// assert!(!arr.includes("2")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn includes<T: IntoValue>(&self, value: T) -> Result<bool, Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array, val is a valid VALUE
+        let found = protect(|| unsafe { rb_sys::rb_ary_includes(ary, val) })?;
+        // SAFETY: rb_ary_includes returns true or false
+        Ok(unsafe { Value::from_raw(found) }.is_true())
+    }
+
+    /// Find the index of the first element equal to `value`, comparing with
+    /// Ruby's `==`, like `Array#index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a call to `==` raises.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    /// assert_eq!(arr.index_of(3i64)?, Some(2));
+    /// assert_eq!(arr.index_of(4i64)?, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn index_of<T: IntoValue>(&self, value: T) -> Result<Option<usize>, Error> {
+        let val = value.into_value().as_raw();
+        let ary = self.0.as_raw();
+        // SAFETY: ary is a valid Ruby array, val is a valid VALUE. The length
+        // is checked on every step, since `==` may modify the array.
+        protect(|| unsafe {
+            let mut i = 0;
+            while i < rb_sys::RARRAY_LEN(ary) {
+                let equal = rb_sys::rb_equal(rb_sys::rb_ary_entry(ary, i), val);
+                if Value::from_raw(equal).is_true() {
+                    return Some(i as usize);
+                }
+                i += 1;
+            }
+            None
+        })
+    }
+
+    /// Get `len` elements starting at `start` as a new array, stored in the
+    /// Context.
+    ///
+    /// This is `array[start, len]`: the result is shorter if the array ends
+    /// first, and `None` if `start` is past the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn middle<'ctx>(ctx: &'ctx Context<'ctx>, arr: RArray) -> Result<Option<Pin<&'ctx StackPinned<RArray>>>, Error> {
+    ///     arr.subseq(ctx, 1, arr.len().saturating_sub(2))
+    /// }
+    /// ```
+    pub fn subseq<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        start: usize,
+        len: usize,
+    ) -> Result<Option<Pin<&'ctx StackPinned<RArray>>>, Error> {
+        match self.subseq_raw(start, len) {
+            Some(sub) => Ok(Some(ctx.pin_value(sub)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get `len` elements starting at `start` as a new, heap-allocated array.
+    ///
+    /// Returns `None` if `start` is past the end; see [`subseq`](Self::subseq).
+    pub fn subseq_boxed(&self, start: usize, len: usize) -> Option<BoxValue<RArray>> {
+        self.subseq_raw(start, len).map(BoxValue::new)
+    }
+
+    fn subseq_raw(&self, start: usize, len: usize) -> Option<RArray> {
+        // Limit both to the length, which doesn't change the result, so they
+        // fit in a C long
+        let total = self.len();
+        if start > total {
+            return None;
+        }
+        let len = len.min(total);
+        // SAFETY: self.0 is a valid Ruby array; rb_ary_subseq clamps len and
+        // returns nil if start is out of range
+        let val = unsafe {
+            Value::from_raw(rb_sys::rb_ary_subseq(self.0.as_raw(), start as _, len as _))
+        };
+        // SAFETY: anything but nil is a new array
+        (!val.is_nil()).then(|| unsafe { RArray::from_value_unchecked(val) })
+    }
+
+    /// Join the elements into a string with `separator`, stored in the
+    /// Context.
+    ///
+    /// Elements are converted with `to_s`, and nested arrays are joined
+    /// recursively, like `Array#join`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if converting an element raises, if the array contains
+    /// itself, or if the Context has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn csv_line<'ctx>(ctx: &'ctx Context<'ctx>, fields: RArray) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+    ///     fields.join(ctx, ",")
+    /// }
+    /// ```
+    pub fn join<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        separator: &str,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let joined = self.join_raw(separator)?;
+        Ok(ctx.pin_value(joined)?)
+    }
+
+    /// Join the elements into a heap-allocated string with `separator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if converting an element raises, or if the array
+    /// contains itself.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    /// assert_eq!(arr.join_boxed("-")?.to_string()?, "1-2-3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join_boxed(&self, separator: &str) -> Result<BoxValue<RString>, Error> {
+        self.join_raw(separator).map(BoxValue::new)
+    }
+
+    fn join_raw(&self, separator: &str) -> Result<RString, Error> {
+        let ary = self.0.as_raw();
+        // SAFETY: rb_utf8_str_new copies the separator
+        let sep = unsafe {
+            rb_sys::rb_utf8_str_new(
+                separator.as_ptr() as *const std::os::raw::c_char,
+                separator.len() as _,
+            )
+        };
+        // SAFETY: ary is a valid Ruby array and sep a valid string
+        let val = protect(|| unsafe { rb_sys::rb_ary_join(ary, sep) })?;
+        // SAFETY: rb_ary_join returns a new string
+        Ok(unsafe { RString::from_value_unchecked(Value::from_raw(val)) })
+    }
+
+    /// Copy the array, storing the copy in the Context.
+    ///
+    /// The copy is shallow and never frozen, like `Array#dup`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the copy.
+    pub fn dup<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+        Ok(ctx.pin_value(self.dup_raw())?)
+    }
+
+    /// Copy the array, boxed for heap storage.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2]);
+    /// let copy = arr.dup_boxed();
+    /// copy.push(3i64)?;
+    /// assert_eq!(arr.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dup_boxed(&self) -> BoxValue<RArray> {
+        BoxValue::new(self.dup_raw())
+    }
+
+    fn dup_raw(&self) -> RArray {
+        // SAFETY: self.0 is a valid Ruby array and rb_ary_dup returns a new one
+        unsafe {
+            RArray::from_value_unchecked(Value::from_raw(rb_sys::rb_ary_dup(self.0.as_raw())))
+        }
+    }
+
+    /// Freeze the array, so that modifying it raises `FrozenError`.
+    ///
+    /// Only the array itself is frozen, not its elements.
+    pub fn freeze(&self) {
+        // SAFETY: self.0 is a valid VALUE
+        unsafe {
+            rb_sys::rb_obj_freeze(self.0.as_raw());
+        }
+    }
+
+    /// Check whether the array is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RArray;
+    ///
+    /// let arr = RArray::new_boxed();
+    /// assert!(!arr.is_frozen());
+    /// arr.freeze();
+    /// assert!(arr.is_frozen());
+    /// assert!(arr.clear().is_err());
+    /// ```
+    pub fn is_frozen(&self) -> bool {
+        // SAFETY: self.0 is a valid VALUE
+        unsafe { Value::from_raw(rb_sys::rb_obj_frozen_p(self.0.as_raw())) }.is_truthy()
+    }
+
+    /// Borrow the elements of the array as a slice, without copying.
+    ///
+    /// This is the fastest way to read a whole array from Rust.
+    ///
+    /// # Safety
+    ///
+    /// The slice points into the array's own storage, which Ruby may move or
+    /// free when the array is modified or when Ruby code runs. The caller
+    /// must not modify the array, call any Ruby method or create any Ruby
+    /// object while the slice is in use.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RArray;
+    /// use solidus::value::ReprValue;
+    ///
+    /// let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+    /// // SAFETY: nothing touches Ruby while the slice is in use
+    /// let nil_count = unsafe { arr.as_slice() }.iter().filter(|v| v.is_nil()).count();
+    /// assert_eq!(nil_count, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn as_slice(&self) -> &[Value] {
+        let len = self.len();
+        if len == 0 {
+            return &[];
+        }
+        // SAFETY: the array has len elements at RARRAY_CONST_PTR, and Value is
+        // #[repr(transparent)] over VALUE; the caller keeps them in place
+        unsafe {
+            let ptr = rb_sys::RARRAY_CONST_PTR(self.0.as_raw()) as *const Value;
+            std::slice::from_raw_parts(ptr, len)
+        }
+    }
+}
+
+/// An iterator over the elements of an [`RArray`], created by
+/// [`RArray::iter`].
+///
+/// The array is borrowed, so it stays alive for as long as the iterator.
+#[derive(Clone, Debug)]
+pub struct ArrayIter<'a> {
+    array: &'a RArray,
+    index: usize,
+}
+
+impl Iterator for ArrayIter<'_> {
+    type Item = Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<Value> {
+        // Check the current length every time, since the array may have
+        // changed since the last element
+        if self.index >= self.array.len() {
+            return None;
+        }
+        let val = self.array.entry(self.index as isize);
+        self.index += 1;
+        Some(val)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The array may shrink or grow before the next element, so neither
+        // bound can be promised
+        (0, None)
+    }
+}
+
+impl<'a> IntoIterator for &'a RArray {
+    type Item = Value;
+    type IntoIter = ArrayIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> ArrayIter<'a> {
+        self.iter()
+    }
 }
 
 impl ReprValue for RArray {
@@ -460,26 +1134,26 @@ mod tests {
     #[ruby_test]
     fn test_rarray_push() {
         let arr = RArray::new_boxed();
-        arr.push(42i64);
+        arr.push(42i64).unwrap();
         assert_eq!(arr.len(), 1);
         assert!(!arr.is_empty());
 
-        arr.push(100i64);
+        arr.push(100i64).unwrap();
         assert_eq!(arr.len(), 2);
     }
 
     #[ruby_test]
     fn test_rarray_pop() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
-        arr.push(2i64);
-        arr.push(3i64);
+        arr.push(1i64).unwrap();
+        arr.push(2i64).unwrap();
+        arr.push(3i64).unwrap();
 
-        let val = arr.pop().unwrap();
+        let val = arr.pop().unwrap().unwrap();
         assert_eq!(i64::try_convert(val).unwrap(), 3);
         assert_eq!(arr.len(), 2);
 
-        let val = arr.pop().unwrap();
+        let val = arr.pop().unwrap().unwrap();
         assert_eq!(i64::try_convert(val).unwrap(), 2);
         assert_eq!(arr.len(), 1);
     }
@@ -487,15 +1161,15 @@ mod tests {
     #[ruby_test]
     fn test_rarray_pop_empty() {
         let arr = RArray::new_boxed();
-        assert!(arr.pop().is_none());
+        assert!(arr.pop().unwrap().is_none());
     }
 
     #[ruby_test]
     fn test_rarray_entry() {
         let arr = RArray::new_boxed();
-        arr.push(10i64);
-        arr.push(20i64);
-        arr.push(30i64);
+        arr.push(10i64).unwrap();
+        arr.push(20i64).unwrap();
+        arr.push(30i64).unwrap();
 
         let val = arr.entry(0);
         assert_eq!(i64::try_convert(val).unwrap(), 10);
@@ -510,9 +1184,9 @@ mod tests {
     #[ruby_test]
     fn test_rarray_entry_negative() {
         let arr = RArray::new_boxed();
-        arr.push(10i64);
-        arr.push(20i64);
-        arr.push(30i64);
+        arr.push(10i64).unwrap();
+        arr.push(20i64).unwrap();
+        arr.push(30i64).unwrap();
 
         let val = arr.entry(-1);
         assert_eq!(i64::try_convert(val).unwrap(), 30);
@@ -527,7 +1201,7 @@ mod tests {
     #[ruby_test]
     fn test_rarray_entry_out_of_bounds() {
         let arr = RArray::new_boxed();
-        arr.push(10i64);
+        arr.push(10i64).unwrap();
 
         let val = arr.entry(5);
         assert!(val.is_nil());
@@ -539,13 +1213,13 @@ mod tests {
     #[ruby_test]
     fn test_rarray_store() {
         let arr = RArray::new_boxed();
-        arr.store(0, 42i64);
+        arr.store(0, 42i64).unwrap();
         assert_eq!(arr.len(), 1);
 
         let val = arr.entry(0);
         assert_eq!(i64::try_convert(val).unwrap(), 42);
 
-        arr.store(0, 99i64);
+        arr.store(0, 99i64).unwrap();
         let val = arr.entry(0);
         assert_eq!(i64::try_convert(val).unwrap(), 99);
     }
@@ -553,7 +1227,7 @@ mod tests {
     #[ruby_test]
     fn test_rarray_store_extends() {
         let arr = RArray::new_boxed();
-        arr.store(5, 42i64);
+        arr.store(5, 42i64).unwrap();
         assert_eq!(arr.len(), 6);
 
         // Elements 0-4 should be nil
@@ -568,21 +1242,25 @@ mod tests {
     #[ruby_test]
     fn test_rarray_store_negative() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
-        arr.push(2i64);
-        arr.push(3i64);
+        arr.push(1i64).unwrap();
+        arr.push(2i64).unwrap();
+        arr.push(3i64).unwrap();
 
-        arr.store(-1, 99i64);
+        arr.store(-1, 99i64).unwrap();
         let val = arr.entry(-1);
         assert_eq!(i64::try_convert(val).unwrap(), 99);
+
+        // Storing before the start of the array raises IndexError
+        assert!(arr.store(-4, 0i64).is_err());
+        assert_eq!(arr.len(), 3);
     }
 
     #[ruby_test]
     fn test_rarray_each() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
-        arr.push(2i64);
-        arr.push(3i64);
+        arr.push(1i64).unwrap();
+        arr.push(2i64).unwrap();
+        arr.push(3i64).unwrap();
 
         let mut sum = 0i64;
         arr.each(|val| {
@@ -610,14 +1288,53 @@ mod tests {
     #[ruby_test]
     fn test_rarray_each_error() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
-        arr.push(2i64);
-        arr.push(3i64);
+        arr.push(1i64).unwrap();
+        arr.push(2i64).unwrap();
+        arr.push(3i64).unwrap();
 
         let result = arr.each(|_| Err(Error::type_error("test error")));
         assert!(result.is_err());
     }
 
+    #[ruby_test]
+    fn test_rarray_iter() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+        let values: Vec<i64> = arr.iter().map(|v| i64::try_convert(v).unwrap()).collect();
+        assert_eq!(values, [1, 2, 3]);
+
+        let mut count = 0;
+        for _ in &*arr {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(RArray::new_boxed().iter().next(), None);
+    }
+
+    #[ruby_test]
+    fn test_rarray_iter_sees_modification() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+        let mut seen = Vec::new();
+        for val in arr.iter() {
+            let n = i64::try_convert(val).unwrap();
+            if n == 1 {
+                arr.push(4i64).unwrap();
+            }
+            seen.push(n);
+        }
+        assert_eq!(seen, [1, 2, 3, 4]);
+
+        let mut seen = Vec::new();
+        for val in arr.iter() {
+            let n = i64::try_convert(val).unwrap();
+            if n == 1 {
+                arr.pop().unwrap();
+                arr.pop().unwrap();
+            }
+            seen.push(n);
+        }
+        assert_eq!(seen, [1, 2]);
+    }
+
     #[ruby_test]
     fn test_rarray_from_slice_boxed() {
         let slice = &[1i64, 2, 3, 4, 5];
@@ -633,9 +1350,9 @@ mod tests {
     #[ruby_test]
     fn test_rarray_to_vec() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
-        arr.push(2i64);
-        arr.push(3i64);
+        arr.push(1i64).unwrap();
+        arr.push(2i64).unwrap();
+        arr.push(3i64).unwrap();
 
         let vec: Vec<i64> = arr.to_vec().unwrap();
         assert_eq!(vec, vec![1, 2, 3]);
@@ -644,7 +1361,7 @@ mod tests {
     #[ruby_test]
     fn test_rarray_try_convert() {
         let arr = RArray::new_boxed();
-        arr.push(1i64);
+        arr.push(1i64).unwrap();
 
         let val = arr.as_value();
         let converted = RArray::try_convert(val).unwrap();
@@ -680,9 +1397,9 @@ mod tests {
         use crate::types::RString;
 
         let arr = RArray::new_boxed();
-        arr.push(42i64);
-        arr.push(RString::new_boxed("hello").as_value());
-        arr.push(true);
+        arr.push(42i64).unwrap();
+        arr.push(RString::new_boxed("hello").as_value()).unwrap();
+        arr.push(true).unwrap();
 
         assert_eq!(arr.len(), 3);
 
@@ -700,12 +1417,12 @@ mod tests {
     #[ruby_test]
     fn test_rarray_nested() {
         let inner = RArray::new_boxed();
-        inner.push(1i64);
-        inner.push(2i64);
+        inner.push(1i64).unwrap();
+        inner.push(2i64).unwrap();
 
         let outer = RArray::new_boxed();
-        outer.push(inner.as_value());
-        outer.push(3i64);
+        outer.push(inner.as_value()).unwrap();
+        outer.push(3i64).unwrap();
 
         assert_eq!(outer.len(), 2);
 
@@ -713,4 +1430,147 @@ mod tests {
         let inner_arr = RArray::try_convert(val).unwrap();
         assert_eq!(inner_arr.len(), 2);
     }
+
+    fn ints(arr: &RArray) -> Vec<i64> {
+        arr.to_vec().unwrap()
+    }
+
+    #[ruby_test]
+    fn test_rarray_shift_and_unshift() {
+        let arr = RArray::from_slice_boxed(&[2i64, 3]);
+        arr.unshift(1i64).unwrap();
+        assert_eq!(ints(&arr), [1, 2, 3]);
+
+        let val = arr.shift().unwrap().unwrap();
+        assert_eq!(i64::try_convert(val).unwrap(), 1);
+        arr.clear().unwrap();
+        assert!(arr.shift().unwrap().is_none());
+
+        // A nil element is still an element
+        arr.push(Value::nil()).unwrap();
+        assert!(arr.shift().unwrap().unwrap().is_nil());
+    }
+
+    #[ruby_test]
+    fn test_rarray_insert_and_delete_at() {
+        let arr = RArray::from_slice_boxed(&[1i64, 3]);
+        arr.insert(1, 2i64).unwrap();
+        arr.insert(-1, 4i64).unwrap();
+        assert_eq!(ints(&arr), [1, 2, 3, 4]);
+        let err = arr.insert(-10, 0i64).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::IndexError.as_value()));
+        assert_eq!(err.message(), "index -10 too small for array; minimum: -5");
+
+        let other = RArray::from_slice_boxed(&[2i64, 4]);
+        other.insert(0, 1i64).unwrap();
+        other.insert(-2, 3i64).unwrap();
+        other.insert(6, 7i64).unwrap();
+        assert_eq!(other.len(), 7);
+        assert_eq!(ints(&other.subseq_boxed(0, 4).unwrap()), [1, 2, 3, 4]);
+        assert!(other.entry(4).is_nil() && other.entry(5).is_nil());
+        assert_eq!(i64::try_convert(other.entry(6)).unwrap(), 7);
+
+        let val = arr.delete_at(-1).unwrap().unwrap();
+        assert_eq!(i64::try_convert(val).unwrap(), 4);
+        let val = arr.delete_at(0).unwrap().unwrap();
+        assert_eq!(i64::try_convert(val).unwrap(), 1);
+        assert!(arr.delete_at(2).unwrap().is_none());
+        assert!(arr.delete_at(-3).unwrap().is_none());
+        assert_eq!(ints(&arr), [2, 3]);
+    }
+
+    #[ruby_test]
+    fn test_rarray_concat_reverse_clear() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2]);
+        arr.concat(&RArray::from_slice_boxed(&[3i64])).unwrap();
+        arr.reverse().unwrap();
+        assert_eq!(ints(&arr), [3, 2, 1]);
+        arr.clear().unwrap();
+        assert!(arr.is_empty());
+    }
+
+    #[ruby_test]
+    fn test_rarray_sort() {
+        let arr = RArray::from_slice_boxed(&[3i64, 1, 2]);
+        arr.sort().unwrap();
+        assert_eq!(ints(&arr), [1, 2, 3]);
+
+        arr.sort_by(|a, b| {
+            let (a, b) = (
+                i64::try_convert(a.clone()).unwrap(),
+                i64::try_convert(b.clone()).unwrap(),
+            );
+            b.cmp(&a)
+        })
+        .unwrap();
+        assert_eq!(ints(&arr), [3, 2, 1]);
+
+        arr.push("mixed").unwrap();
+        let err = arr.sort().unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+    }
+
+    #[ruby_test]
+    fn test_rarray_includes_and_index_of() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+        assert!(arr.includes(2i64).unwrap());
+        assert!(arr.includes(2.0f64).unwrap());
+        assert!(!arr.includes("2").unwrap());
+        assert_eq!(arr.index_of(3i64).unwrap(), Some(2));
+        assert_eq!(arr.index_of(4i64).unwrap(), None);
+    }
+
+    #[ruby_test]
+    fn test_rarray_subseq_join_dup() {
+        let ctx: Context<'_> = Context::default();
+        let arr = RArray::from_slice_boxed(&[1i64, 2, 3, 4]);
+
+        let sub = arr.subseq(&ctx, 1, 2).unwrap().unwrap();
+        assert_eq!(ints(sub.get()), [2, 3]);
+        assert_eq!(ints(&arr.subseq_boxed(3, 10).unwrap()), [4]);
+        assert!(arr.subseq_boxed(4, 1).unwrap().is_empty());
+        assert!(arr.subseq_boxed(5, 1).is_none());
+        assert_eq!(ints(&arr.subseq_boxed(1, usize::MAX).unwrap()), [2, 3, 4]);
+        assert!(arr.subseq_boxed(usize::MAX, 1).is_none());
+
+        let joined = arr.join(&ctx, ", ").unwrap();
+        assert_eq!(joined.get().to_string().unwrap(), "1, 2, 3, 4");
+
+        let copy = arr.dup(&ctx).unwrap();
+        copy.get().push(5i64).unwrap();
+        assert_eq!(arr.len(), 4);
+        assert_eq!(copy.get().len(), 5);
+    }
+
+    #[ruby_test]
+    fn test_rarray_freeze() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2]);
+        assert!(!arr.is_frozen());
+        arr.freeze();
+        assert!(arr.is_frozen());
+
+        let err = arr.unshift(0i64).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::FrozenError.as_value()));
+        assert!(arr.shift().is_err());
+        assert!(arr.push(3i64).is_err());
+        assert!(arr.insert(0, 0i64).is_err());
+        assert!(arr.pop().is_err());
+        assert!(arr.store(0, 0i64).is_err());
+        assert!(arr.clear().is_err());
+        assert!(arr.sort_by(|_, _| Ordering::Equal).is_err());
+        assert_eq!(ints(&arr), [1, 2]);
+        assert!(!arr.dup_boxed().is_frozen());
+    }
+
+    #[ruby_test]
+    fn test_rarray_as_slice() {
+        let arr = RArray::from_slice_boxed(&[1i64, 2, 3]);
+        let sum: i64 = unsafe { arr.as_slice() }
+            .iter()
+            .map(|v| i64::try_convert(v.clone()).unwrap())
+            .sum();
+        assert_eq!(sum, 6);
+        let empty = RArray::new_boxed();
+        assert!(unsafe { empty.as_slice() }.is_empty());
+    }
 }
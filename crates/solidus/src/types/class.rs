@@ -1,7 +1,7 @@
 //! Ruby Class type.
 
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::Error;
+use crate::error::{Error, protect};
 use crate::value::{ReprValue, Value};
 
 /// Ruby Class.
@@ -29,13 +29,8 @@ pub struct RClass(Value);
 impl RClass {
     /// Get a class by name.
     ///
-    /// Returns `None` if the class doesn't exist.
-    ///
-    /// # Known Issues
-    ///
-    /// Currently, there's an issue with exception handling when looking up
-    /// non-existent classes that causes tests to hang. Use only with known
-    /// class names for now. This will be fixed in a future update.
+    /// Returns `None` if the class doesn't exist or the name refers to
+    /// something other than a class.
     ///
     /// # Example
     ///
@@ -49,25 +44,9 @@ impl RClass {
         // Convert to C string
         let c_name = std::ffi::CString::new(name).ok()?;
 
-        // SAFETY: rb_path2class looks up a class by name and may raise an exception
-        // If it raises, we catch it by checking rb_errinfo()
-        let val = unsafe {
-            // Save the current exception state
-            let old_errinfo = rb_sys::rb_errinfo();
-
-            // Try to get the class
-            let result = rb_sys::rb_path2class(c_name.as_ptr());
-
-            // Check if an exception was raised
-            let new_errinfo = rb_sys::rb_errinfo();
-            if new_errinfo != old_errinfo {
-                // Exception was raised, clear it and return None
-                rb_sys::rb_set_errinfo(rb_sys::Qnil.into());
-                return None;
-            }
-
-            result
-        };
+        // SAFETY: rb_path2class looks up a class by name and raises if it
+        // doesn't exist; protect turns that into an Err
+        let val = protect(|| unsafe { rb_sys::rb_path2class(c_name.as_ptr()) }).ok()?;
 
         if val == rb_sys::Qnil.into() {
             None
@@ -180,7 +159,6 @@ mod tests {
     }
 
     #[ruby_test]
-    #[ignore] // TODO: Fix exception handling in from_name
     fn test_rclass_from_name_missing() {
         let missing = RClass::from_name("NonExistentClass123");
         assert!(missing.is_none());
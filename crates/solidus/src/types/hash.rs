@@ -1,10 +1,14 @@
 //! Ruby Hash type.
 
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
 
+use crate::context::Context;
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::Error;
-use crate::value::{BoxValue, ReprValue, Value};
+use crate::error::{Error, protect};
+use crate::types::{RArray, RProc};
+use crate::value::{BoxValue, ReprValue, StackPinned, Value};
 
 /// Ruby Hash (heap allocated).
 ///
@@ -17,14 +21,17 @@ use crate::value::{BoxValue, ReprValue, Value};
 /// # Example
 ///
 /// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// use solidus::types::RHash;
 ///
 /// // For heap storage, use new_boxed()
 /// let mut hash = RHash::new_boxed();
-/// hash.insert("key", "value");
+/// hash.insert("key", "value")?;
 /// assert_eq!(hash.len(), 1);
 ///
 /// // For stack-pinned hashes in methods, use Context::new_hash()
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Clone, Debug)]
 #[repr(transparent)]
@@ -91,17 +98,66 @@ impl RHash {
         unsafe { BoxValue::new(Self::new_internal()) }
     }
 
+    /// Create a new Ruby hash with room for `capacity` pairs.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the returned value is:
+    /// - Pinned on the stack with `pin_on_stack!`, OR
+    /// - Immediately boxed with `.into_box()`, OR
+    /// - Immediately returned to Ruby
+    ///
+    /// For safe alternatives, use:
+    /// - `RHash::with_capacity_boxed()` for heap storage
+    /// - `Context::new_hash_with_capacity()` for stack-pinned hashes in methods
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RHash;
+    ///
+    /// // SAFETY: Value is immediately returned to Ruby
+    /// let hash = unsafe { RHash::with_capacity(16) };
+    /// ```
+    pub unsafe fn with_capacity(capacity: usize) -> Self {
+        // SAFETY: rb_hash_new_capa creates a new Ruby hash
+        let val = unsafe { rb_sys::rb_hash_new_capa(capacity as _) };
+        // SAFETY: rb_hash_new_capa returns a valid VALUE
+        RHash(unsafe { Value::from_raw(val) })
+    }
+
+    /// Create a new Ruby hash with room for `capacity` pairs, boxed for heap
+    /// storage.
+    ///
+    /// This is safe because the value is immediately registered with Ruby's GC.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RHash;
+    ///
+    /// let boxed = RHash::with_capacity_boxed(16);
+    /// assert!(boxed.is_empty());
+    /// ```
+    pub fn with_capacity_boxed(capacity: usize) -> BoxValue<Self> {
+        // SAFETY: We immediately box and register with GC
+        unsafe { BoxValue::new(Self::with_capacity(capacity)) }
+    }
+
     /// Get the number of key-value pairs in the hash.
     ///
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RHash;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("a", 1);
-    /// hash.insert("b", 2);
+    /// hash.insert("a", 1)?;
+    /// hash.insert("b", 2)?;
     /// assert_eq!(hash.len(), 2);
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     pub fn len(&self) -> usize {
@@ -114,13 +170,16 @@ impl RHash {
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RHash;
     ///
     /// let mut hash = RHash::new_boxed();
     /// assert!(hash.is_empty());
     ///
-    /// hash.insert("key", "value");
+    /// hash.insert("key", "value")?;
     /// assert!(!hash.is_empty());
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -134,7 +193,12 @@ impl RHash {
     /// # Note
     ///
     /// This method returns `None` for both missing keys and keys with `nil` values.
-    /// If you need to distinguish between these cases, use Ruby's `Hash#key?` method.
+    /// If you need to distinguish between these cases, use [`fetch`](Self::fetch)
+    /// or [`has_key`](Self::has_key).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the key's `#hash` or `#eql?` raises.
     ///
     /// # Example
     ///
@@ -144,25 +208,25 @@ impl RHash {
     /// use solidus::convert::TryConvert;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("key", 42i64);
+    /// hash.insert("key", 42i64)?;
     ///
-    /// let val = hash.get("key").unwrap();
+    /// let val = hash.get("key")?.unwrap();
     /// assert_eq!(i64::try_convert(val)?, 42);
     ///
-    /// assert!(hash.get("missing").is_none());
+    /// assert!(hash.get("missing")?.is_none());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get<K: IntoValue>(&self, key: K) -> Option<Value> {
-        let key_val = key.into_value();
-        // SAFETY: self.0 is a valid Ruby hash, key_val is a valid VALUE
-        let val = unsafe { rb_sys::rb_hash_lookup(self.0.as_raw(), key_val.as_raw()) };
+    pub fn get<K: IntoValue>(&self, key: K) -> Result<Option<Value>, Error> {
+        let key = key.into_value().as_raw();
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash, key is a valid VALUE
+        let val = protect(|| unsafe { rb_sys::rb_hash_lookup(hash, key) })?;
         // SAFETY: rb_hash_lookup returns a valid VALUE or Qnil
         let value = unsafe { Value::from_raw(val) };
 
         // rb_hash_lookup returns Qnil if the key doesn't exist
-        // Note: This means we can't distinguish between a nil value and missing key
-        if value.is_nil() { None } else { Some(value) }
+        Ok(if value.is_nil() { None } else { Some(value) })
     }
 
     /// Insert or update a key-value pair.
@@ -170,32 +234,45 @@ impl RHash {
     /// This modifies the hash in place. If the key already exists, its value
     /// is updated. Otherwise, a new key-value pair is added.
     ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen, or the error raised by
+    /// the key's `#hash` or `#eql?`.
+    ///
     /// # Example
     ///
     /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use solidus::types::RHash;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("name", "Alice");
-    /// hash.insert("age", 30i64);
+    /// hash.insert("name", "Alice")?;
+    /// hash.insert("age", 30i64)?;
     /// assert_eq!(hash.len(), 2);
     ///
-    /// hash.insert("age", 31i64); // Update existing key
+    /// hash.insert("age", 31i64)?; // Update existing key
     /// assert_eq!(hash.len(), 2);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn insert<K: IntoValue, V: IntoValue>(&self, key: K, value: V) {
-        let key_val = key.into_value();
-        let val = value.into_value();
-        // SAFETY: self.0 is a valid Ruby hash, both VALUES are valid
-        unsafe {
-            rb_sys::rb_hash_aset(self.0.as_raw(), key_val.as_raw(), val.as_raw());
-        }
+    pub fn insert<K: IntoValue, V: IntoValue>(&self, key: K, value: V) -> Result<(), Error> {
+        let key = key.into_value().as_raw();
+        let val = value.into_value().as_raw();
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash, both VALUEs are valid
+        protect(|| unsafe { rb_sys::rb_hash_aset(hash, key, val) })?;
+        Ok(())
     }
 
     /// Delete a key-value pair and return the value.
     ///
     /// Returns `None` if the key doesn't exist.
     ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen, or the error raised by
+    /// the key's `#hash` or `#eql?`.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -204,25 +281,405 @@ impl RHash {
     /// use solidus::convert::TryConvert;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("key", 42i64);
+    /// hash.insert("key", 42i64)?;
     ///
-    /// let val = hash.delete("key").unwrap();
+    /// let val = hash.delete("key")?.unwrap();
     /// assert_eq!(i64::try_convert(val)?, 42);
     /// assert_eq!(hash.len(), 0);
     ///
-    /// assert!(hash.delete("key").is_none());
+    /// assert!(hash.delete("key")?.is_none());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn delete<K: IntoValue>(&self, key: K) -> Option<Value> {
-        let key_val = key.into_value();
-        // SAFETY: self.0 is a valid Ruby hash, key_val is a valid VALUE
-        let val = unsafe { rb_sys::rb_hash_delete(self.0.as_raw(), key_val.as_raw()) };
+    pub fn delete<K: IntoValue>(&self, key: K) -> Result<Option<Value>, Error> {
+        let key = key.into_value().as_raw();
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash, key is a valid VALUE
+        let val = protect(|| unsafe { rb_sys::rb_hash_delete(hash, key) })?;
         // SAFETY: rb_hash_delete returns a valid VALUE or Qnil
         let value = unsafe { Value::from_raw(val) };
 
         // rb_hash_delete returns Qnil if the key doesn't exist
-        if value.is_nil() { None } else { Some(value) }
+        Ok(if value.is_nil() { None } else { Some(value) })
+    }
+
+    /// Get the value for a key, or raise `KeyError` if there is none.
+    ///
+    /// Unlike [`get`](Self::get), this tells a `nil` value apart from a
+    /// missing key. Like `Hash#fetch`, it ignores the hash's default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `KeyError` (`key not found: :name`) if the key is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let hash = RHash::new_boxed();
+    /// hash.insert("port", 8080i64)?;
+    ///
+    /// assert_eq!(i64::try_convert(hash.fetch("port")?)?, 8080);
+    /// assert!(hash.fetch("host").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch<K: IntoValue>(&self, key: K) -> Result<Value, Error> {
+        let key = key.into_value().as_raw();
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash, key is a valid VALUE
+        let val = protect(|| unsafe { rb_sys::rb_hash_fetch(hash, key) })?;
+        // SAFETY: rb_hash_fetch returns a valid VALUE
+        Ok(unsafe { Value::from_raw(val) })
+    }
+
+    /// Check whether the hash contains a key, like `Hash#key?`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the key's `hash` or `eql?` method raises.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    /// use solidus::value::Value;
+    ///
+    /// let hash = RHash::new_boxed();
+    /// hash.insert("debug", Value::nil())?;
+    ///
+    /// assert!(hash.has_key("debug")?);
+    /// assert!(!hash.has_key("verbose")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn has_key<K: IntoValue>(&self, key: K) -> Result<bool, Error> {
+        let key = key.into_value().as_raw();
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash, key is a valid VALUE; Qundef
+        // marks a missing key
+        let val = protect(|| unsafe { rb_sys::rb_hash_lookup2(hash, key, rb_sys::Qundef.into()) })?;
+        // SAFETY: rb_hash_lookup2 returns a valid VALUE or Qundef
+        Ok(!unsafe { Value::from_raw(val) }.is_undef())
+    }
+
+    /// Set the value Ruby's `hash[key]` returns for missing keys, like
+    /// `Hash#default=`.
+    ///
+    /// This also removes any default proc. [`get`](Self::get) and
+    /// [`fetch`](Self::fetch) ignore the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    ///
+    /// let counts = RHash::new_boxed();
+    /// counts.set_default(0i64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_default<T: IntoValue>(&self, value: T) -> Result<(), Error> {
+        self.0.funcall_boxed("default=", (value.into_value(),))?;
+        Ok(())
+    }
+
+    /// Set the proc Ruby's `hash[key]` calls for missing keys, like
+    /// `Hash#default_proc=`, or remove it with `None`.
+    ///
+    /// The proc is called with the hash and the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if `proc` is a lambda that doesn't take two
+    /// arguments, or a `FrozenError` if the hash is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::prelude::*;
+    ///
+    /// let groups = RHash::new_boxed();
+    /// // groups[key] creates an empty list for a new key
+    /// let new_group = RProc::from_fn_boxed(|args: &[Value]| {
+    ///     let list = RArray::new_boxed();
+    ///     RHash::try_convert(args[0].clone())?.insert(args[1].clone(), list.as_value())?;
+    ///     Ok(list.as_value())
+    /// });
+    /// groups.set_default_proc(Some(&new_group))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_default_proc(&self, proc: Option<&RProc>) -> Result<(), Error> {
+        let proc = proc.map_or_else(Value::nil, |proc| proc.as_value());
+        self.0.funcall_boxed("default_proc=", (proc,))?;
+        Ok(())
+    }
+
+    /// Remove all pairs from the hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen.
+    pub fn clear(&self) -> Result<(), Error> {
+        let hash = self.0.as_raw();
+        // SAFETY: hash is a valid Ruby hash
+        protect(|| unsafe {
+            rb_sys::rb_hash_clear(hash);
+        })
+    }
+
+    /// Insert all pairs of `other` into this hash, replacing the values of
+    /// existing keys, like `Hash#update`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if this hash is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    ///
+    /// let options = RHash::new_boxed();
+    /// options.insert("verbose", false)?;
+    /// let overrides = RHash::new_boxed();
+    /// overrides.insert("verbose", true)?;
+    ///
+    /// options.update(&overrides)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(&self, other: &RHash) -> Result<(), Error> {
+        let (hash, other) = (self.0.as_raw(), other.as_raw());
+        // SAFETY: both are valid Ruby hashes; without a block function,
+        // values from other win
+        protect(|| unsafe {
+            rb_sys::rb_hash_update_by(hash, other, None);
+        })
+    }
+
+    /// Merge this hash and `other` into a new hash, stored in the Context.
+    ///
+    /// Values from `other` win, like `Hash#merge`. Neither hash is modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn with_defaults<'ctx>(
+    ///     ctx: &'ctx Context<'ctx>,
+    ///     defaults: RHash,
+    ///     options: RHash,
+    /// ) -> Result<Pin<&'ctx StackPinned<RHash>>, Error> {
+    ///     defaults.merge(ctx, &options)
+    /// }
+    /// ```
+    pub fn merge<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        other: &RHash,
+    ) -> Result<Pin<&'ctx StackPinned<RHash>>, Error> {
+        let merged = self.merge_raw(other)?;
+        Ok(ctx.pin_value(merged)?)
+    }
+
+    /// Merge this hash and `other` into a new, heap-allocated hash.
+    ///
+    /// # Errors
+    ///
+    /// See [`merge`](Self::merge).
+    pub fn merge_boxed(&self, other: &RHash) -> Result<BoxValue<RHash>, Error> {
+        self.merge_raw(other).map(BoxValue::new)
+    }
+
+    fn merge_raw(&self, other: &RHash) -> Result<RHash, Error> {
+        let (hash, other) = (self.0.as_raw(), other.as_raw());
+        // SAFETY: both are valid Ruby hashes; the copy isn't frozen, and
+        // stays on the stack while it is updated
+        let val = protect(|| unsafe {
+            let merged = rb_sys::rb_hash_dup(hash);
+            rb_sys::rb_hash_update_by(merged, other, None);
+            merged
+        })?;
+        // SAFETY: rb_hash_dup returns a new hash
+        Ok(unsafe { RHash::from_value_unchecked(Value::from_raw(val)) })
+    }
+
+    /// Insert many pairs at once, like the `{k1 => v1, k2 => v2}` literal.
+    ///
+    /// This is faster than calling [`insert`](Self::insert) for each pair.
+    /// Later pairs win if a key is repeated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    ///
+    /// let hash = RHash::with_capacity_boxed(3);
+    /// hash.bulk_insert(&[("a", 1i64), ("b", 2), ("c", 3)])?;
+    /// assert_eq!(hash.len(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_insert<K, V>(&self, pairs: &[(K, V)]) -> Result<(), Error>
+    where
+        K: IntoValue + Clone,
+        V: IntoValue + Clone,
+    {
+        // The converted keys and values are kept in a Ruby array on the
+        // stack, so the GC can see them until they are in the hash
+        // SAFETY: the array doesn't escape this function
+        let argv = unsafe { RArray::with_capacity(pairs.len() * 2) };
+        for (key, val) in pairs {
+            argv.push(key.clone())?;
+            argv.push(val.clone())?;
+        }
+
+        let (hash, argv) = (self.0.as_raw(), argv.as_raw());
+        // SAFETY: hash is a valid Ruby hash and argv holds key-value pairs.
+        // rb_hash_bulk_insert doesn't check for a frozen hash itself.
+        protect(|| unsafe {
+            if Value::from_raw(rb_sys::rb_obj_frozen_p(hash)).is_truthy() {
+                rb_sys::rb_error_frozen_object(hash);
+            }
+            rb_sys::rb_hash_bulk_insert(
+                rb_sys::RARRAY_LEN(argv),
+                rb_sys::RARRAY_CONST_PTR(argv),
+                hash,
+            );
+        })
+    }
+
+    /// Make the hash compare keys by identity (`equal?`) instead of `eql?`,
+    /// like `Hash#compare_by_identity`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the hash is frozen, or a `RuntimeError`
+    /// if it is being iterated.
+    pub fn compare_by_identity(&self) -> Result<(), Error> {
+        self.0.funcall_boxed("compare_by_identity", ())?;
+        Ok(())
+    }
+
+    /// Get the keys as a new array, stored in the Context.
+    ///
+    /// Use [`keys`](Self::keys) to iterate over them without an array.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the array.
+    pub fn keys_array<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+        let keys = self.collect_raw(push_key)?;
+        Ok(ctx.pin_value(keys)?)
+    }
+
+    /// Get the keys as a new, heap-allocated array.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if Ruby raises while walking the hash.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    ///
+    /// let hash = RHash::new_boxed();
+    /// hash.insert("a", 1i64)?;
+    /// assert_eq!(hash.keys_array_boxed()?.to_vec::<String>()?, vec!["a"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keys_array_boxed(&self) -> Result<BoxValue<RArray>, Error> {
+        self.collect_raw(push_key).map(BoxValue::new)
+    }
+
+    /// Get the values as a new array, stored in the Context.
+    ///
+    /// Use [`values`](Self::values) to iterate over them without an array.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the array.
+    pub fn values_array<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+        let values = self.collect_raw(push_value)?;
+        Ok(ctx.pin_value(values)?)
+    }
+
+    /// Get the values as a new, heap-allocated array.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if Ruby raises while walking the hash.
+    pub fn values_array_boxed(&self) -> Result<BoxValue<RArray>, Error> {
+        self.collect_raw(push_value).map(BoxValue::new)
+    }
+
+    /// Get the pairs as a new array of `[key, value]` arrays, stored in the
+    /// Context, like `Hash#to_a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the array.
+    pub fn to_a<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+        let pairs = self.collect_raw(push_pair)?;
+        Ok(ctx.pin_value(pairs)?)
+    }
+
+    /// Get the pairs as a new, heap-allocated array of `[key, value]` arrays.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if Ruby raises while walking the hash.
+    pub fn to_a_boxed(&self) -> Result<BoxValue<RArray>, Error> {
+        self.collect_raw(push_pair).map(BoxValue::new)
+    }
+
+    /// Collect something from every pair into a new array, with `push`.
+    fn collect_raw(&self, push: PushFn) -> Result<RArray, Error> {
+        // SAFETY: the array stays on the stack, where the GC can see it,
+        // until the caller pins or boxes it
+        let array = unsafe { RArray::with_capacity(self.len()) };
+        let (hash, array_raw) = (self.0.as_raw(), array.as_raw());
+        // SAFETY: hash is a valid hash, array is a valid array, push follows
+        // the callback contract
+        protect(|| unsafe {
+            rb_sys::rb_hash_foreach(hash, Some(push), array_raw);
+        })?;
+        Ok(array)
     }
 
     /// Iterate over the hash key-value pairs.
@@ -230,12 +687,9 @@ impl RHash {
     /// The closure is called for each (key, value) pair in the hash. If the
     /// closure returns an error, iteration stops and the error is returned.
     ///
-    /// # Why not Iterator?
-    ///
-    /// We don't implement Rust's `Iterator` trait because it would be unsafe.
-    /// Between iterator calls, Ruby code could run (if the closure calls back
-    /// into Ruby), potentially triggering GC which could modify or move the hash.
-    /// By using a closure, we maintain control over when Ruby code can execute.
+    /// The closure runs inside Ruby's own hash iteration, so like
+    /// `Hash#each`, adding a key to the hash from the closure is a
+    /// `RuntimeError`, while deleting keys and updating values is allowed.
     ///
     /// # Example
     ///
@@ -245,8 +699,8 @@ impl RHash {
     /// use solidus::convert::TryConvert;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("a", 1i64);
-    /// hash.insert("b", 2i64);
+    /// hash.insert("a", 1i64)?;
+    /// hash.insert("b", 2i64)?;
     ///
     /// let mut sum = 0i64;
     /// hash.each(|key, val| {
@@ -258,54 +712,150 @@ impl RHash {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn each<F>(&self, mut f: F) -> Result<(), Error>
+    pub fn each<F>(&self, f: F) -> Result<(), Error>
     where
         F: FnMut(Value, Value) -> Result<(), Error>,
     {
-        use crate::types::RArray;
-
-        // Create a temporary array to collect key-value pairs
-        // This is safer than using rb_hash_foreach which requires complex FFI callbacks
-        // SAFETY: We immediately use the array and don't let it escape
-        let pairs = unsafe { RArray::new() };
+        /// State shared between `each` and the `rb_hash_foreach` callback.
+        struct Each<F> {
+            f: F,
+            result: std::thread::Result<Result<(), Error>>,
+        }
 
-        // Use rb_hash_foreach to collect all pairs
-        unsafe extern "C" fn collect_pair(
+        unsafe extern "C" fn call<F>(
             key: rb_sys::VALUE,
             val: rb_sys::VALUE,
             arg: rb_sys::VALUE,
-        ) -> i32 {
-            // SAFETY: We're in an unsafe block within an extern "C" function called from Ruby
-            unsafe {
-                let pairs_array = Value::from_raw(arg);
-                let pair = rb_sys::rb_ary_new();
-                rb_sys::rb_ary_push(pair, key);
-                rb_sys::rb_ary_push(pair, val);
-                rb_sys::rb_ary_push(pairs_array.as_raw(), pair);
+        ) -> i32
+        where
+            F: FnMut(Value, Value) -> Result<(), Error>,
+        {
+            // SAFETY: arg is the pointer to the Each passed to rb_hash_foreach
+            let each = unsafe { &mut *(arg as *mut Each<F>) };
+            // SAFETY: rb_hash_foreach passes valid VALUEs
+            let (key, val) = unsafe { (Value::from_raw(key), Value::from_raw(val)) };
+            // Panics must not unwind into Ruby's C frames
+            each.result = panic::catch_unwind(AssertUnwindSafe(|| (each.f)(key, val)));
+            match each.result {
+                Ok(Ok(())) => 0, // ST_CONTINUE
+                _ => 1,          // ST_STOP
             }
-            0 // ST_CONTINUE
         }
 
-        // SAFETY: self.0 is a valid hash, pairs is a valid array, collect_pair follows the callback contract
-        unsafe {
-            rb_sys::rb_hash_foreach(
-                self.0.as_raw(),
-                Some(collect_pair),
-                pairs.as_value().as_raw(),
-            );
+        let mut each = Each {
+            f,
+            result: Ok(Ok(())),
+        };
+        let (hash, arg) = (self.0.as_raw(), &mut each as *mut Each<F> as rb_sys::VALUE);
+        // SAFETY: hash is a valid hash, call follows the callback contract and
+        // the pointer to each is valid for the duration of rb_hash_foreach
+        protect(|| unsafe {
+            rb_sys::rb_hash_foreach(hash, Some(call::<F>), arg);
+        })?;
+
+        match each.result {
+            Ok(result) => result,
+            Err(panic) => panic::resume_unwind(panic),
         }
+    }
 
-        // Now iterate over the collected pairs
-        let pairs_arr = RArray::try_convert(pairs.as_value())?;
-        for i in 0..pairs_arr.len() {
-            let pair_val = pairs_arr.entry(i as isize);
-            let pair = RArray::try_convert(pair_val)?;
-            let key = pair.entry(0);
-            let val = pair.entry(1);
-            f(key, val)?;
+    /// Iterate over the hash key-value pairs, in insertion order.
+    ///
+    /// The first call to `next` copies the keys and values into a single
+    /// array, in one pass over the hash, and the iterator yields the pairs
+    /// from that copy. Adding or deleting keys while iterating is an error:
+    /// the next item is a `RuntimeError`, after which the iterator ends.
+    /// Values updated in the meantime are yielded as they were when the
+    /// iteration started.
+    ///
+    /// Only changes in the size of the hash are detected. Use
+    /// [`each`](Self::each) to get `Hash#each`'s own checks.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let mut hash = RHash::new_boxed();
+    /// hash.insert("a", 1i64)?;
+    /// hash.insert("b", 2i64)?;
+    ///
+    /// let mut sum = 0i64;
+    /// for pair in hash.iter() {
+    ///     let (_key, val) = pair?;
+    ///     sum += i64::try_convert(val)?;
+    /// }
+    /// assert_eq!(sum, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> HashIter<'_> {
+        HashIter {
+            hash: self,
+            pairs: None,
+            size: 0,
+            index: 0,
+            done: false,
         }
+    }
 
-        Ok(())
+    /// Iterate over the keys of the hash, in insertion order.
+    ///
+    /// See [`iter`](Self::iter) for how modifications are handled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let mut hash = RHash::new_boxed();
+    /// hash.insert("a", 1i64)?;
+    /// hash.insert("b", 2i64)?;
+    ///
+    /// let keys = hash
+    ///     .keys()
+    ///     .map(|key| String::try_convert(key?))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(keys, ["a", "b"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> HashKeys<'_> {
+        HashKeys(self.iter())
+    }
+
+    /// Iterate over the values of the hash, in insertion order.
+    ///
+    /// See [`iter`](Self::iter) for how modifications are handled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RHash;
+    /// use solidus::convert::TryConvert;
+    ///
+    /// let mut hash = RHash::new_boxed();
+    /// hash.insert("a", 1i64)?;
+    /// hash.insert("b", 2i64)?;
+    ///
+    /// let mut sum = 0i64;
+    /// for val in hash.values() {
+    ///     sum += i64::try_convert(val?)?;
+    /// }
+    /// assert_eq!(sum, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn values(&self) -> HashValues<'_> {
+        HashValues(self.iter())
     }
 
     /// Convert this hash to a Rust HashMap.
@@ -321,8 +871,8 @@ impl RHash {
     /// use std::collections::HashMap;
     ///
     /// let mut hash = RHash::new_boxed();
-    /// hash.insert("a", 1i64);
-    /// hash.insert("b", 2i64);
+    /// hash.insert("a", 1i64)?;
+    /// hash.insert("b", 2i64)?;
     ///
     /// let map: HashMap<String, i64> = hash.to_hash_map()?;
     /// assert_eq!(map.get("a"), Some(&1));
@@ -379,7 +929,15 @@ impl RHash {
         // SAFETY: Caller ensures the returned value is properly handled
         let hash = unsafe { Self::new() };
         for (k, v) in map {
-            hash.insert(k, v);
+            // SAFETY: the hash is new, so it is not frozen, and keys converted
+            // from Rust values use Ruby's built-in #hash and #eql?
+            unsafe {
+                rb_sys::rb_hash_aset(
+                    hash.as_raw(),
+                    k.into_value().as_raw(),
+                    v.into_value().as_raw(),
+                );
+            }
         }
         hash
     }
@@ -394,7 +952,15 @@ impl RHash {
         // SAFETY: Caller ensures the returned value is properly handled
         let hash = unsafe { Self::new_internal() };
         for (k, v) in map {
-            hash.insert(k, v);
+            // SAFETY: the hash is new, so it is not frozen, and keys converted
+            // from Rust values use Ruby's built-in #hash and #eql?
+            unsafe {
+                rb_sys::rb_hash_aset(
+                    hash.as_raw(),
+                    k.into_value().as_raw(),
+                    v.into_value().as_raw(),
+                );
+            }
         }
         hash
     }
@@ -426,6 +992,166 @@ impl RHash {
     }
 }
 
+/// An `rb_hash_foreach` callback adding to the array passed as its argument.
+type PushFn = unsafe extern "C" fn(rb_sys::VALUE, rb_sys::VALUE, rb_sys::VALUE) -> i32;
+
+unsafe extern "C" fn push_key(
+    key: rb_sys::VALUE,
+    _val: rb_sys::VALUE,
+    array: rb_sys::VALUE,
+) -> i32 {
+    // SAFETY: array is the array passed to rb_hash_foreach
+    unsafe { rb_sys::rb_ary_push(array, key) };
+    0 // ST_CONTINUE
+}
+
+unsafe extern "C" fn push_value(
+    _key: rb_sys::VALUE,
+    val: rb_sys::VALUE,
+    array: rb_sys::VALUE,
+) -> i32 {
+    // SAFETY: array is the array passed to rb_hash_foreach
+    unsafe { rb_sys::rb_ary_push(array, val) };
+    0 // ST_CONTINUE
+}
+
+unsafe extern "C" fn push_key_and_value(
+    key: rb_sys::VALUE,
+    val: rb_sys::VALUE,
+    array: rb_sys::VALUE,
+) -> i32 {
+    // SAFETY: array is the array passed to rb_hash_foreach
+    unsafe {
+        rb_sys::rb_ary_push(array, key);
+        rb_sys::rb_ary_push(array, val);
+    }
+    0 // ST_CONTINUE
+}
+
+unsafe extern "C" fn push_pair(
+    key: rb_sys::VALUE,
+    val: rb_sys::VALUE,
+    array: rb_sys::VALUE,
+) -> i32 {
+    // SAFETY: array is the array passed to rb_hash_foreach
+    unsafe {
+        let pair = rb_sys::rb_ary_new_from_values(2, [key, val].as_ptr());
+        rb_sys::rb_ary_push(array, pair);
+    }
+    0 // ST_CONTINUE
+}
+
+/// An iterator over the key-value pairs of an [`RHash`], created by
+/// [`RHash::iter`].
+///
+/// Yields `Err` once if the size of the hash changes during iteration, then
+/// ends.
+#[derive(Debug)]
+pub struct HashIter<'a> {
+    hash: &'a RHash,
+    /// The keys and values, alternating, recorded on the first call to
+    /// `next`. Boxed so the GC keeps them alive even if the iterator is moved
+    /// to the heap.
+    pairs: Option<BoxValue<RArray>>,
+    /// The size of the hash when the pairs were recorded.
+    size: usize,
+    index: usize,
+    done: bool,
+}
+
+impl HashIter<'_> {
+    /// Record the pairs of the hash, unless already done.
+    fn record_pairs(&mut self) -> Result<(), Error> {
+        if self.pairs.is_some() {
+            return Ok(());
+        }
+
+        let pairs = BoxValue::new(self.hash.collect_raw(push_key_and_value)?);
+        self.size = pairs.len() / 2;
+        self.pairs = Some(pairs);
+        Ok(())
+    }
+}
+
+impl Iterator for HashIter<'_> {
+    type Item = Result<(Value, Value), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Err(error) = self.record_pairs() {
+            self.done = true;
+            return Some(Err(error));
+        }
+        let pairs = self.pairs.as_ref()?;
+
+        if self.hash.len() != self.size {
+            self.done = true;
+            return Some(Err(Error::runtime("hash modified during iteration")));
+        }
+        if self.index >= self.size {
+            self.done = true;
+            return None;
+        }
+
+        let i = (self.index * 2) as isize;
+        self.index += 1;
+        Some(Ok((pairs.entry(i), pairs.entry(i + 1))))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done { (0, Some(0)) } else { (0, None) }
+    }
+}
+
+impl<'a> IntoIterator for &'a RHash {
+    type Item = Result<(Value, Value), Error>;
+    type IntoIter = HashIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> HashIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the keys of an [`RHash`], created by [`RHash::keys`].
+#[derive(Debug)]
+pub struct HashKeys<'a>(HashIter<'a>);
+
+impl Iterator for HashKeys<'_> {
+    type Item = Result<Value, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|pair| pair.map(|(key, _)| key))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An iterator over the values of an [`RHash`], created by [`RHash::values`].
+#[derive(Debug)]
+pub struct HashValues<'a>(HashIter<'a>);
+
+impl Iterator for HashValues<'_> {
+    type Item = Result<Value, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|pair| pair.map(|(_, val)| val))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
 impl ReprValue for RHash {
     #[inline]
     fn as_value(&self) -> Value {
@@ -483,6 +1209,7 @@ where
 #[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
 mod tests {
     use super::*;
+    use crate::test_util::eval;
     use rb_sys_test_helpers::ruby_test;
 
     #[ruby_test]
@@ -495,54 +1222,63 @@ mod tests {
     #[ruby_test]
     fn test_rhash_insert_and_get() {
         let hash = RHash::new_boxed();
-        hash.insert("key", 42i64);
+        hash.insert("key", 42i64).unwrap();
 
         assert_eq!(hash.len(), 1);
         assert!(!hash.is_empty());
 
-        let val = hash.get("key").unwrap();
+        let val = hash.get("key").unwrap().unwrap();
         assert_eq!(i64::try_convert(val).unwrap(), 42);
     }
 
     #[ruby_test]
     fn test_rhash_get_missing() {
         let hash = RHash::new_boxed();
-        assert!(hash.get("missing").is_none());
+        assert!(hash.get("missing").unwrap().is_none());
     }
 
     #[ruby_test]
     fn test_rhash_insert_update() {
         let hash = RHash::new_boxed();
-        hash.insert("key", 1i64);
+        hash.insert("key", 1i64).unwrap();
         assert_eq!(hash.len(), 1);
 
-        hash.insert("key", 2i64);
+        hash.insert("key", 2i64).unwrap();
         assert_eq!(hash.len(), 1); // Should still be 1
 
-        let val = hash.get("key").unwrap();
+        let val = hash.get("key").unwrap().unwrap();
         assert_eq!(i64::try_convert(val).unwrap(), 2);
     }
 
     #[ruby_test]
     fn test_rhash_multiple_keys() {
         let hash = RHash::new_boxed();
-        hash.insert("a", 1i64);
-        hash.insert("b", 2i64);
-        hash.insert("c", 3i64);
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+        hash.insert("c", 3i64).unwrap();
 
         assert_eq!(hash.len(), 3);
 
-        assert_eq!(i64::try_convert(hash.get("a").unwrap()).unwrap(), 1);
-        assert_eq!(i64::try_convert(hash.get("b").unwrap()).unwrap(), 2);
-        assert_eq!(i64::try_convert(hash.get("c").unwrap()).unwrap(), 3);
+        assert_eq!(
+            i64::try_convert(hash.get("a").unwrap().unwrap()).unwrap(),
+            1
+        );
+        assert_eq!(
+            i64::try_convert(hash.get("b").unwrap().unwrap()).unwrap(),
+            2
+        );
+        assert_eq!(
+            i64::try_convert(hash.get("c").unwrap().unwrap()).unwrap(),
+            3
+        );
     }
 
     #[ruby_test]
     fn test_rhash_delete() {
         let hash = RHash::new_boxed();
-        hash.insert("key", 42i64);
+        hash.insert("key", 42i64).unwrap();
 
-        let val = hash.delete("key").unwrap();
+        let val = hash.delete("key").unwrap().unwrap();
         assert_eq!(i64::try_convert(val).unwrap(), 42);
         assert_eq!(hash.len(), 0);
     }
@@ -550,24 +1286,24 @@ mod tests {
     #[ruby_test]
     fn test_rhash_delete_missing() {
         let hash = RHash::new_boxed();
-        assert!(hash.delete("missing").is_none());
+        assert!(hash.delete("missing").unwrap().is_none());
     }
 
     #[ruby_test]
     fn test_rhash_delete_twice() {
         let hash = RHash::new_boxed();
-        hash.insert("key", 42i64);
+        hash.insert("key", 42i64).unwrap();
 
-        hash.delete("key");
-        assert!(hash.delete("key").is_none());
+        hash.delete("key").unwrap();
+        assert!(hash.delete("key").unwrap().is_none());
     }
 
     #[ruby_test]
     fn test_rhash_each() {
         let hash = RHash::new_boxed();
-        hash.insert("a", 1i64);
-        hash.insert("b", 2i64);
-        hash.insert("c", 3i64);
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+        hash.insert("c", 3i64).unwrap();
 
         let mut sum = 0i64;
         hash.each(|_key, val| {
@@ -597,8 +1333,8 @@ mod tests {
         use crate::types::RString;
 
         let hash = RHash::new_boxed();
-        hash.insert("a", 1i64);
-        hash.insert("b", 2i64);
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
 
         let mut keys = Vec::new();
         hash.each(|key, _val| {
@@ -616,16 +1352,169 @@ mod tests {
     #[ruby_test]
     fn test_rhash_each_error() {
         let hash = RHash::new_boxed();
-        hash.insert("a", 1i64);
+        hash.insert("a", 1i64).unwrap();
 
         let result = hash.each(|_, _| Err(Error::type_error("test error")));
         assert!(result.is_err());
     }
 
+    #[ruby_test]
+    fn test_rhash_iter() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+        hash.insert("c", 3i64).unwrap();
+
+        let pairs: Vec<(String, i64)> = hash
+            .iter()
+            .map(|pair| {
+                let (key, val) = pair.unwrap();
+                (
+                    String::try_convert(key).unwrap(),
+                    i64::try_convert(val).unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 3)
+            ]
+        );
+        assert_eq!((&*hash).into_iter().count(), 3);
+        assert!(RHash::new_boxed().iter().next().is_none());
+    }
+
+    #[ruby_test]
+    fn test_rhash_keys_and_values() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+
+        let keys: Vec<String> = hash
+            .keys()
+            .map(|key| String::try_convert(key.unwrap()).unwrap())
+            .collect();
+        assert_eq!(keys, ["a", "b"]);
+
+        let values: Vec<i64> = hash
+            .values()
+            .map(|val| i64::try_convert(val.unwrap()).unwrap())
+            .collect();
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[ruby_test]
+    fn test_rhash_iter_update() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+
+        let mut seen = Vec::new();
+        for pair in hash.iter() {
+            let (key, val) = pair.unwrap();
+            hash.insert("b", 20i64).unwrap();
+            seen.push((
+                String::try_convert(key).unwrap(),
+                i64::try_convert(val).unwrap(),
+            ));
+        }
+        // The pairs are copied when the iteration starts
+        assert_eq!(seen, [("a".to_string(), 1), ("b".to_string(), 2)]);
+    }
+
+    #[ruby_test]
+    fn test_rhash_iter_raising_key() {
+        // The key's #hash starts raising once it is in the hash
+        let hash = eval(
+            "key = Class.new { attr_accessor :broken; def hash = broken ? raise('no hash') : 0 }.new
+             hash = {key => 1}
+             key.broken = true
+             hash",
+        )
+        .unwrap();
+        let hash = RHash::try_convert(hash.inner()).unwrap();
+
+        // Neither way of iterating rehashes the keys
+        let (_, val) = hash.iter().next().unwrap().unwrap();
+        assert_eq!(i64::try_convert(val).unwrap(), 1);
+        let mut count = 0;
+        hash.each(|_, _| {
+            count += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[ruby_test]
+    fn test_rhash_iter_resize_is_error() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+
+        let mut iter = hash.iter();
+        assert!(iter.next().unwrap().is_ok());
+        hash.insert("new", 3i64).unwrap();
+        let err = iter.next().unwrap().unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::RuntimeError.as_value()));
+        assert!(iter.next().is_none());
+
+        let mut iter = hash.iter();
+        assert!(iter.next().unwrap().is_ok());
+        hash.delete("b").unwrap();
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
+    #[ruby_test]
+    fn test_rhash_each_modify() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
+
+        // Deleting and updating is allowed
+        hash.each(|key, _| {
+            hash.delete(key)?;
+            Ok(())
+        })
+        .unwrap();
+        assert!(hash.is_empty());
+
+        // Adding a key is not, even after deleting one
+        hash.insert("a", 1i64).unwrap();
+        let err = hash
+            .each(|_, _| {
+                hash.delete("a")?;
+                hash.insert("another", 4i64)?;
+                Ok(())
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "can't add a new key into hash during iteration"
+        );
+        assert!(hash.is_empty());
+    }
+
+    #[ruby_test]
+    fn test_rhash_each_panic() {
+        let hash = RHash::new_boxed();
+        hash.insert("a", 1i64).unwrap();
+
+        let result = std::panic::catch_unwind(|| hash.each(|_, _| panic!("boom")));
+        assert!(result.is_err());
+        // The hash is no longer being iterated
+        hash.insert("b", 2i64).unwrap();
+        assert_eq!(hash.len(), 2);
+    }
+
     #[ruby_test]
     fn test_rhash_try_convert() {
         let hash = RHash::new_boxed();
-        hash.insert("key", 42i64);
+        hash.insert("key", 42i64).unwrap();
 
         let val = hash.as_value();
         let converted = RHash::try_convert(val).unwrap();
@@ -641,8 +1530,8 @@ mod tests {
     #[ruby_test]
     fn test_rhash_to_hash_map() {
         let hash = RHash::new_boxed();
-        hash.insert("a", 1i64);
-        hash.insert("b", 2i64);
+        hash.insert("a", 1i64).unwrap();
+        hash.insert("b", 2i64).unwrap();
 
         let map: HashMap<String, i64> = hash.to_hash_map().unwrap();
         assert_eq!(map.len(), 2);
@@ -659,8 +1548,14 @@ mod tests {
         let hash = RHash::from_hash_map_boxed(map);
         assert_eq!(hash.len(), 2);
 
-        assert_eq!(i64::try_convert(hash.get("a").unwrap()).unwrap(), 1);
-        assert_eq!(i64::try_convert(hash.get("b").unwrap()).unwrap(), 2);
+        assert_eq!(
+            i64::try_convert(hash.get("a").unwrap().unwrap()).unwrap(),
+            1
+        );
+        assert_eq!(
+            i64::try_convert(hash.get("b").unwrap().unwrap()).unwrap(),
+            2
+        );
     }
 
     #[ruby_test]
@@ -682,16 +1577,16 @@ mod tests {
         use crate::types::{RString, Symbol};
 
         let hash = RHash::new_boxed();
-        hash.insert("string_key", 42i64);
-        hash.insert(Symbol::new("symbol_key"), "value");
-        hash.insert(123i64, true);
+        hash.insert("string_key", 42i64).unwrap();
+        hash.insert(Symbol::new("symbol_key"), "value").unwrap();
+        hash.insert(123i64, true).unwrap();
 
         assert_eq!(hash.len(), 3);
 
-        let val1 = hash.get("string_key").unwrap();
+        let val1 = hash.get("string_key").unwrap().unwrap();
         assert_eq!(i64::try_convert(val1).unwrap(), 42);
 
-        let val2 = hash.get(Symbol::new("symbol_key")).unwrap();
+        let val2 = hash.get(Symbol::new("symbol_key")).unwrap().unwrap();
         // Check if it's a symbol value
         if let Ok(sym) = Symbol::try_convert(val2.clone()) {
             // It's a symbol, not a string
@@ -700,40 +1595,176 @@ mod tests {
         let s = RString::try_convert(val2).unwrap();
         assert_eq!(s.to_string().unwrap(), "value");
 
-        let val3 = hash.get(123i64).unwrap();
+        let val3 = hash.get(123i64).unwrap().unwrap();
         assert_eq!(bool::try_convert(val3).unwrap(), true);
     }
 
     #[ruby_test]
     fn test_rhash_nested() {
         let inner = RHash::new_boxed();
-        inner.insert("inner_key", 99i64);
+        inner.insert("inner_key", 99i64).unwrap();
 
         let outer = RHash::new_boxed();
-        outer.insert("outer_key", inner.as_value());
+        outer.insert("outer_key", inner.as_value()).unwrap();
 
         assert_eq!(outer.len(), 1);
 
-        let val = outer.get("outer_key").unwrap();
+        let val = outer.get("outer_key").unwrap().unwrap();
         let inner_hash = RHash::try_convert(val).unwrap();
         assert_eq!(inner_hash.len(), 1);
 
-        let inner_val = inner_hash.get("inner_key").unwrap();
+        let inner_val = inner_hash.get("inner_key").unwrap().unwrap();
         assert_eq!(i64::try_convert(inner_val).unwrap(), 99);
     }
 
     #[ruby_test]
     fn test_rhash_with_integer_keys() {
         let hash = RHash::new_boxed();
-        hash.insert(1i64, "one");
-        hash.insert(2i64, "two");
-        hash.insert(3i64, "three");
+        hash.insert(1i64, "one").unwrap();
+        hash.insert(2i64, "two").unwrap();
+        hash.insert(3i64, "three").unwrap();
 
         assert_eq!(hash.len(), 3);
 
         use crate::types::RString;
-        let val = hash.get(2i64).unwrap();
+        let val = hash.get(2i64).unwrap().unwrap();
         let s = RString::try_convert(val).unwrap();
         assert_eq!(s.to_string().unwrap(), "two");
     }
+
+    #[ruby_test]
+    fn test_rhash_fetch_and_has_key() {
+        let hash = RHash::new_boxed();
+        hash.insert("port", 8080i64).unwrap();
+        hash.insert("debug", Value::nil()).unwrap();
+
+        assert_eq!(i64::try_convert(hash.fetch("port").unwrap()).unwrap(), 8080);
+        assert!(hash.fetch("debug").unwrap().is_nil());
+        let err = hash.fetch("host").unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::KeyError.as_value()));
+        assert_eq!(err.message(), "key not found: \"host\"");
+
+        assert!(hash.has_key("debug").unwrap());
+        assert!(!hash.has_key("host").unwrap());
+    }
+
+    #[ruby_test]
+    fn test_rhash_defaults() {
+        let hash = RHash::new_boxed();
+        hash.set_default(0i64).unwrap();
+        let val = hash.as_value().funcall_boxed("[]", ("missing",)).unwrap();
+        assert_eq!(i64::try_convert(val.inner()).unwrap(), 0);
+        // get and fetch ignore the default
+        assert!(hash.get("missing").unwrap().is_none());
+        assert!(hash.fetch("missing").is_err());
+
+        let proc = eval("proc { |h, k| h[k] = k * 2 }").unwrap();
+        let proc = RProc::try_convert(proc.inner()).unwrap();
+        hash.set_default_proc(Some(&proc)).unwrap();
+        let val = hash.as_value().funcall_boxed("[]", (21i64,)).unwrap();
+        assert_eq!(i64::try_convert(val.inner()).unwrap(), 42);
+        assert_eq!(hash.len(), 1);
+
+        hash.set_default_proc(None).unwrap();
+        let val = hash.as_value().funcall_boxed("[]", (1i64,)).unwrap();
+        assert!(val.is_nil());
+
+        let lambda = RProc::try_convert(eval("lambda { |k| k }").unwrap().inner()).unwrap();
+        let err = hash.set_default_proc(Some(&lambda)).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::TypeError.as_value()));
+    }
+
+    #[ruby_test]
+    fn test_rhash_clear_update_merge() {
+        let ctx: Context<'_> = Context::default();
+        let options = RHash::new_boxed();
+        options
+            .bulk_insert(&[("verbose", false), ("color", true)])
+            .unwrap();
+        let overrides = RHash::new_boxed();
+        overrides.insert("verbose", true).unwrap();
+
+        let merged = options.merge(&ctx, &overrides).unwrap();
+        assert!(bool::try_convert(merged.get().get("verbose").unwrap().unwrap()).unwrap());
+        assert!(!bool::try_convert(options.get("verbose").unwrap().unwrap()).unwrap());
+        assert_eq!(options.merge_boxed(&overrides).unwrap().len(), 2);
+
+        options.update(&overrides).unwrap();
+        assert!(bool::try_convert(options.get("verbose").unwrap().unwrap()).unwrap());
+        options.clear().unwrap();
+        assert!(options.is_empty());
+    }
+
+    #[ruby_test]
+    fn test_rhash_bulk_insert() {
+        let hash = RHash::with_capacity_boxed(16);
+        let pairs: Vec<(i64, i64)> = (0..16).map(|i| (i, i * i)).collect();
+        hash.bulk_insert(&pairs).unwrap();
+        assert_eq!(hash.len(), 16);
+        assert_eq!(i64::try_convert(hash.fetch(15i64).unwrap()).unwrap(), 225);
+
+        // Later pairs win, and string keys are copied like Hash#[]=
+        hash.bulk_insert(&[("k", 1i64), ("k", 2)]).unwrap();
+        assert_eq!(i64::try_convert(hash.fetch("k").unwrap()).unwrap(), 2);
+        assert_eq!(hash.len(), 17);
+    }
+
+    #[ruby_test]
+    fn test_rhash_frozen() {
+        let hash = RHash::try_convert(eval("{a: 1}.freeze").unwrap().inner()).unwrap();
+        for result in [
+            hash.clear(),
+            hash.set_default(1i64),
+            hash.update(&RHash::new_boxed()),
+            hash.bulk_insert(&[(1i64, 2i64)]),
+            hash.compare_by_identity(),
+            hash.insert("b", 2i64),
+            hash.delete(crate::types::Symbol::new("a")).map(drop),
+        ] {
+            let err = result.unwrap_err();
+            assert!(err.is_a(&crate::ExceptionClass::FrozenError.as_value()));
+        }
+        assert_eq!(hash.len(), 1);
+    }
+
+    #[ruby_test]
+    fn test_rhash_raising_key() {
+        let hash = RHash::new_boxed();
+        let key = eval("Class.new { def hash = raise(ArgumentError, 'no hash') }.new").unwrap();
+        assert!(hash.insert(key.inner(), 1i64).is_err());
+        assert!(hash.get(key.inner()).is_err());
+        assert!(hash.delete(key.inner()).is_err());
+        assert!(hash.is_empty());
+    }
+
+    #[ruby_test]
+    fn test_rhash_compare_by_identity() {
+        let hash = RHash::new_boxed();
+        hash.compare_by_identity().unwrap();
+        let key = crate::types::RString::new_boxed("key");
+        hash.insert(key.as_value(), 1i64).unwrap();
+        assert!(hash.has_key(key.as_value()).unwrap());
+        assert!(!hash.has_key("key").unwrap());
+    }
+
+    #[ruby_test]
+    fn test_rhash_arrays() {
+        let ctx: Context<'_> = Context::default();
+        let hash = RHash::new_boxed();
+        hash.bulk_insert(&[("a", 1i64), ("b", 2)]).unwrap();
+
+        let keys = hash.keys_array(&ctx).unwrap();
+        assert_eq!(keys.get().to_vec::<String>().unwrap(), ["a", "b"]);
+        let values = hash.values_array(&ctx).unwrap();
+        assert_eq!(values.get().to_vec::<i64>().unwrap(), [1, 2]);
+        let pairs = hash.to_a(&ctx).unwrap();
+        let pairs = pairs.get().to_vec::<RArray>().unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(String::try_convert(pairs[1].entry(0)).unwrap(), "b");
+        assert_eq!(i64::try_convert(pairs[1].entry(1)).unwrap(), 2);
+
+        assert_eq!(hash.keys_array_boxed().unwrap().len(), 2);
+        assert_eq!(hash.values_array_boxed().unwrap().len(), 2);
+        assert_eq!(hash.to_a_boxed().unwrap().len(), 2);
+    }
 }
@@ -1,7 +1,7 @@
 //! Ruby integer types: Fixnum (immediate) and Bignum (heap).
 
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::Error;
+use crate::error::{Error, protect};
 use crate::value::{BoxValue, NewValue, ReprValue, Value};
 
 /// Small integer that fits in a VALUE (immediate value).
@@ -380,20 +380,20 @@ impl RBignum {
     ///
     /// Returns an error if the value is out of range for i64.
     pub fn to_i64(&self) -> Result<i64, Error> {
-        // SAFETY: self.0 is a valid Bignum VALUE
-        // rb_num2ll will raise a RangeError if the value is out of range,
-        // which we should catch. For now, we'll use rb_big2ll which is more direct.
-        let result = unsafe { rb_sys::rb_big2ll(self.0.as_raw()) };
-        Ok(result as i64)
+        let val = self.0.as_raw();
+        // SAFETY: val is a valid Bignum VALUE; rb_big2ll raises RangeError
+        // if it doesn't fit, which protect turns into an Err
+        protect(|| unsafe { rb_sys::rb_big2ll(val) })
     }
 
     /// Convert to u64.
     ///
     /// Returns an error if the value is negative or out of range for u64.
     pub fn to_u64(&self) -> Result<u64, Error> {
-        // SAFETY: self.0 is a valid Bignum VALUE
-        let result = unsafe { rb_sys::rb_big2ull(self.0.as_raw()) };
-        Ok(result as u64)
+        let val = self.0.as_raw();
+        // SAFETY: val is a valid Bignum VALUE; rb_big2ull raises RangeError
+        // if it doesn't fit, which protect turns into an Err
+        protect(|| unsafe { rb_sys::rb_big2ull(val) })
     }
 }
 
@@ -632,6 +632,13 @@ mod tests {
         }
     }
 
+    #[ruby_test]
+    fn test_bignum_to_i64_out_of_range() {
+        let b = RBignum::from_u64_boxed(u64::MAX).unwrap();
+        let err = b.to_i64().unwrap_err();
+        assert!(err.exception().is_some());
+    }
+
     #[ruby_test]
     fn test_integer_bignum_conversion() {
         // Create an Integer from a large value
@@ -9,7 +9,7 @@
 //!
 //! - **Immediate values**: Encoded directly in the VALUE (Fixnum, Symbol, true, false, nil).
 //!   These don't require GC protection and can be passed without pinning.
-//! - **Heap values**: Allocated on the Ruby heap (String, Array, Hash, etc.).
+//! - **Heap values**: Allocated on the Ruby heap (String, Array, Hash, Proc, etc.).
 //!   These require GC protection and must be stack-pinned or heap-boxed.
 //!
 //! # Example
@@ -37,16 +37,20 @@ mod hash;
 mod immediate;
 mod integer;
 mod module;
+mod proc;
 mod string;
+mod string_builder;
 mod symbol;
 
-pub use array::RArray;
+pub use array::{ArrayIter, RArray};
 pub use class::RClass;
-pub use hash::RHash;
+pub use hash::{HashIter, HashKeys, HashValues, RHash};
 pub use immediate::{Qfalse, Qnil, Qtrue};
 pub use integer::{Fixnum, Integer, RBignum};
 pub use module::{Module, RModule};
-pub use string::{Encoding, RString};
+pub use proc::{RMethod, RProc};
+pub use string::{Chars, EncodeOptions, Encoding, RString, StringLock};
+pub use string_builder::RStringBuilder;
 pub use symbol::Symbol;
 
 // Flonum is only available on 64-bit platforms
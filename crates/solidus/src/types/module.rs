@@ -1,7 +1,8 @@
 //! Ruby Module type.
 
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::{Error, ExceptionClass};
+use crate::error::{Error, protect};
+use crate::method::MethodFn;
 use crate::types::RClass;
 use crate::value::{ReprValue, Value};
 
@@ -43,25 +44,9 @@ impl RModule {
         // Convert to C string
         let c_name = std::ffi::CString::new(name).ok()?;
 
-        // SAFETY: rb_path2class looks up a class/module by name and may raise an exception
-        // If it raises, we catch it by checking rb_errinfo()
-        let val = unsafe {
-            // Save the current exception state
-            let old_errinfo = rb_sys::rb_errinfo();
-
-            // Try to get the module
-            let result = rb_sys::rb_path2class(c_name.as_ptr());
-
-            // Check if an exception was raised
-            let new_errinfo = rb_sys::rb_errinfo();
-            if new_errinfo != old_errinfo {
-                // Exception was raised, clear it and return None
-                rb_sys::rb_set_errinfo(rb_sys::Qnil.into());
-                return None;
-            }
-
-            result
-        };
+        // SAFETY: rb_path2class looks up a class/module by name and raises if
+        // it doesn't exist (or isn't a class); protect turns that into an Err
+        let val = protect(|| unsafe { rb_sys::rb_path2class(c_name.as_ptr()) }).ok()?;
 
         if val == rb_sys::Qnil.into() {
             None
@@ -158,6 +143,11 @@ pub trait Module: ReprValue {
     ///
     /// This sets a constant that can be accessed as `ModuleName::CONST_NAME`.
     ///
+    /// # Errors
+    ///
+    /// Returns the Ruby exception if the constant can't be set, e.g. because
+    /// the module is frozen.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -171,23 +161,20 @@ pub trait Module: ReprValue {
         let c_name = std::ffi::CString::new(name)
             .map_err(|_| Error::argument("constant name contains null byte"))?;
 
+        let module = self.as_raw();
         let val = value.into_value();
+        let val = val.as_raw();
 
-        // SAFETY: self is a valid module/class, c_name is a valid C string, val is a valid VALUE
-        unsafe {
-            rb_sys::rb_const_set(
-                self.as_value().as_raw(),
-                rb_sys::rb_intern(c_name.as_ptr()),
-                val.as_raw(),
-            );
-        }
-
-        Ok(())
+        // SAFETY: module is a valid module/class, c_name is a valid C string, val is a valid VALUE
+        protect(|| unsafe {
+            rb_sys::rb_const_set(module, rb_sys::rb_intern(c_name.as_ptr()), val);
+        })
     }
 
     /// Get a constant from this module/class.
     ///
-    /// Returns an error if the constant doesn't exist.
+    /// Returns the Ruby exception (usually a `NameError`) if the constant
+    /// doesn't exist. Autoloads and `const_missing` are honoured.
     ///
     /// # Example
     ///
@@ -202,22 +189,15 @@ pub trait Module: ReprValue {
         let c_name = std::ffi::CString::new(name)
             .map_err(|_| Error::argument("constant name contains null byte"))?;
 
-        // Get the symbol ID for the constant name
-        let id = unsafe { rb_sys::rb_intern(c_name.as_ptr()) };
-
-        // Check if the constant is defined first
-        let defined = unsafe { rb_sys::rb_const_defined(self.as_value().as_raw(), id) != 0 };
+        let module = self.as_raw();
 
-        if !defined {
-            return Err(Error::new(
-                ExceptionClass::NameError,
-                format!("uninitialized constant {}", name),
-            ));
-        }
-
-        // SAFETY: We've verified the constant exists, so rb_const_get should succeed
-        let val = unsafe { rb_sys::rb_const_get(self.as_value().as_raw(), id) };
+        // SAFETY: module is a valid module/class, c_name is a valid C string;
+        // rb_const_get raises NameError for missing constants
+        let val = protect(|| unsafe {
+            rb_sys::rb_const_get(module, rb_sys::rb_intern(c_name.as_ptr()))
+        })?;
 
+        // SAFETY: rb_const_get returns a valid VALUE
         Ok(unsafe { Value::from_raw(val) })
     }
 
@@ -229,8 +209,7 @@ pub trait Module: ReprValue {
     /// # Arguments
     ///
     /// * `name` - The method name (Ruby-style method names like "foo" or "foo_bar")
-    /// * `func` - A function pointer generated by the `method!` macro
-    /// * `arity` - The number of arguments (-1 for variadic, 0-15 for fixed)
+    /// * `func` - A function generated by the `method!` macro, which carries its arity
     ///
     /// # Example
     ///
@@ -246,28 +225,27 @@ pub trait Module: ReprValue {
     /// let ruby = unsafe { Ruby::get() };
     /// let class = ruby.define_class("MyClass", ruby.class_object());
     /// let rclass = RClass::try_convert(class)?;
-    /// rclass.define_method("my_method", method!(my_method, 0), 0)?;
+    /// rclass.define_method("my_method", method!(my_method, 0))?;
     /// # Ok(())
     /// # }
     /// ```
-    fn define_method(
+    fn define_method<const ARITY: i32>(
         self,
         name: &str,
-        func: unsafe extern "C" fn() -> rb_sys::VALUE,
-        arity: i32,
+        func: MethodFn<ARITY>,
     ) -> Result<(), Error> {
         // Convert name to C string
         let c_name = std::ffi::CString::new(name)
             .map_err(|_| Error::argument("method name contains null byte"))?;
 
-        // SAFETY: self is a valid module/class, c_name is a valid C string
+        let module = self.as_raw();
+
+        // SAFETY: module is a valid module/class, c_name is a valid C string
         // rb_define_method registers the function pointer with Ruby
         // The function pointer must remain valid for the lifetime of the Ruby VM
-        unsafe {
-            rb_sys::rb_define_method(self.as_value().as_raw(), c_name.as_ptr(), Some(func), arity);
-        }
-
-        Ok(())
+        protect(|| unsafe {
+            rb_sys::rb_define_method(module, c_name.as_ptr(), Some(func.as_raw()), ARITY);
+        })
     }
 
     /// Define a singleton method on this class/module.
@@ -278,8 +256,8 @@ pub trait Module: ReprValue {
     /// # Arguments
     ///
     /// * `name` - The method name
-    /// * `func` - A function pointer generated by the `method!` or `function!` macro
-    /// * `arity` - The number of arguments (-1 for variadic, 0-15 for fixed)
+    /// * `func` - A function generated by the `method!` or `function!` macro,
+    ///   which carries its arity
     ///
     /// # Example
     ///
@@ -296,32 +274,26 @@ pub trait Module: ReprValue {
     /// let ruby = unsafe { Ruby::get() };
     /// let class = ruby.define_class("MyClass", ruby.class_object());
     /// let rclass = RClass::try_convert(class)?;
-    /// rclass.define_singleton_method("class_method", function!(class_method, 0), 0)?;
+    /// rclass.define_singleton_method("class_method", function!(class_method, 0))?;
     /// # Ok(())
     /// # }
     /// ```
-    fn define_singleton_method(
+    fn define_singleton_method<const ARITY: i32>(
         self,
         name: &str,
-        func: unsafe extern "C" fn() -> rb_sys::VALUE,
-        arity: i32,
+        func: MethodFn<ARITY>,
     ) -> Result<(), Error> {
         // Convert name to C string
         let c_name = std::ffi::CString::new(name)
             .map_err(|_| Error::argument("method name contains null byte"))?;
 
-        // SAFETY: self is a valid module/class, c_name is a valid C string
-        // rb_define_singleton_method registers the function pointer with Ruby
-        unsafe {
-            rb_sys::rb_define_singleton_method(
-                self.as_value().as_raw(),
-                c_name.as_ptr(),
-                Some(func),
-                arity,
-            );
-        }
+        let module = self.as_raw();
 
-        Ok(())
+        // SAFETY: module is a valid module/class, c_name is a valid C string
+        // rb_define_singleton_method registers the function pointer with Ruby
+        protect(|| unsafe {
+            rb_sys::rb_define_singleton_method(module, c_name.as_ptr(), Some(func.as_raw()), ARITY);
+        })
     }
 
     /// Define a module function.
@@ -333,8 +305,8 @@ pub trait Module: ReprValue {
     /// # Arguments
     ///
     /// * `name` - The function name
-    /// * `func` - A function pointer generated by the `method!` or `function!` macro
-    /// * `arity` - The number of arguments (-1 for variadic, 0-15 for fixed)
+    /// * `func` - A function generated by the `method!` or `function!` macro,
+    ///   which carries its arity
     ///
     /// # Example
     ///
@@ -351,32 +323,26 @@ pub trait Module: ReprValue {
     /// let ruby = unsafe { Ruby::get() };
     /// let module = ruby.define_module("MyModule");
     /// let rmodule = RModule::try_convert(module)?;
-    /// rmodule.define_module_function("my_function", function!(my_function, 0), 0)?;
+    /// rmodule.define_module_function("my_function", function!(my_function, 0))?;
     /// # Ok(())
     /// # }
     /// ```
-    fn define_module_function(
+    fn define_module_function<const ARITY: i32>(
         self,
         name: &str,
-        func: unsafe extern "C" fn() -> rb_sys::VALUE,
-        arity: i32,
+        func: MethodFn<ARITY>,
     ) -> Result<(), Error> {
         // Convert name to C string
         let c_name = std::ffi::CString::new(name)
             .map_err(|_| Error::argument("method name contains null byte"))?;
 
-        // SAFETY: self is a valid module, c_name is a valid C string
-        // rb_define_module_function registers the function as both instance and singleton
-        unsafe {
-            rb_sys::rb_define_module_function(
-                self.as_value().as_raw(),
-                c_name.as_ptr(),
-                Some(func),
-                arity,
-            );
-        }
+        let module = self.as_raw();
 
-        Ok(())
+        // SAFETY: module is a valid module, c_name is a valid C string
+        // rb_define_module_function registers the function as both instance and singleton
+        protect(|| unsafe {
+            rb_sys::rb_define_module_function(module, c_name.as_ptr(), Some(func.as_raw()), ARITY);
+        })
     }
 }
 
@@ -398,7 +364,6 @@ mod tests {
     }
 
     #[ruby_test]
-    #[ignore] // TODO: Fix exception handling in from_name
     fn test_rmodule_from_name_missing() {
         let missing = RModule::from_name("NonExistentModule123");
         assert!(missing.is_none());
@@ -482,6 +447,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[ruby_test]
+    fn test_module_trait_const_get_missing_carries_exception() {
+        let string_class = RClass::from_name("String").unwrap();
+
+        let err = string_class.const_get("NONEXISTENT_CONST_XYZ").unwrap_err();
+        assert!(err.exception().is_some());
+        assert!(err.message().contains("NONEXISTENT_CONST_XYZ"));
+    }
+
+    #[ruby_test]
+    fn test_module_trait_define_const_invalid_name() {
+        let string_class = RClass::from_name("String").unwrap();
+
+        // Constant names must start with an uppercase letter
+        let result = string_class.define_const("not_a_constant", 1i64);
+        assert!(result.is_err());
+    }
+
     #[ruby_test]
     fn test_module_trait_const_get_builtin() {
         let file_class = RClass::from_name("File").unwrap();
@@ -561,7 +544,7 @@ mod tests {
 
         // Define an instance method - if this doesn't crash, it worked
         rclass
-            .define_method("test_method", method!(test_method_arity_0, 0), 0)
+            .define_method("test_method", method!(test_method_arity_0, 0))
             .unwrap();
 
         // Success - method was registered without crashing
@@ -577,7 +560,7 @@ mod tests {
 
         // Define an instance method with 1 argument - if this doesn't crash, it worked
         rclass
-            .define_method("test_method_arg", method!(test_method_arity_1, 1), 1)
+            .define_method("test_method_arg", method!(test_method_arity_1, 1))
             .unwrap();
     }
 
@@ -591,7 +574,7 @@ mod tests {
 
         // Define a singleton method (class method) - if this doesn't crash, it worked
         rclass
-            .define_singleton_method("test_class_method", function!(test_function_arity_0, 0), 0)
+            .define_singleton_method("test_class_method", function!(test_function_arity_0, 0))
             .unwrap();
     }
 
@@ -605,11 +588,7 @@ mod tests {
 
         // Define a singleton method with 1 argument - if this doesn't crash, it worked
         rclass
-            .define_singleton_method(
-                "test_class_method_arg",
-                function!(test_function_arity_1, 1),
-                1,
-            )
+            .define_singleton_method("test_class_method_arg", function!(test_function_arity_1, 1))
             .unwrap();
     }
 
@@ -623,7 +602,7 @@ mod tests {
 
         // Define a module function - if this doesn't crash, it worked
         rmodule
-            .define_module_function("test_mod_func", function!(test_function_arity_0, 0), 0)
+            .define_module_function("test_mod_func", function!(test_function_arity_0, 0))
             .unwrap();
     }
 
@@ -636,8 +615,140 @@ mod tests {
         let rclass = RClass::try_convert(class).unwrap();
 
         // Try to define a method with a null byte in the name
-        let result = rclass.define_method("test\0method", method!(test_method_arity_0, 0), 0);
+        let result = rclass.define_method("test\0method", method!(test_method_arity_0, 0));
 
         assert!(result.is_err());
     }
+
+    fn test_method_variadic(
+        _ctx: &crate::Context,
+        _rb_self: crate::Value,
+        args: &[crate::Value],
+    ) -> Result<i64, Error> {
+        let crate::method::Args {
+            required: (a,),
+            optional: (b,),
+            splat,
+            ..
+        } = crate::method::scan_args::<(i64,), (Option<i64>,), &[crate::Value], ()>(args)?;
+        Ok(a + b.unwrap_or(0) + splat.len() as i64)
+    }
+
+    fn test_function_array_args(
+        _ctx: &crate::Context,
+        args: Pin<&crate::value::StackPinned<crate::types::RArray>>,
+    ) -> Result<i64, Error> {
+        Ok(args.get().len() as i64)
+    }
+
+    #[ruby_test]
+    fn test_module_define_method_variadic() {
+        use crate::Ruby;
+
+        let ruby = unsafe { Ruby::get() };
+        let class = ruby.define_class("TestDefineMethodVariadic", ruby.class_object());
+        let rclass = RClass::try_convert(class).unwrap();
+        rclass
+            .clone()
+            .define_method("sum", method!(test_method_variadic, -1))
+            .unwrap();
+
+        let obj = rclass.as_value().funcall_boxed("new", ()).unwrap();
+        let sum = |result: Result<crate::BoxValue<crate::Value>, Error>| {
+            i64::try_convert(result.unwrap().inner()).unwrap()
+        };
+        assert_eq!(sum(obj.funcall_boxed("sum", [1i64])), 1);
+        assert_eq!(sum(obj.funcall_boxed("sum", [1i64, 2])), 3);
+        assert_eq!(sum(obj.funcall_boxed("sum", [1i64, 2, 3, 4])), 5);
+
+        let err = obj.funcall_boxed("sum", ()).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(
+            err.message(),
+            "wrong number of arguments (given 0, expected 1+)"
+        );
+    }
+
+    #[ruby_test]
+    fn test_module_define_singleton_method_array_args() {
+        use crate::Ruby;
+
+        let ruby = unsafe { Ruby::get() };
+        let class = ruby.define_class("TestDefineSingletonArray", ruby.class_object());
+        let rclass = RClass::try_convert(class).unwrap();
+        rclass
+            .clone()
+            .define_singleton_method("count", function!(test_function_array_args, -2))
+            .unwrap();
+
+        let count = rclass
+            .as_value()
+            .funcall_boxed("count", ("a", "b", "c"))
+            .unwrap();
+        assert_eq!(i64::try_convert(count.inner()).unwrap(), 3);
+    }
+
+    // Creates 10 strings, which needs a Context with at least 10 slots
+    fn fill_context<'ctx, const N: usize>(
+        ctx: &'ctx crate::Context<'ctx, N>,
+    ) -> Result<usize, Error> {
+        for i in 0..10 {
+            ctx.new_string(&i.to_string())?;
+        }
+        Ok(ctx.available())
+    }
+
+    fn fill_large_context<'ctx>(ctx: &'ctx crate::Context<'ctx, 16>) -> Result<usize, Error> {
+        fill_context(ctx)
+    }
+
+    fn fill_default_context<'ctx>(ctx: &'ctx crate::Context) -> Result<usize, Error> {
+        fill_context(ctx)
+    }
+
+    #[ruby_test]
+    fn test_context_capacity() {
+        use crate::Ruby;
+
+        let ruby = unsafe { Ruby::get() };
+        let class = ruby.define_class("TestContextCapacity", ruby.class_object());
+        let rclass = RClass::try_convert(class).unwrap();
+        rclass
+            .clone()
+            .define_singleton_method("default", function!(fill_default_context, 0))
+            .unwrap();
+        rclass
+            .clone()
+            .define_singleton_method("large", function!(fill_large_context, 0))
+            .unwrap();
+        rclass
+            .clone()
+            .define_singleton_method("sized", function!(fill_context, 0, slots = 12))
+            .unwrap();
+
+        let available = |name: &str| {
+            let result = rclass.as_value().funcall_boxed(name, ());
+            result.map(|value| i64::try_convert(value.inner()).unwrap())
+        };
+        assert_eq!(available("large").unwrap(), 6);
+        assert_eq!(available("sized").unwrap(), 2);
+        let err = available("default").unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::RuntimeError.as_value()));
+
+        // Slot usage is only recorded in debug builds
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let usage = crate::context::slot_usage();
+        let large = usage
+            .iter()
+            .find(|usage| usage.method == "fill_large_context")
+            .unwrap();
+        assert_eq!((large.high_water, large.capacity), (10, 16));
+        let default = usage
+            .iter()
+            .find(|usage| usage.method == "fill_default_context")
+            .unwrap();
+        assert_eq!((default.high_water, default.capacity), (8, 8));
+    }
 }
@@ -0,0 +1,464 @@
+//! Ruby Proc and Method types.
+
+use std::os::raw::c_int;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+use crate::context::Context;
+use crate::convert::{ArgList, IntoValue, TryConvert};
+use crate::error::{Error, protect};
+use crate::typed_data::{self, DataType, DataTypeBuilder, TypedData};
+use crate::value::{BoxValue, ReprValue, StackPinned, Value};
+
+/// Ruby Proc (heap allocated).
+///
+/// An `RProc` is either a block or lambda created in Ruby, or a Rust closure
+/// turned into a proc with [`RProc::from_fn_boxed`] or `Context::new_proc()`.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// fn apply_twice(callback: &RProc, value: i64) -> Result<i64, Error> {
+///     let once = callback.call_boxed((value,))?;
+///     let twice = callback.call_boxed((once.inner(),))?;
+///     i64::try_convert(twice.inner())
+/// }
+///
+/// let double = RProc::from_fn_boxed(|args| Ok(i64::try_convert(args[0].clone())? * 2));
+/// assert_eq!(apply_twice(&double, 3).unwrap(), 12);
+/// ```
+#[derive(Clone, Debug)]
+#[repr(transparent)]
+pub struct RProc(Value);
+
+impl RProc {
+    /// Create a proc that calls a Rust closure.
+    ///
+    /// The closure receives the arguments the proc was called with and its
+    /// result is returned to Ruby. An `Err` (or a panic) is raised as a Ruby
+    /// exception. The proc is not a lambda, so Ruby doesn't check the number
+    /// of arguments; the closure sees exactly what it was called with.
+    ///
+    /// The closure is stored in a Ruby object owned by the proc and dropped
+    /// when the proc is garbage collected. Ruby values captured by the
+    /// closure must be [`BoxValue`]s so the GC can see them.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the returned value is:
+    /// - Pinned on the stack with `pin_on_stack!`, OR
+    /// - Immediately boxed with `.into_box()`, OR
+    /// - Immediately returned to Ruby
+    ///
+    /// For safe alternatives, use:
+    /// - `RProc::from_fn_boxed()` for heap storage
+    /// - `Context::new_proc()` for stack-pinned procs in methods
+    pub unsafe fn from_fn<F, R>(func: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<R, Error> + 'static,
+        R: IntoValue,
+    {
+        let closure = ProcClosure {
+            func: Box::new(move |args| func(args).map(IntoValue::into_value)),
+        };
+
+        // The closure object has no class, so it is invisible to Ruby code.
+        // It stays on the stack until rb_proc_new takes it, and from then on
+        // the proc marks it for as long as the proc is alive.
+        // SAFETY: a class of 0 makes a hidden object
+        let data = unsafe { typed_data::wrap_raw(0, closure) };
+
+        // SAFETY: call_closure matches rb_block_call_func and data is the
+        // closure object it expects as its callback argument
+        let val = unsafe { rb_sys::rb_proc_new(Some(call_closure), data) };
+        // SAFETY: rb_proc_new returns a valid Proc
+        RProc(unsafe { Value::from_raw(val) })
+    }
+
+    /// Create a proc that calls a Rust closure, boxed for heap storage.
+    ///
+    /// See [`from_fn`](Self::from_fn) for how the closure is called.
+    /// Use `Context::new_proc()` for stack-pinned procs within methods.
+    pub fn from_fn_boxed<F, R>(func: F) -> BoxValue<Self>
+    where
+        F: Fn(&[Value]) -> Result<R, Error> + 'static,
+        R: IntoValue,
+    {
+        // SAFETY: We immediately box and register with GC
+        BoxValue::new(unsafe { Self::from_fn(func) })
+    }
+
+    /// Call the proc, storing the result in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the proc raises, or if the Context has no free slots
+    /// to hold the result.
+    pub fn call<'ctx, A: ArgList, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        args: A,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_raw(args)?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Call the proc, returning a heap-allocated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the proc raises.
+    pub fn call_boxed<A: ArgList>(&self, args: A) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_raw(args)?))
+    }
+
+    /// The number of arguments the proc takes, as `Proc#arity` reports it.
+    ///
+    /// Procs with optional arguments or a splat return `-n - 1`, where `n`
+    /// is the number of required arguments.
+    pub fn arity(&self) -> i32 {
+        // SAFETY: self is a valid Proc
+        unsafe { rb_sys::rb_proc_arity(self.0.as_raw()) as i32 }
+    }
+
+    /// Check whether the proc is a lambda.
+    pub fn is_lambda(&self) -> bool {
+        // SAFETY: self is a valid Proc; rb_proc_lambda_p returns Qtrue or Qfalse
+        unsafe { Value::from_raw(rb_sys::rb_proc_lambda_p(self.0.as_raw())).is_true() }
+    }
+
+    /// Call the proc, catching any exception it raises.
+    fn call_raw<A: ArgList>(&self, args: A) -> Result<Value, Error> {
+        let recv = self.0.as_raw();
+        call_with_args(args, |argc, argv| unsafe {
+            // SAFETY: recv is a valid Proc and argv points to argc VALUEs
+            rb_sys::rb_proc_call_with_block(recv, argc, argv, rb_sys::Qnil.into())
+        })
+    }
+}
+
+impl ReprValue for RProc {
+    #[inline]
+    fn as_value(&self) -> Value {
+        self.0.clone()
+    }
+
+    #[inline]
+    unsafe fn from_value_unchecked(val: Value) -> Self {
+        RProc(val)
+    }
+}
+
+impl TryConvert for RProc {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        // SAFETY: val is a valid VALUE; rb_obj_is_proc returns Qtrue or Qfalse
+        let is_proc = unsafe { Value::from_raw(rb_sys::rb_obj_is_proc(val.as_raw())).is_true() };
+        if is_proc {
+            // SAFETY: We've verified it's a Proc
+            Ok(unsafe { RProc::from_value_unchecked(val) })
+        } else {
+            Err(Error::type_error("expected Proc"))
+        }
+    }
+}
+
+impl IntoValue for RProc {
+    #[inline]
+    fn into_value(self) -> Value {
+        self.0
+    }
+}
+
+/// Ruby Method (heap allocated).
+///
+/// A method bound to its receiver, as returned by `Object#method`.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::prelude::*;
+///
+/// let method = Value::nil().funcall_boxed("eval", ("1.method(:+)",)).unwrap();
+/// let method = RMethod::try_convert(method.inner()).unwrap();
+/// let sum = method.call_boxed((2i64,)).unwrap();
+/// assert_eq!(i64::try_convert(sum.inner()).unwrap(), 3);
+/// ```
+#[derive(Clone, Debug)]
+#[repr(transparent)]
+pub struct RMethod(Value);
+
+impl RMethod {
+    /// Call the method, storing the result in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises, or if the Context has no free
+    /// slots to hold the result.
+    pub fn call<'ctx, A: ArgList, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        args: A,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_raw(args)?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Call the method, returning a heap-allocated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises.
+    pub fn call_boxed<A: ArgList>(&self, args: A) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_raw(args)?))
+    }
+
+    /// The number of arguments the method takes, as `Method#arity` reports it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `arity` has been redefined to raise or return
+    /// something other than an Integer.
+    pub fn arity(&self) -> Result<i32, Error> {
+        let arity = self.0.funcall_boxed("arity", ())?;
+        i32::try_convert(arity.inner())
+    }
+
+    /// Call the method, catching any exception it raises.
+    fn call_raw<A: ArgList>(&self, args: A) -> Result<Value, Error> {
+        let recv = self.0.as_raw();
+        call_with_args(args, |argc, argv| unsafe {
+            // SAFETY: recv is a valid Method and argv points to argc VALUEs
+            rb_sys::rb_method_call(argc, argv, recv)
+        })
+    }
+}
+
+impl ReprValue for RMethod {
+    #[inline]
+    fn as_value(&self) -> Value {
+        self.0.clone()
+    }
+
+    #[inline]
+    unsafe fn from_value_unchecked(val: Value) -> Self {
+        RMethod(val)
+    }
+}
+
+impl TryConvert for RMethod {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        // SAFETY: val is a valid VALUE; rb_obj_is_method returns Qtrue or Qfalse
+        let is_method =
+            unsafe { Value::from_raw(rb_sys::rb_obj_is_method(val.as_raw())).is_true() };
+        if is_method {
+            // SAFETY: We've verified it's a Method
+            Ok(unsafe { RMethod::from_value_unchecked(val) })
+        } else {
+            Err(Error::type_error("expected Method"))
+        }
+    }
+}
+
+impl IntoValue for RMethod {
+    #[inline]
+    fn into_value(self) -> Value {
+        self.0
+    }
+}
+
+/// Convert `args` and pass them to `call` as `argc`/`argv`, catching any
+/// exception it raises.
+fn call_with_args<A: ArgList>(
+    args: A,
+    call: impl FnOnce(c_int, *const rb_sys::VALUE) -> rb_sys::VALUE,
+) -> Result<Value, Error> {
+    // The converted arguments live in a stack array for the duration of
+    // the call, so Ruby's GC can see them.
+    let args = args.into_arg_list();
+    let args = args.as_ref();
+    // Value is #[repr(transparent)] over VALUE, so the slice can be passed as argv
+    let argv = args.as_ptr() as *const rb_sys::VALUE;
+    let argc = args.len() as c_int;
+
+    let raw = protect(|| call(argc, argv))?;
+
+    // SAFETY: the call returned a valid VALUE
+    Ok(unsafe { Value::from_raw(raw) })
+}
+
+// ============================================================================
+// Closure storage
+// ============================================================================
+
+type ProcFn = dyn Fn(&[Value]) -> Result<Value, Error>;
+
+/// The Rust closure behind a proc created by [`RProc::from_fn`].
+struct ProcClosure {
+    func: Box<ProcFn>,
+}
+
+impl TypedData for ProcClosure {
+    fn class_name() -> &'static str {
+        "solidus::ProcClosure"
+    }
+
+    fn data_type() -> &'static DataType {
+        static DATA_TYPE: OnceLock<DataType> = OnceLock::new();
+        DATA_TYPE
+            .get_or_init(|| DataTypeBuilder::<ProcClosure>::new("solidus::ProcClosure").build())
+    }
+}
+
+/// `rb_block_call_func` for procs created by [`RProc::from_fn`].
+///
+/// `data` is the closure object passed to `rb_proc_new`.
+unsafe extern "C" fn call_closure(
+    _yielded_arg: rb_sys::VALUE,
+    data: rb_sys::VALUE,
+    argc: c_int,
+    argv: *const rb_sys::VALUE,
+    _blockarg: rb_sys::VALUE,
+) -> rb_sys::VALUE {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: data is the closure object this proc was created with
+        let data = unsafe { Value::from_raw(data) };
+        let closure = typed_data::get::<ProcClosure>(&data)?;
+        // SAFETY: Ruby passes argc valid VALUEs that stay on its stack for the call
+        let args = unsafe { crate::method::args_from_raw(argc, argv) };
+        (closure.func)(args).map(|value| value.as_raw())
+    }));
+
+    // Everything created by the call has been dropped by now, so raising
+    // can't leak it.
+    match crate::method::finish_call(result) {
+        Ok(value) => value,
+        // SAFETY: nothing is left on this frame that needs dropping
+        Err(pending) => unsafe { pending.perform() },
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use crate::test_util::eval;
+    use crate::types::{RArray, RString};
+    use rb_sys_test_helpers::ruby_test;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[ruby_test]
+    fn test_call_ruby_lambda() {
+        let add = RProc::try_convert(eval("->(a, b) { a + b }").unwrap().inner()).unwrap();
+        assert!(add.is_lambda());
+        assert_eq!(add.arity(), 2);
+
+        let ctx: Context<'_> = Context::default();
+        let result = add.call(&ctx, (1i64, 2i64)).unwrap();
+        assert_eq!(i64::try_convert(result.get().clone()).unwrap(), 3);
+
+        // Lambdas check their arguments
+        assert!(add.call_boxed((1i64,)).is_err());
+    }
+
+    #[ruby_test]
+    fn test_call_ruby_proc() {
+        let proc = RProc::try_convert(eval("proc { |a, b = 10| a + b }").unwrap().inner()).unwrap();
+        assert!(!proc.is_lambda());
+        assert_eq!(proc.arity(), -2);
+
+        let result = proc.call_boxed((1i64,)).unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 11);
+    }
+
+    #[ruby_test]
+    fn test_call_raises() {
+        let proc = RProc::try_convert(
+            eval("proc { raise ArgumentError, 'boom' }")
+                .unwrap()
+                .inner(),
+        )
+        .unwrap();
+        let err = proc.call_boxed(()).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert_eq!(err.message(), "boom");
+    }
+
+    #[ruby_test]
+    fn test_try_convert_rejects_non_proc() {
+        assert!(RProc::try_convert(eval("1").unwrap().inner()).is_err());
+        assert!(RMethod::try_convert(eval("proc {}").unwrap().inner()).is_err());
+    }
+
+    #[ruby_test]
+    fn test_from_fn() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let sum = RProc::from_fn_boxed(move |args| {
+            counter.set(counter.get() + 1);
+            args.iter()
+                .try_fold(0i64, |acc, arg| Ok(acc + i64::try_convert(arg.clone())?))
+        });
+        assert!(!sum.is_lambda());
+
+        let result = sum.call_boxed((1i64, 2i64, 3i64)).unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 6);
+
+        // Ruby can call it too
+        let result = eval("->(f) { [1, 2].map(&f) }")
+            .unwrap()
+            .funcall_boxed("call", (sum.as_value(),))
+            .unwrap();
+        let result = RArray::try_convert(result.inner()).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[ruby_test]
+    fn test_from_fn_error_is_raised() {
+        let fail = RProc::from_fn_boxed(|_args| -> Result<Value, Error> {
+            Err(Error::argument("from closure"))
+        });
+        let err = eval("->(f) { begin; f.call; rescue ArgumentError => e; e.message; end }")
+            .unwrap()
+            .funcall_boxed("call", (fail.as_value(),))
+            .unwrap();
+        let message = RString::try_convert(err.inner()).unwrap();
+        assert_eq!(message.to_string().unwrap(), "from closure");
+    }
+
+    #[ruby_test]
+    fn test_from_fn_survives_gc() {
+        let greeting = RString::new_boxed("hello");
+        let proc = RProc::from_fn_boxed(move |_args| greeting.to_string());
+        eval("GC.start(full_mark: true, immediate_sweep: true)").unwrap();
+
+        // The closure object is kept alive by the proc
+        let result = proc.call_boxed(()).unwrap();
+        let result = RString::try_convert(result.inner()).unwrap();
+        assert_eq!(result.to_string().unwrap(), "hello");
+    }
+
+    #[ruby_test]
+    fn test_context_new_proc() {
+        let ctx: Context<'_> = Context::default();
+        let proc = ctx.new_proc(|args| Ok(args.len() as i64)).unwrap();
+        let result = proc.get().call(&ctx, ("a", "b")).unwrap();
+        assert_eq!(i64::try_convert(result.get().clone()).unwrap(), 2);
+        assert_eq!(ctx.available(), 6);
+    }
+
+    #[ruby_test]
+    fn test_method_call() {
+        let method = RMethod::try_convert(eval("1.method(:+)").unwrap().inner()).unwrap();
+        assert_eq!(method.arity().unwrap(), 1);
+
+        let ctx: Context<'_> = Context::default();
+        let result = method.call(&ctx, (41i64,)).unwrap();
+        assert_eq!(i64::try_convert(result.get().clone()).unwrap(), 42);
+
+        assert!(method.call_boxed(()).is_err());
+    }
+}
@@ -1,10 +1,13 @@
 //! Ruby String type.
 
 use std::ffi::CStr;
+use std::pin::Pin;
 
+use crate::context::Context;
 use crate::convert::{IntoValue, TryConvert};
-use crate::error::Error;
-use crate::value::{BoxValue, NewValue, ReprValue, Value};
+use crate::error::{Error, protect};
+use crate::types::{RArray, Symbol};
+use crate::value::{BoxValue, NewValue, ReprValue, StackPinned, Value};
 
 /// Ruby String (heap allocated).
 ///
@@ -33,6 +36,8 @@ pub struct RString(Value);
 impl RString {
     /// Create a new Ruby string from a Rust string slice.
     ///
+    /// The string has UTF-8 encoding.
+    ///
     /// Returns a `NewValue<RString>` guard that must be either:
     /// - Pinned on the stack with `pin_on_stack!`, OR
     /// - Boxed for heap storage with `.into_box()`, OR
@@ -63,10 +68,10 @@ impl RString {
     /// ```
     pub unsafe fn new(s: &str) -> NewValue<Self> {
         // SAFETY: Caller ensures the returned value is properly handled
-        NewValue::new(unsafe { Self::from_slice_raw(s.as_bytes()) })
+        NewValue::new(unsafe { Self::from_str_raw(s) })
     }
 
-    /// Create a new Ruby string, boxed for heap storage.
+    /// Create a new UTF-8 Ruby string, boxed for heap storage.
     ///
     /// This is safe because the value is immediately registered with Ruby's GC.
     /// Use `Context::new_string()` for stack-pinned strings within methods.
@@ -81,11 +86,25 @@ impl RString {
     /// ```
     pub fn new_boxed(s: &str) -> BoxValue<Self> {
         // SAFETY: We immediately box and register with GC
-        unsafe { BoxValue::new(Self::from_slice_raw(s.as_bytes())) }
+        unsafe { BoxValue::new(Self::from_str_raw(s)) }
+    }
+
+    /// Internal: Create a new UTF-8 Ruby string without guard.
+    #[doc(hidden)]
+    pub(crate) unsafe fn from_str_raw(s: &str) -> Self {
+        // SAFETY: rb_utf8_str_new creates a new UTF-8 Ruby string with the
+        // given bytes
+        let val = unsafe {
+            rb_sys::rb_utf8_str_new(s.as_ptr() as *const std::os::raw::c_char, s.len() as _)
+        };
+        // SAFETY: rb_utf8_str_new returns a valid VALUE
+        RString(unsafe { Value::from_raw(val) })
     }
 
     /// Create a new Ruby string from a byte slice.
     ///
+    /// The string has binary (ASCII-8BIT) encoding.
+    ///
     /// Returns a `NewValue<RString>` guard that must be either:
     /// - Pinned on the stack with `pin_on_stack!`, OR
     /// - Boxed for heap storage with `.into_box()`, OR
@@ -129,7 +148,7 @@ impl RString {
         RString(unsafe { Value::from_raw(val) })
     }
 
-    /// Create a new Ruby string from bytes, boxed for heap storage.
+    /// Create a new binary Ruby string from bytes, boxed for heap storage.
     ///
     /// This is safe because the value is immediately registered with Ruby's GC.
     ///
@@ -213,6 +232,83 @@ impl RString {
         }
     }
 
+    /// Borrow the string contents as bytes, without copying.
+    ///
+    /// Unlike [`as_slice`](Self::as_slice), the slice borrows from `self`, so
+    /// it can't outlive the pinned or boxed string it came from.
+    ///
+    /// # Safety
+    ///
+    /// No Ruby code may run while the slice is in use, since it could modify
+    /// the string and invalidate the slice. Use [`lock`](Self::lock) to get a
+    /// slice that stays valid across calls into Ruby.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// // SAFETY: no Ruby code runs while `bytes` is in use
+    /// let count = unsafe { s.as_bytes() }.iter().filter(|b| **b == b'l').count();
+    /// assert_eq!(count, 2);
+    /// ```
+    #[inline]
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the caller ensures the string isn't modified while borrowed
+        unsafe { self.as_slice() }
+    }
+
+    /// Borrow the string contents as UTF-8, without copying.
+    ///
+    /// # Safety
+    ///
+    /// As for [`as_bytes`](Self::as_bytes), no Ruby code may run while the
+    /// `&str` is in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if the contents are not valid UTF-8.
+    pub unsafe fn as_str(&self) -> Result<&str, Error> {
+        // SAFETY: the caller ensures the string isn't modified while borrowed
+        str_from_utf8(unsafe { self.as_bytes() })
+    }
+
+    /// Lock the string's buffer, for borrowing its contents while Ruby code
+    /// runs or the GVL is released.
+    ///
+    /// While the returned [`StringLock`] is alive, any attempt to modify the
+    /// string, from Rust or from Ruby, raises a `RuntimeError` instead of
+    /// moving or freeing the buffer. The lock is released when the guard is
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RuntimeError` if the string is already locked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn checksum(_ctx: &Context, rb_self: RString) -> Result<u64, Error> {
+    ///     let lock = rb_self.lock()?;
+    ///     let bytes = lock.as_bytes();
+    ///     solidus::without_gvl(
+    ///         || bytes.iter().fold(0u64, |sum, b| sum.wrapping_mul(31).wrapping_add(*b as u64)),
+    ///         (),
+    ///     )
+    /// }
+    /// ```
+    pub fn lock(&self) -> Result<StringLock<'_>, Error> {
+        let str_val = self.0.as_raw();
+        // SAFETY: str_val is a valid Ruby string
+        protect(|| unsafe {
+            rb_sys::rb_str_locktmp(str_val);
+        })?;
+        Ok(StringLock { string: self })
+    }
+
     /// Copy string contents to a Rust String.
     ///
     /// Returns an error if the string contains invalid UTF-8.
@@ -228,17 +324,840 @@ impl RString {
     pub fn to_string(&self) -> Result<String, Error> {
         // SAFETY: We immediately copy the bytes, so they don't outlive the string
         let bytes = unsafe { self.as_slice() };
-        String::from_utf8(bytes.to_vec()).map_err(|e| {
-            Error::new(
-                crate::ExceptionClass::TypeError,
-                format!("invalid UTF-8 in Ruby string: {}", e),
-            )
+        str_from_utf8(bytes).map(str::to_owned)
+    }
+
+    /// Copy string contents to a byte vector.
+    ///
+    /// This always succeeds and works with any byte sequence.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// assert_eq!(s.to_bytes(), b"hello");
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // SAFETY: We immediately copy the bytes, so they don't outlive the string
+        unsafe { self.as_slice().to_vec() }
+    }
+
+    /// Get the encoding of this string.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// let enc = s.encoding();
+    /// ```
+    pub fn encoding(&self) -> Encoding {
+        // SAFETY: self.0 is a valid Ruby string VALUE
+        let enc_ptr = unsafe { rb_sys::rb_enc_get(self.0.as_raw()) };
+        Encoding { ptr: enc_ptr }
+    }
+
+    /// Encode this string to a different encoding.
+    ///
+    /// Returns a new string with the specified encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns the Ruby exception (e.g. `Encoding::UndefinedConversionError`)
+    /// if the string cannot be converted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::{RString, Encoding};
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// let utf8 = Encoding::utf8();
+    /// let encoded = s.encode(utf8).unwrap();
+    /// ```
+    pub fn encode(&self, encoding: Encoding) -> Result<RString, Error> {
+        let str_val = self.0.as_raw();
+
+        // SAFETY: str_val is a valid Ruby string, encoding.ptr is a valid encoding
+        let val = protect(|| unsafe {
+            let enc_value = rb_sys::rb_enc_from_encoding(encoding.ptr);
+            rb_sys::rb_str_encode(str_val, enc_value, 0, rb_sys::Qnil.into())
+        })?;
+
+        // SAFETY: rb_str_encode returns a valid Ruby string
+        Ok(RString(unsafe { Value::from_raw(val) }))
+    }
+
+    /// Encode this string to a different encoding, with options for
+    /// characters that can't be converted, storing the result in the
+    /// Context.
+    ///
+    /// This is `String#encode` with `invalid:`, `undef:` and `replace:`
+    /// options; see [`EncodeOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EncodingError` (such as
+    /// `Encoding::UndefinedConversionError`) if the string can't be
+    /// converted and the options don't say to replace the offending
+    /// characters, or `Err` if the Context has no free slots to hold the
+    /// result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    /// use solidus::types::EncodeOptions;
+    ///
+    /// fn to_ascii<'ctx>(ctx: &'ctx Context<'ctx>, s: RString) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+    ///     let options = EncodeOptions::new().replace_invalid().replace_undefined().replacement("?");
+    ///     s.encode_with(ctx, Encoding::us_ascii(), &options)
+    /// }
+    /// ```
+    pub fn encode_with<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        encoding: Encoding,
+        options: &EncodeOptions,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let encoded = self.encode_with_raw(&encoding, options)?;
+        Ok(ctx.pin_value(encoded)?)
+    }
+
+    /// Encode this string to a different encoding, with options for
+    /// characters that can't be converted, boxing the result for heap
+    /// storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EncodingError` if the string can't be converted; see
+    /// [`encode_with`](Self::encode_with).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::{EncodeOptions, Encoding, RString};
+    ///
+    /// let s = RString::new_boxed("café");
+    /// let options = EncodeOptions::new().replace_undefined().replacement("?");
+    /// let ascii = s.encode_with_boxed(Encoding::us_ascii(), &options)?;
+    /// assert_eq!(ascii.to_string()?, "caf?");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_with_boxed(
+        &self,
+        encoding: Encoding,
+        options: &EncodeOptions,
+    ) -> Result<BoxValue<RString>, Error> {
+        self.encode_with_raw(&encoding, options).map(BoxValue::new)
+    }
+
+    fn encode_with_raw(
+        &self,
+        encoding: &Encoding,
+        options: &EncodeOptions,
+    ) -> Result<RString, Error> {
+        let str_val = self.0.as_raw();
+        let mut flags = 0;
+        if options.replace_invalid {
+            flags |= rb_sys::ECONV_INVALID_REPLACE as std::os::raw::c_int;
+        }
+        if options.replace_undefined {
+            flags |= rb_sys::ECONV_UNDEF_REPLACE as std::os::raw::c_int;
+        }
+
+        // The replacement string and options hash are only referenced from
+        // this stack frame, which Ruby's GC scans
+        let replacement = match &options.replacement {
+            // SAFETY: rb_utf8_str_new copies the replacement
+            Some(replacement) => unsafe {
+                rb_sys::rb_utf8_str_new(
+                    replacement.as_ptr() as *const std::os::raw::c_char,
+                    replacement.len() as _,
+                )
+            },
+            None => rb_sys::Qnil.into(),
+        };
+
+        let val = if self.encoding() == *encoding && options.replace_invalid {
+            // Transcoding to the same encoding leaves the bytes alone, so
+            // invalid ones have to be scrubbed instead
+            // SAFETY: str_val is a valid Ruby string; rb_str_scrub returns
+            // nil if there was nothing to replace
+            let scrubbed = protect(|| unsafe { rb_sys::rb_str_scrub(str_val, replacement) })?;
+            // SAFETY: scrubbed is a valid VALUE
+            if unsafe { Value::from_raw(scrubbed) }.is_nil() {
+                // SAFETY: str_val is a valid Ruby string
+                unsafe { rb_sys::rb_str_dup(str_val) }
+            } else {
+                scrubbed
+            }
+        } else {
+            // SAFETY: str_val is a valid Ruby string, encoding.ptr is a valid
+            // encoding and ecopts is nil or a hash
+            protect(|| unsafe {
+                let ecopts = if options.replacement.is_some() {
+                    let hash = rb_sys::rb_hash_new();
+                    rb_sys::rb_hash_aset(hash, Symbol::new("replace").as_raw(), replacement);
+                    hash
+                } else {
+                    rb_sys::Qnil.into()
+                };
+                let enc_value = rb_sys::rb_enc_from_encoding(encoding.ptr);
+                rb_sys::rb_str_encode(str_val, enc_value, flags, ecopts)
+            })?
+        };
+
+        // SAFETY: both branches return a valid Ruby string
+        Ok(RString(unsafe { Value::from_raw(val) }))
+    }
+
+    /// Change the encoding of this string without changing its bytes, like
+    /// `String#force_encoding`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the string is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::{Encoding, RString};
+    ///
+    /// let s = RString::from_slice_boxed("é".as_bytes());
+    /// assert_eq!(s.encoding().name(), "ASCII-8BIT");
+    /// s.force_encoding(Encoding::utf8())?;
+    /// assert_eq!(s.to_string()?, "é");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn force_encoding(&self, encoding: Encoding) -> Result<(), Error> {
+        let str_val = self.0.as_raw();
+        // SAFETY: str_val is a valid Ruby string and encoding.ptr a valid
+        // encoding
+        protect(|| unsafe {
+            rb_sys::rb_enc_associate(str_val, encoding.ptr);
         })
     }
 
-    /// Copy string contents to a byte vector.
-    ///
-    /// This always succeeds and works with any byte sequence.
+    /// Check whether the bytes of the string are valid in its encoding,
+    /// like `String#valid_encoding?`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::{Encoding, RString};
+    ///
+    /// let s = RString::from_slice_boxed(b"\xff");
+    /// assert!(s.is_valid_encoding());
+    /// s.force_encoding(Encoding::utf8())?;
+    /// assert!(!s.is_valid_encoding());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_valid_encoding(&self) -> bool {
+        self.coderange() != rb_sys::ruby_coderange_type::RUBY_ENC_CODERANGE_BROKEN as u32
+    }
+
+    /// Check whether the string only contains ASCII characters, like
+    /// `String#ascii_only?`.
+    ///
+    /// Strings in encodings that aren't ASCII compatible, such as UTF-16,
+    /// are never ASCII only.
+    pub fn is_ascii_only(&self) -> bool {
+        self.coderange() == rb_sys::ruby_coderange_type::RUBY_ENC_CODERANGE_7BIT as u32
+    }
+
+    /// The string's code range, which Ruby caches until the string changes.
+    fn coderange(&self) -> u32 {
+        // SAFETY: self.0 is a valid Ruby string; rb_enc_str_coderange scans
+        // it if the code range isn't known yet
+        unsafe { rb_sys::rb_enc_str_coderange(self.0.as_raw()) as u32 }
+    }
+
+    /// Copy the string contents to a Rust `String`, replacing anything that
+    /// can't be represented with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// Strings in other encodings are converted to UTF-8 first, so unlike
+    /// [`to_string`](Self::to_string), this works for any string.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::from_slice_boxed(b"caf\xc3\xa9 \xff");
+    /// assert_eq!(s.to_string_lossy(), "caf\u{FFFD}\u{FFFD} \u{FFFD}");
+    /// ```
+    pub fn to_string_lossy(&self) -> String {
+        let utf8 = Encoding::utf8();
+        if self.encoding() == utf8 || self.is_ascii_only() {
+            // SAFETY: We immediately copy the bytes, so they don't outlive
+            // the string
+            let bytes = unsafe { self.as_slice() };
+            return String::from_utf8_lossy(bytes).into_owned();
+        }
+
+        let options = EncodeOptions::new().replace_invalid().replace_undefined();
+        match self.encode_with_raw(&utf8, &options) {
+            Ok(encoded) => {
+                // SAFETY: encoded is on the stack, and its bytes are copied
+                // before anything else runs
+                let bytes = unsafe { encoded.as_slice() };
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+            // There is no converter from this encoding to UTF-8
+            Err(_) => String::from_utf8_lossy(&self.to_bytes()).into_owned(),
+        }
+    }
+
+    /// Iterate over the characters of the string, decoded according to the
+    /// string's encoding.
+    ///
+    /// Each character is yielded as a Rust `char`, which works for strings
+    /// in any Unicode encoding (UTF-8, UTF-16, UTF-32) and for ASCII
+    /// characters in other encodings. Like [`RArray::iter`], the string is
+    /// read afresh at every step, so it is safe to run Ruby code while
+    /// iterating.
+    ///
+    /// # Errors
+    ///
+    /// The iterator yields an `ArgumentError` and then stops at an invalid
+    /// byte sequence, and an `EncodingError` for a non-ASCII character in
+    /// an encoding other than Unicode; use [`encode_with`](Self::encode_with)
+    /// to convert the string to UTF-8 first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("héllo");
+    /// let chars = s.chars().collect::<Result<Vec<char>, _>>()?;
+    /// assert_eq!(chars, vec!['h', 'é', 'l', 'l', 'o']);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn chars(&self) -> Chars<'_> {
+        Chars {
+            string: self,
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Append bytes to the end of the string.
+    ///
+    /// The bytes are added as they are, without looking at the string's
+    /// encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the string is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("abc");
+    /// s.cat(b"\x00def")?;
+    /// assert_eq!(s.to_bytes(), b"abc\x00def");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cat(&self, bytes: &[u8]) -> Result<(), Error> {
+        let str_val = self.0.as_raw();
+        // SAFETY: str_val is a valid Ruby string and rb_str_cat copies the bytes
+        protect(|| unsafe {
+            rb_sys::rb_str_cat(
+                str_val,
+                bytes.as_ptr() as *const std::os::raw::c_char,
+                bytes.len() as _,
+            );
+        })
+    }
+
+    /// Append a Rust string slice to the end of the string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if the string is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// s.push_str(", world")?;
+    /// assert_eq!(s.to_string()?, "hello, world");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_str(&self, s: &str) -> Result<(), Error> {
+        self.cat(s.as_bytes())
+    }
+
+    /// Append another Ruby string, like `String#<<`.
+    ///
+    /// Unlike [`cat`](Self::cat), this takes the encodings of both strings
+    /// into account.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if this string is frozen, or an
+    /// `Encoding::CompatibilityError` if the encodings can't be combined.
+    pub fn append(&self, other: &RString) -> Result<(), Error> {
+        let str_val = self.0.as_raw();
+        let other = other.0.as_raw();
+        // SAFETY: both are valid Ruby strings
+        protect(|| unsafe {
+            rb_sys::rb_str_buf_append(str_val, other);
+        })
+    }
+
+    /// Replace the contents (and encoding) of the string with those of
+    /// `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrozenError` if this string is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("old");
+    /// s.replace(&RString::new_boxed("new"))?;
+    /// assert_eq!(s.to_string()?, "new");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replace(&self, other: &RString) -> Result<(), Error> {
+        let str_val = self.0.as_raw();
+        let other = other.0.as_raw();
+        // SAFETY: both are valid Ruby strings
+        protect(|| unsafe {
+            rb_sys::rb_str_replace(str_val, other);
+        })
+    }
+
+    /// Get `len` characters starting at character `start` as a new string,
+    /// stored in the Context.
+    ///
+    /// This is `string[start, len]`: characters are counted in the string's
+    /// encoding, the result is shorter if the string ends first, and `None`
+    /// if `start` is past the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn first_char<'ctx>(ctx: &'ctx Context<'ctx>, s: RString) -> Result<Option<Pin<&'ctx StackPinned<RString>>>, Error> {
+    ///     s.substr(ctx, 0, 1)
+    /// }
+    /// ```
+    pub fn substr<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        start: usize,
+        len: usize,
+    ) -> Result<Option<Pin<&'ctx StackPinned<RString>>>, Error> {
+        match self.substr_raw(start, len) {
+            Some(sub) => Ok(Some(ctx.pin_value(sub)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get `len` characters starting at character `start` as a new,
+    /// heap-allocated string.
+    ///
+    /// Returns `None` if `start` is past the end; see [`substr`](Self::substr).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("héllo");
+    /// assert_eq!(s.substr_boxed(1, 3).unwrap().to_string()?, "éll");
+    /// assert!(s.substr_boxed(6, 1).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn substr_boxed(&self, start: usize, len: usize) -> Option<BoxValue<RString>> {
+        self.substr_raw(start, len).map(BoxValue::new)
+    }
+
+    fn substr_raw(&self, start: usize, len: usize) -> Option<RString> {
+        // Every character is at least one byte, so limiting both to the byte
+        // length doesn't change the result, and keeps them in range of a
+        // C long
+        let bytes = self.len();
+        if start > bytes {
+            return None;
+        }
+        let len = len.min(bytes);
+        // SAFETY: self.0 is a valid Ruby string; rb_str_substr clamps len and
+        // returns nil if start is out of range
+        let val = unsafe {
+            Value::from_raw(rb_sys::rb_str_substr(self.0.as_raw(), start as _, len as _))
+        };
+        (!val.is_nil()).then_some(RString(val))
+    }
+
+    /// Get `len` bytes starting at byte `start` as a new string, stored in
+    /// the Context.
+    ///
+    /// This is `string.byteslice(start, len)`: the result keeps the string's
+    /// encoding even if the slice cuts a character in half, is shorter if
+    /// the string ends first, and is `None` if `start` is past the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the result.
+    pub fn byte_substr<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        start: usize,
+        len: usize,
+    ) -> Result<Option<Pin<&'ctx StackPinned<RString>>>, Error> {
+        match self.byte_substr_raw(start, len) {
+            Some(sub) => Ok(Some(ctx.pin_value(sub)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get `len` bytes starting at byte `start` as a new, heap-allocated
+    /// string.
+    ///
+    /// Returns `None` if `start` is past the end; see
+    /// [`byte_substr`](Self::byte_substr).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// assert_eq!(s.byte_substr_boxed(3, 10).unwrap().to_bytes(), b"lo");
+    /// ```
+    pub fn byte_substr_boxed(&self, start: usize, len: usize) -> Option<BoxValue<RString>> {
+        self.byte_substr_raw(start, len).map(BoxValue::new)
+    }
+
+    fn byte_substr_raw(&self, start: usize, len: usize) -> Option<RString> {
+        let total = self.len();
+        if start > total {
+            return None;
+        }
+        let len = len.min(total - start);
+        // SAFETY: start..start + len is within the string
+        let val = unsafe {
+            Value::from_raw(rb_sys::rb_str_subseq(self.0.as_raw(), start as _, len as _))
+        };
+        Some(RString(val))
+    }
+
+    /// Check whether the string starts with `prefix`, comparing bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello world");
+    /// assert!(s.starts_with("hello"));
+    /// assert!(!s.starts_with("world"));
+    /// ```
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        // SAFETY: no Ruby code runs while the slice is in use
+        unsafe { self.as_slice() }.starts_with(prefix.as_bytes())
+    }
+
+    /// Check whether the string ends with `suffix`, comparing bytes.
+    pub fn ends_with(&self, suffix: &str) -> bool {
+        // SAFETY: no Ruby code runs while the slice is in use
+        unsafe { self.as_slice() }.ends_with(suffix.as_bytes())
+    }
+
+    /// Find the first occurrence of `needle`, returning its byte offset.
+    ///
+    /// An empty `needle` is found at offset 0.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("héllo");
+    /// assert_eq!(s.find("llo"), Some(3));
+    /// assert_eq!(s.find("xyz"), None);
+    /// ```
+    pub fn find(&self, needle: &str) -> Option<usize> {
+        let needle = needle.as_bytes();
+        if needle.is_empty() {
+            return Some(0);
+        }
+        // SAFETY: no Ruby code runs while the slice is in use
+        let haystack = unsafe { self.as_slice() };
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    }
+
+    /// Split the string on `separator` into an array of strings, stored in
+    /// the Context.
+    ///
+    /// This is `String#split`, so trailing empty fields are dropped and a
+    /// single space splits on runs of whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Encoding::CompatibilityError` if `separator` can't be
+    /// searched for in this string's encoding, a `TypeError` if `split` has
+    /// been redefined to return something other than an array, or `Err` if
+    /// the Context has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn fields<'ctx>(ctx: &'ctx Context<'ctx>, line: RString) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+    ///     line.split(ctx, ",")
+    /// }
+    /// ```
+    pub fn split<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        separator: &str,
+    ) -> Result<Pin<&'ctx StackPinned<RArray>>, Error> {
+        let parts = self.split_raw(separator)?;
+        Ok(ctx.pin_value(parts)?)
+    }
+
+    /// Split the string on `separator` into a heap-allocated array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Encoding::CompatibilityError` if `separator` can't be
+    /// searched for in this string's encoding, or a `TypeError` if `split`
+    /// has been redefined to return something other than an array.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("a,b,c");
+    /// assert_eq!(s.split_boxed(",")?.to_vec::<String>()?, vec!["a", "b", "c"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split_boxed(&self, separator: &str) -> Result<BoxValue<RArray>, Error> {
+        self.split_raw(separator).map(BoxValue::new)
+    }
+
+    fn split_raw(&self, separator: &str) -> Result<RArray, Error> {
+        let parts = self.0.funcall_boxed("split", (separator,))?;
+        // String#split can be redefined to return anything
+        RArray::try_convert(parts.as_value())
+    }
+
+    /// Convert to upper case as a new string, stored in the Context.
+    ///
+    /// Like `String#upcase`, this handles non-ASCII characters for Unicode
+    /// encodings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, a `TypeError` if
+    /// the method has been redefined to return something other than a string,
+    /// or `Err` if the Context has no free slots to hold the result.
+    pub fn upcase<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let converted = self.convert_case("upcase")?;
+        Ok(ctx.pin_value(converted)?)
+    }
+
+    /// Convert to upper case as a new, heap-allocated string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, or a `TypeError` if
+    /// the method has been redefined to return something other than a string.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("straße");
+    /// assert_eq!(s.upcase_boxed()?.to_string()?, "STRASSE");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upcase_boxed(&self) -> Result<BoxValue<RString>, Error> {
+        self.convert_case("upcase").map(BoxValue::new)
+    }
+
+    /// Convert to lower case as a new string, stored in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, a `TypeError` if
+    /// the method has been redefined to return something other than a string,
+    /// or `Err` if the Context has no free slots to hold the result.
+    pub fn downcase<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let converted = self.convert_case("downcase")?;
+        Ok(ctx.pin_value(converted)?)
+    }
+
+    /// Convert to lower case as a new, heap-allocated string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, or a `TypeError` if
+    /// the method has been redefined to return something other than a string.
+    pub fn downcase_boxed(&self) -> Result<BoxValue<RString>, Error> {
+        self.convert_case("downcase").map(BoxValue::new)
+    }
+
+    /// Upper-case the first character and lower-case the rest, as a new
+    /// string stored in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, a `TypeError` if
+    /// the method has been redefined to return something other than a string,
+    /// or `Err` if the Context has no free slots to hold the result.
+    pub fn capitalize<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let converted = self.convert_case("capitalize")?;
+        Ok(ctx.pin_value(converted)?)
+    }
+
+    /// Upper-case the first character and lower-case the rest, as a new,
+    /// heap-allocated string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, or a `TypeError` if
+    /// the method has been redefined to return something other than a string.
+    pub fn capitalize_boxed(&self) -> Result<BoxValue<RString>, Error> {
+        self.convert_case("capitalize").map(BoxValue::new)
+    }
+
+    /// Swap upper and lower case as a new string, stored in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, a `TypeError` if
+    /// the method has been redefined to return something other than a string,
+    /// or `Err` if the Context has no free slots to hold the result.
+    pub fn swapcase<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        let converted = self.convert_case("swapcase")?;
+        Ok(ctx.pin_value(converted)?)
+    }
+
+    /// Swap upper and lower case as a new, heap-allocated string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the string's encoding is invalid, or a `TypeError` if
+    /// the method has been redefined to return something other than a string.
+    pub fn swapcase_boxed(&self) -> Result<BoxValue<RString>, Error> {
+        self.convert_case("swapcase").map(BoxValue::new)
+    }
+
+    /// Call one of the non-mutating case conversion methods of `String`.
+    fn convert_case(&self, method: &str) -> Result<RString, Error> {
+        let converted = self.0.funcall_boxed(method, ())?;
+        // The case conversion methods can be redefined to return anything
+        RString::try_convert(converted.as_value())
+    }
+
+    /// Copy the string, storing the copy in the Context.
+    ///
+    /// The copy is never frozen, like `String#dup`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the copy.
+    pub fn dup<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        Ok(ctx.pin_value(self.dup_raw())?)
+    }
+
+    /// Copy the string, boxed for heap storage.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::RString;
+    ///
+    /// let s = RString::new_boxed("hello");
+    /// s.freeze();
+    /// let copy = s.dup_boxed();
+    /// copy.push_str("!")?;
+    /// assert_eq!(s.to_string()?, "hello");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dup_boxed(&self) -> BoxValue<RString> {
+        BoxValue::new(self.dup_raw())
+    }
+
+    fn dup_raw(&self) -> RString {
+        // SAFETY: self.0 is a valid Ruby string and rb_str_dup returns a new one
+        RString(unsafe { Value::from_raw(rb_sys::rb_str_dup(self.0.as_raw())) })
+    }
+
+    /// Freeze the string, so that modifying it raises `FrozenError`.
+    pub fn freeze(&self) {
+        // SAFETY: self.0 is a valid Ruby string
+        unsafe {
+            rb_sys::rb_str_freeze(self.0.as_raw());
+        }
+    }
+
+    /// Check whether the string is frozen.
     ///
     /// # Example
     ///
@@ -246,52 +1165,110 @@ impl RString {
     /// use solidus::types::RString;
     ///
     /// let s = RString::new_boxed("hello");
-    /// assert_eq!(s.to_bytes(), b"hello");
+    /// assert!(!s.is_frozen());
+    /// s.freeze();
+    /// assert!(s.is_frozen());
+    /// assert!(s.push_str("!").is_err());
     /// ```
-    pub fn to_bytes(&self) -> Vec<u8> {
-        // SAFETY: We immediately copy the bytes, so they don't outlive the string
-        unsafe { self.as_slice().to_vec() }
+    pub fn is_frozen(&self) -> bool {
+        // SAFETY: self.0 is a valid VALUE
+        unsafe { Value::from_raw(rb_sys::rb_obj_frozen_p(self.0.as_raw())) }.is_truthy()
     }
 
-    /// Get the encoding of this string.
+    /// Get the symbol with this string as its name, like `String#to_sym`,
+    /// storing it in the Context.
+    ///
+    /// The symbol is dynamic, like those from
+    /// [`Context::new_symbol`](crate::Context::new_symbol), so it is garbage
+    /// collected again once nothing refers to it. Interning strings from
+    /// untrusted input doesn't grow the symbol table for good.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EncodingError` if the string isn't valid in its encoding,
+    /// or `Err` if the Context has no free slots to hold the result.
+    pub fn intern<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<Symbol>>, Error> {
+        let sym = self.intern_raw()?;
+        Ok(ctx.pin_value(sym)?)
+    }
+
+    /// Get the symbol with this string as its name, boxed for heap storage.
+    ///
+    /// See [`intern`](Self::intern).
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EncodingError` if the string isn't valid in its encoding.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use solidus::types::RString;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use solidus::types::{RString, Symbol};
+    /// use solidus::value::ReprValue;
     ///
-    /// let s = RString::new_boxed("hello");
-    /// let enc = s.encoding();
+    /// let sym = RString::new_boxed("name").intern_boxed()?;
+    /// assert_eq!(sym.as_value(), Symbol::new("name").as_value());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn encoding(&self) -> Encoding {
-        // SAFETY: self.0 is a valid Ruby string VALUE
-        let enc_ptr = unsafe { rb_sys::rb_enc_get(self.0.as_raw()) };
-        Encoding { ptr: enc_ptr }
+    pub fn intern_boxed(&self) -> Result<BoxValue<Symbol>, Error> {
+        self.intern_raw().map(BoxValue::new)
     }
 
-    /// Encode this string to a different encoding.
+    fn intern_raw(&self) -> Result<Symbol, Error> {
+        let str_val = self.0.as_raw();
+        // SAFETY: str_val is a valid Ruby string
+        let sym = protect(|| unsafe { rb_sys::rb_str_intern(str_val) })?;
+        // SAFETY: rb_str_intern returns a Symbol VALUE
+        Ok(unsafe { Symbol::from_value_unchecked(Value::from_raw(sym)) })
+    }
+
+    /// Get the deduplicated, frozen copy of this string, storing it in the
+    /// Context.
     ///
-    /// Returns a new string with the specified encoding.
+    /// Ruby keeps one such "fstring" per distinct content and encoding, the
+    /// same table frozen string literals live in, so interning many equal
+    /// strings keeps only one of them alive. The result is this string
+    /// itself if it is already interned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the result.
+    pub fn to_interned_str<'ctx, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        Ok(ctx.pin_value(self.to_interned_str_raw())?)
+    }
+
+    /// Get the deduplicated, frozen copy of this string, boxed for heap
+    /// storage.
+    ///
+    /// See [`to_interned_str`](Self::to_interned_str).
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use solidus::types::{RString, Encoding};
+    /// use solidus::types::RString;
+    /// use solidus::value::ReprValue;
     ///
-    /// let s = RString::new_boxed("hello");
-    /// let utf8 = Encoding::utf8();
-    /// let encoded = s.encode(utf8).unwrap();
+    /// let a = RString::new_boxed("shared").to_interned_str_boxed();
+    /// let b = RString::new_boxed("shared").to_interned_str_boxed();
+    /// assert!(a.is_frozen());
+    /// assert_eq!(a.as_raw(), b.as_raw());
     /// ```
-    pub fn encode(&self, encoding: Encoding) -> Result<RString, Error> {
-        // SAFETY: self.0 is a valid Ruby string, encoding.ptr is a valid encoding
-        let val = unsafe {
-            let enc_value = rb_sys::rb_enc_from_encoding(encoding.ptr);
-            rb_sys::rb_str_encode(self.0.as_raw(), enc_value, 0, rb_sys::Qnil.into())
-        };
+    pub fn to_interned_str_boxed(&self) -> BoxValue<RString> {
+        BoxValue::new(self.to_interned_str_raw())
+    }
 
-        // Check if an exception was raised
-        // For now, we'll just wrap the result
-        Ok(RString(unsafe { Value::from_raw(val) }))
+    fn to_interned_str_raw(&self) -> RString {
+        // SAFETY: self.0 is a valid Ruby string and rb_str_to_interned_str
+        // returns a frozen string
+        RString(unsafe { Value::from_raw(rb_sys::rb_str_to_interned_str(self.0.as_raw())) })
     }
 }
 
@@ -349,6 +1326,206 @@ impl IntoValue for &str {
     }
 }
 
+/// A locked Ruby string, returned by [`RString::lock`].
+///
+/// Ruby refuses to modify a locked string, so its buffer stays where it is
+/// and the borrowed contents stay valid even while Ruby code runs or the GVL
+/// is released. The borrows can't outlive the guard, and dropping the guard
+/// unlocks the string.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use solidus::types::RString;
+///
+/// let s = RString::new_boxed("hello");
+/// let lock = s.lock()?;
+/// assert_eq!(lock.as_str()?, "hello");
+/// // Modifying the string raises while it is locked
+/// assert!(s.push_str("!").is_err());
+/// drop(lock);
+/// s.push_str("!")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct StringLock<'a> {
+    string: &'a RString,
+}
+
+impl<'a> StringLock<'a> {
+    /// The locked string.
+    #[inline]
+    pub fn string(&self) -> &'a RString {
+        self.string
+    }
+
+    /// Borrow the string contents as bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the string can't be modified while it is locked
+        unsafe { self.string.as_slice() }
+    }
+
+    /// Borrow the string contents as UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if the contents are not valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, Error> {
+        str_from_utf8(self.as_bytes())
+    }
+}
+
+impl Drop for StringLock<'_> {
+    fn drop(&mut self) {
+        let str_val = self.string.0.as_raw();
+        // rb_str_unlocktmp only raises if the string isn't locked, which
+        // can't happen unless C code unlocked it behind our back; there's
+        // nothing useful to do with that error here.
+        // SAFETY: str_val is a valid Ruby string
+        let _ = protect(|| unsafe {
+            rb_sys::rb_str_unlocktmp(str_val);
+        });
+    }
+}
+
+impl std::fmt::Debug for StringLock<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StringLock")
+            .field("string", self.string)
+            .finish()
+    }
+}
+
+/// Check that `bytes` are UTF-8, reporting a Ruby `TypeError` if not.
+fn str_from_utf8(bytes: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(bytes).map_err(|e| {
+        Error::new(
+            crate::ExceptionClass::TypeError,
+            format!("invalid UTF-8 in Ruby string: {}", e),
+        )
+    })
+}
+
+/// Options for [`RString::encode_with`], matching the keyword arguments of
+/// `String#encode`.
+///
+/// By default, invalid byte sequences and characters that don't exist in
+/// the target encoding are errors.
+///
+/// # Example
+///
+/// ```no_run
+/// use solidus::types::EncodeOptions;
+///
+/// // invalid: :replace, undef: :replace, replace: "?"
+/// let options = EncodeOptions::new()
+///     .replace_invalid()
+///     .replace_undefined()
+///     .replacement("?");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EncodeOptions {
+    replace_invalid: bool,
+    replace_undefined: bool,
+    replacement: Option<String>,
+}
+
+impl EncodeOptions {
+    /// Options that make every conversion problem an error.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace invalid byte sequences in the source, like `invalid: :replace`.
+    pub fn replace_invalid(mut self) -> Self {
+        self.replace_invalid = true;
+        self
+    }
+
+    /// Replace characters the target encoding can't represent, like
+    /// `undef: :replace`.
+    pub fn replace_undefined(mut self) -> Self {
+        self.replace_undefined = true;
+        self
+    }
+
+    /// The string to replace characters with, like `replace:`.
+    ///
+    /// Without this, Ruby uses U+FFFD for Unicode target encodings and `?`
+    /// for others.
+    pub fn replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = Some(replacement.into());
+        self
+    }
+}
+
+/// An iterator over the characters of an [`RString`], returned by
+/// [`RString::chars`].
+pub struct Chars<'a> {
+    string: &'a RString,
+    pos: usize,
+    done: bool,
+}
+
+impl Iterator for Chars<'_> {
+    type Item = Result<char, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.pos >= self.string.len() {
+            return None;
+        }
+
+        let str_val = self.string.0.as_raw();
+        let pos = self.pos;
+        let mut len: std::os::raw::c_int = 0;
+        // SAFETY: pos is within the string, whose length was checked above,
+        // and nothing can change the string during the call
+        let result = protect(|| unsafe {
+            let start = rb_sys::RSTRING_PTR(str_val);
+            let end = start.add(rb_sys::RSTRING_LEN(str_val) as usize);
+            let enc = rb_sys::rb_enc_get(str_val);
+            rb_sys::rb_enc_codepoint_len(start.add(pos), end, &mut len, enc)
+        });
+        let codepoint = match result {
+            Ok(codepoint) => codepoint,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        self.pos += len as usize;
+
+        let encoding = self.string.encoding();
+        // SAFETY: encoding.ptr is a valid encoding
+        let unicode = unsafe { rb_sys::rb_enc_unicode_p(encoding.ptr) } != 0;
+        match char::from_u32(codepoint) {
+            Some(c) if unicode || c.is_ascii() => Some(Ok(c)),
+            _ => {
+                self.done = true;
+                Some(Err(Error::new(
+                    crate::ExceptionClass::EncodingError,
+                    format!(
+                        "character 0x{:X} in {} has no Unicode equivalent",
+                        codepoint,
+                        encoding.name()
+                    ),
+                )))
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Chars<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Chars")
+            .field("string", self.string)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
 /// Ruby string encoding.
 ///
 /// This type represents a Ruby encoding object (rb_encoding).
@@ -427,6 +1604,57 @@ impl Encoding {
         }
     }
 
+    /// Get the default external encoding, `Encoding.default_external`.
+    ///
+    /// This is the encoding Ruby assumes for data read from files and other
+    /// I/O, usually taken from the locale.
+    pub fn default_external() -> Self {
+        // SAFETY: rb_default_external_encoding always returns an encoding
+        let ptr = unsafe { rb_sys::rb_default_external_encoding() };
+        Encoding { ptr }
+    }
+
+    /// Get the default internal encoding, `Encoding.default_internal`.
+    ///
+    /// Returns `None` unless it has been set (with `-E` or
+    /// `Encoding.default_internal=`), which is the usual case.
+    pub fn default_internal() -> Option<Self> {
+        // SAFETY: rb_default_internal_encoding returns NULL if unset
+        let ptr = unsafe { rb_sys::rb_default_internal_encoding() };
+        (!ptr.is_null()).then_some(Encoding { ptr })
+    }
+
+    /// Get the encoding that combining `a` and `b` would have, like
+    /// `Encoding.compatible?`.
+    ///
+    /// `a` and `b` are usually strings, but may also be symbols, regexps or
+    /// encodings. Returns `None` if they can't be combined.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::{Encoding, RString};
+    ///
+    /// let ascii = RString::new_boxed("abc");
+    /// let utf8 = RString::new_boxed("héllo");
+    /// let binary = RString::from_slice_boxed(b"\xff");
+    ///
+    /// assert_eq!(Encoding::compatible(&*ascii, &*utf8), Some(Encoding::utf8()));
+    /// assert_eq!(Encoding::compatible(&*utf8, &*binary), None);
+    /// ```
+    pub fn compatible<A: ReprValue, B: ReprValue>(a: &A, b: &B) -> Option<Self> {
+        // SAFETY: a and b are valid VALUEs; rb_enc_compatible returns NULL
+        // if they are incompatible
+        let ptr = unsafe { rb_sys::rb_enc_compatible(a.as_raw(), b.as_raw()) };
+        (!ptr.is_null()).then_some(Encoding { ptr })
+    }
+
+    /// The raw `rb_encoding` pointer.
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> *mut rb_sys::rb_encoding {
+        self.ptr
+    }
+
     /// Get the name of this encoding.
     ///
     /// # Example
@@ -447,6 +1675,14 @@ impl Encoding {
     }
 }
 
+impl PartialEq for Encoding {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl Eq for Encoding {}
+
 // SAFETY: Encoding pointers are global and thread-safe
 unsafe impl Send for Encoding {}
 unsafe impl Sync for Encoding {}
@@ -454,6 +1690,7 @@ unsafe impl Sync for Encoding {}
 #[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
 mod tests {
     use super::*;
+    use crate::test_util::eval;
     use rb_sys_test_helpers::ruby_test;
 
     #[ruby_test]
@@ -570,6 +1807,14 @@ mod tests {
         assert_eq!(encoded.to_string().unwrap(), "hello");
     }
 
+    #[ruby_test]
+    fn test_rstring_encode_invalid() {
+        // A binary string with a high byte has no UTF-8 equivalent
+        let s = RString::from_slice_boxed(b"\xff");
+        let err = s.encode(Encoding::utf8()).unwrap_err();
+        assert!(err.exception().is_some());
+    }
+
     #[ruby_test]
     fn test_rstring_round_trip() {
         let original = "test string with émojis 🎉";
@@ -584,4 +1829,448 @@ mod tests {
         assert_eq!(s.len(), 12);
         assert_eq!(s.to_bytes(), bytes);
     }
+
+    #[ruby_test]
+    fn test_rstring_cat_and_push_str() {
+        let s = RString::new_boxed("abc");
+        s.cat(b"\x00").unwrap();
+        s.push_str("déf").unwrap();
+        assert_eq!(s.to_bytes(), "abc\0déf".as_bytes());
+
+        s.append(&RString::new_boxed("!")).unwrap();
+        assert_eq!(s.to_string().unwrap(), "abc\0déf!");
+    }
+
+    #[ruby_test]
+    fn test_rstring_replace() {
+        let s = RString::new_boxed("old");
+        s.replace(&RString::new_boxed("brand new")).unwrap();
+        assert_eq!(s.to_string().unwrap(), "brand new");
+    }
+
+    #[ruby_test]
+    fn test_rstring_frozen_errors() {
+        let s = RString::new_boxed("hello");
+        assert!(!s.is_frozen());
+        s.freeze();
+        assert!(s.is_frozen());
+
+        let frozen = crate::ExceptionClass::FrozenError.as_value();
+        assert!(s.push_str("!").unwrap_err().is_a(&frozen));
+        assert!(s.cat(b"!").unwrap_err().is_a(&frozen));
+        assert!(
+            s.append(&RString::new_boxed("!"))
+                .unwrap_err()
+                .is_a(&frozen)
+        );
+        assert!(
+            s.replace(&RString::new_boxed("x"))
+                .unwrap_err()
+                .is_a(&frozen)
+        );
+        assert_eq!(s.to_string().unwrap(), "hello");
+    }
+
+    #[ruby_test]
+    fn test_rstring_substr() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("héllo");
+
+        let sub = s.substr(&ctx, 1, 3).unwrap().unwrap();
+        assert_eq!(sub.get().to_string().unwrap(), "éll");
+        assert_eq!(s.substr_boxed(3, 10).unwrap().to_string().unwrap(), "lo");
+        assert_eq!(s.substr_boxed(5, 1).unwrap().len(), 0);
+        assert!(s.substr_boxed(6, 1).is_none());
+
+        // Lengths and starts too large for a C long
+        assert_eq!(
+            s.substr_boxed(0, usize::MAX).unwrap().to_string().unwrap(),
+            "héllo"
+        );
+        assert!(s.substr_boxed(usize::MAX, 1).is_none());
+    }
+
+    #[ruby_test]
+    fn test_rstring_byte_substr() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("héllo");
+
+        let sub = s.byte_substr(&ctx, 1, 2).unwrap().unwrap();
+        assert_eq!(sub.get().to_string().unwrap(), "é");
+        assert_eq!(s.byte_substr_boxed(3, 10).unwrap().to_bytes(), b"llo");
+        assert_eq!(s.byte_substr_boxed(6, 1).unwrap().len(), 0);
+        assert!(s.byte_substr_boxed(7, 1).is_none());
+    }
+
+    #[ruby_test]
+    fn test_rstring_search() {
+        let s = RString::new_boxed("héllo world");
+        assert!(s.starts_with("hé"));
+        assert!(s.starts_with(""));
+        assert!(!s.starts_with("world"));
+        assert!(s.ends_with("world"));
+        assert!(!s.ends_with("hello"));
+
+        assert_eq!(s.find("llo"), Some(3));
+        assert_eq!(s.find("o"), Some(5));
+        assert_eq!(s.find(""), Some(0));
+        assert_eq!(s.find("xyz"), None);
+        assert_eq!(RString::new_boxed("ab").find("abc"), None);
+    }
+
+    #[ruby_test]
+    fn test_rstring_split() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("a,b,,c,,");
+
+        let parts = s.split(&ctx, ",").unwrap();
+        assert_eq!(
+            parts.get().to_vec::<String>().unwrap(),
+            vec!["a", "b", "", "c"]
+        );
+
+        let words = RString::new_boxed("  one two\tthree ")
+            .split_boxed(" ")
+            .unwrap();
+        assert_eq!(
+            words.to_vec::<String>().unwrap(),
+            vec!["one", "two", "three"]
+        );
+    }
+
+    #[ruby_test]
+    fn test_rstring_case_conversion() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("hÉllo World");
+
+        assert_eq!(
+            s.upcase(&ctx).unwrap().get().to_string().unwrap(),
+            "HÉLLO WORLD"
+        );
+        assert_eq!(
+            s.downcase(&ctx).unwrap().get().to_string().unwrap(),
+            "héllo world"
+        );
+        assert_eq!(
+            s.capitalize(&ctx).unwrap().get().to_string().unwrap(),
+            "Héllo world"
+        );
+        assert_eq!(
+            s.swapcase(&ctx).unwrap().get().to_string().unwrap(),
+            "HéLLO wORLD"
+        );
+        assert_eq!(
+            s.upcase_boxed().unwrap().to_string().unwrap(),
+            "HÉLLO WORLD"
+        );
+        assert_eq!(
+            s.downcase_boxed().unwrap().to_string().unwrap(),
+            "héllo world"
+        );
+        assert_eq!(
+            s.capitalize_boxed().unwrap().to_string().unwrap(),
+            "Héllo world"
+        );
+        assert_eq!(
+            s.swapcase_boxed().unwrap().to_string().unwrap(),
+            "HéLLO wORLD"
+        );
+
+        // The receiver is left alone
+        assert_eq!(s.to_string().unwrap(), "hÉllo World");
+    }
+
+    #[ruby_test]
+    fn test_rstring_redefined_methods() {
+        let val = eval("'a,b'.tap { |s| def s.split(*) = 1; def s.upcase = :A }").unwrap();
+        let s = RString::try_convert(val.as_value()).unwrap();
+
+        let err = s.split_boxed(",").unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::TypeError.as_value()));
+        let err = s.upcase_boxed().unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::TypeError.as_value()));
+    }
+
+    #[ruby_test]
+    fn test_rstring_dup() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("hello");
+        s.freeze();
+
+        let copy = s.dup(&ctx).unwrap();
+        assert!(!copy.get().is_frozen());
+        copy.get().push_str("!").unwrap();
+        assert_eq!(copy.get().to_string().unwrap(), "hello!");
+        assert_eq!(s.dup_boxed().to_string().unwrap(), "hello");
+    }
+
+    #[ruby_test]
+    fn test_rstring_intern() {
+        let ctx: Context<'_> = Context::default();
+        let sym = RString::new_boxed("intern_näme").intern(&ctx).unwrap();
+        assert_eq!(sym.get().name().unwrap(), "intern_näme");
+        assert_eq!(
+            RString::new_boxed("plain").intern_boxed().unwrap().as_raw(),
+            Symbol::new("plain").as_raw()
+        );
+
+        let invalid = eval("\"\\xff\".force_encoding('UTF-8')").unwrap();
+        let invalid = RString::try_convert(invalid.as_value()).unwrap();
+        assert!(invalid.intern_boxed().is_err());
+    }
+
+    #[ruby_test]
+    fn test_rstring_to_interned_str() {
+        let ctx: Context<'_> = Context::default();
+        let a = RString::new_boxed("fstring_test");
+        let b = RString::new_boxed("fstring_test");
+
+        let ia = a.to_interned_str(&ctx).unwrap();
+        let ib = b.to_interned_str_boxed();
+        assert!(ia.get().is_frozen());
+        assert_eq!(ia.get().as_raw(), ib.as_raw());
+        assert_eq!(ib.to_string().unwrap(), "fstring_test");
+        // The original strings are untouched
+        assert!(!a.is_frozen());
+        assert_ne!(a.as_raw(), b.as_raw());
+    }
+
+    #[ruby_test]
+    fn test_rstring_as_bytes_and_as_str() {
+        let s = RString::new_boxed("héllo");
+        unsafe {
+            assert_eq!(s.as_bytes(), "héllo".as_bytes());
+            assert_eq!(s.as_str().unwrap(), "héllo");
+        }
+
+        let binary = RString::from_slice_boxed(b"\xff\xfe");
+        unsafe {
+            assert_eq!(binary.as_bytes(), b"\xff\xfe");
+            assert!(binary.as_str().is_err());
+        }
+    }
+
+    #[ruby_test]
+    fn test_rstring_lock() {
+        let s = RString::new_boxed("locked");
+        {
+            let lock = s.lock().unwrap();
+            assert_eq!(lock.as_bytes(), b"locked");
+            assert_eq!(lock.as_str().unwrap(), "locked");
+            assert_eq!(lock.string().as_raw(), s.as_raw());
+
+            // Locking twice is an error
+            assert!(s.lock().is_err());
+
+            // So is modifying the string, from Rust or from Ruby
+            assert!(s.push_str("!").is_err());
+            let err = s.as_value().funcall_boxed("<<", ("!",)).unwrap_err();
+            assert!(err.is_a(&crate::ExceptionClass::RuntimeError.as_value()));
+            assert_eq!(lock.as_str().unwrap(), "locked");
+        }
+
+        // Dropping the guard unlocks the string
+        s.push_str("!").unwrap();
+        assert_eq!(s.to_string().unwrap(), "locked!");
+        drop(s.lock().unwrap());
+    }
+
+    #[ruby_test]
+    fn test_rstring_lock_without_gvl() {
+        let s = RString::new_boxed("hello world");
+        let lock = s.lock().unwrap();
+        let bytes = lock.as_bytes();
+        let spaces =
+            crate::without_gvl(|| bytes.iter().filter(|b| **b == b' ').count(), ()).unwrap();
+        assert_eq!(spaces, 1);
+    }
+
+    #[ruby_test]
+    fn test_rstring_new_is_utf8() {
+        assert_eq!(RString::new_boxed("héllo").encoding(), Encoding::utf8());
+        assert_eq!(
+            RString::from_slice_boxed(b"bytes").encoding(),
+            Encoding::ascii_8bit()
+        );
+
+        let ctx: Context<'_> = Context::default();
+        assert_eq!(
+            ctx.new_string("x").unwrap().get().encoding(),
+            Encoding::utf8()
+        );
+    }
+
+    #[ruby_test]
+    fn test_rstring_force_encoding() {
+        let s = RString::from_slice_boxed("é".as_bytes());
+        assert_eq!(s.encoding(), Encoding::ascii_8bit());
+        s.force_encoding(Encoding::utf8()).unwrap();
+        assert_eq!(s.encoding(), Encoding::utf8());
+        assert_eq!(s.to_bytes(), "é".as_bytes());
+
+        s.freeze();
+        let err = s.force_encoding(Encoding::ascii_8bit()).unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::FrozenError.as_value()));
+    }
+
+    #[ruby_test]
+    fn test_rstring_valid_encoding_and_ascii_only() {
+        let ascii = RString::new_boxed("plain");
+        assert!(ascii.is_valid_encoding());
+        assert!(ascii.is_ascii_only());
+
+        let utf8 = RString::new_boxed("héllo");
+        assert!(utf8.is_valid_encoding());
+        assert!(!utf8.is_ascii_only());
+
+        let broken = RString::from_slice_boxed(b"caf\xc3");
+        assert!(broken.is_valid_encoding());
+        broken.force_encoding(Encoding::utf8()).unwrap();
+        assert!(!broken.is_valid_encoding());
+        assert!(!broken.is_ascii_only());
+
+        // The cached code range is reset when the string changes
+        broken.cat(b"\xa9").unwrap();
+        assert!(broken.is_valid_encoding());
+    }
+
+    #[ruby_test]
+    fn test_rstring_to_string_lossy() {
+        assert_eq!(RString::new_boxed("héllo").to_string_lossy(), "héllo");
+
+        let broken = RString::from_slice_boxed(b"ok\xff");
+        broken.force_encoding(Encoding::utf8()).unwrap();
+        assert_eq!(broken.to_string_lossy(), "ok\u{FFFD}");
+
+        // Binary strings only keep their ASCII characters
+        let binary = RString::from_slice_boxed("aé".as_bytes());
+        assert_eq!(binary.to_string_lossy(), "a\u{FFFD}\u{FFFD}");
+
+        // Other encodings are converted
+        let latin1 = RString::from_slice_boxed(b"caf\xe9");
+        latin1
+            .force_encoding(Encoding::find("ISO-8859-1").unwrap())
+            .unwrap();
+        assert_eq!(latin1.to_string_lossy(), "café");
+        assert!(latin1.to_string().is_err());
+    }
+
+    #[ruby_test]
+    fn test_rstring_encode_with() {
+        let ctx: Context<'_> = Context::default();
+        let s = RString::new_boxed("café");
+        let ascii = Encoding::us_ascii();
+
+        // Without options, conversion errors are returned
+        let err = s
+            .encode_with(&ctx, ascii.clone(), &EncodeOptions::new())
+            .err()
+            .unwrap();
+        assert!(err.is_a(&crate::ExceptionClass::EncodingError.as_value()));
+        assert!(s.encode(ascii.clone()).is_err());
+
+        let options = EncodeOptions::new().replace_undefined();
+        let replaced = s.encode_with(&ctx, ascii.clone(), &options).unwrap();
+        assert_eq!(replaced.get().to_string().unwrap(), "caf?");
+        assert_eq!(replaced.get().encoding(), ascii);
+
+        let options = EncodeOptions::new().replace_undefined().replacement("[?]");
+        let replaced = s.encode_with_boxed(ascii, &options).unwrap();
+        assert_eq!(replaced.to_string().unwrap(), "caf[?]");
+
+        let latin1 = s
+            .encode_with_boxed(Encoding::find("ISO-8859-1").unwrap(), &EncodeOptions::new())
+            .unwrap();
+        assert_eq!(latin1.to_bytes(), b"caf\xe9");
+    }
+
+    #[ruby_test]
+    fn test_rstring_encode_with_invalid_bytes() {
+        let broken = RString::from_slice_boxed(b"ok\xff");
+        broken.force_encoding(Encoding::utf8()).unwrap();
+
+        let utf16 = Encoding::find("UTF-16LE").unwrap();
+        let err = broken
+            .encode_with_boxed(utf16.clone(), &EncodeOptions::new())
+            .unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::EncodingError.as_value()));
+
+        let options = EncodeOptions::new().replace_invalid();
+        let converted = broken.encode_with_boxed(utf16, &options).unwrap();
+        assert_eq!(converted.to_string_lossy(), "ok\u{FFFD}");
+
+        // Within the same encoding, invalid bytes are scrubbed
+        let options = EncodeOptions::new().replace_invalid().replacement("?");
+        let scrubbed = broken
+            .encode_with_boxed(Encoding::utf8(), &options)
+            .unwrap();
+        assert_eq!(scrubbed.to_string().unwrap(), "ok?");
+        let clean = RString::new_boxed("fine")
+            .encode_with_boxed(Encoding::utf8(), &options)
+            .unwrap();
+        assert_eq!(clean.to_string().unwrap(), "fine");
+    }
+
+    #[ruby_test]
+    fn test_rstring_chars() {
+        let s = RString::new_boxed("héllo ✓");
+        let chars: Vec<char> = s.chars().map(Result::unwrap).collect();
+        assert_eq!(chars, "héllo ✓".chars().collect::<Vec<_>>());
+
+        let utf16 = s
+            .encode_with_boxed(Encoding::find("UTF-16LE").unwrap(), &EncodeOptions::new())
+            .unwrap();
+        let chars: Vec<char> = utf16.chars().map(Result::unwrap).collect();
+        assert_eq!(chars, "héllo ✓".chars().collect::<Vec<_>>());
+
+        assert_eq!(RString::new_boxed("").chars().count(), 0);
+    }
+
+    #[ruby_test]
+    fn test_rstring_chars_errors() {
+        let broken = RString::from_slice_boxed(b"a\xffb");
+        broken.force_encoding(Encoding::utf8()).unwrap();
+        let mut chars = broken.chars();
+        assert_eq!(chars.next().unwrap().unwrap(), 'a');
+        let err = chars.next().unwrap().unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::ArgumentError.as_value()));
+        assert!(chars.next().is_none());
+
+        let latin1 = RString::from_slice_boxed(b"caf\xe9");
+        latin1
+            .force_encoding(Encoding::find("ISO-8859-1").unwrap())
+            .unwrap();
+        let results: Vec<_> = latin1.chars().collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(*results[2].as_ref().unwrap(), 'f');
+        let err = results[3].as_ref().unwrap_err();
+        assert!(err.is_a(&crate::ExceptionClass::EncodingError.as_value()));
+    }
+
+    #[ruby_test]
+    fn test_encoding_compatible() {
+        let ascii = RString::new_boxed("abc");
+        let utf8 = RString::new_boxed("héllo");
+        let binary = RString::from_slice_boxed(b"\xff");
+
+        assert_eq!(
+            Encoding::compatible(&*ascii, &*utf8),
+            Some(Encoding::utf8())
+        );
+        assert_eq!(
+            Encoding::compatible(&*ascii, &*binary),
+            Some(Encoding::ascii_8bit())
+        );
+        assert_eq!(Encoding::compatible(&*utf8, &*binary), None);
+    }
+
+    #[ruby_test]
+    fn test_encoding_defaults() {
+        let external = Encoding::default_external();
+        assert!(Encoding::find(external.name()).is_some());
+
+        // Not set unless Ruby was started with -E or it was assigned
+        let internal = eval("Encoding.default_internal").unwrap();
+        assert_eq!(Encoding::default_internal().is_none(), internal.is_nil());
+    }
 }
@@ -0,0 +1,300 @@
+//! Building Ruby strings incrementally.
+//!
+//! Formatting into a Rust `String` and then creating a Ruby string from it
+//! copies every byte twice. [`RStringBuilder`] writes straight into the
+//! buffer of a Ruby string instead, growing it as needed, and implements
+//! [`std::fmt::Write`] and [`std::io::Write`] so `write!` and serializers
+//! writing to an `io::Write` can target it directly.
+
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+
+use crate::context::Context;
+use crate::error::{Error, protect};
+use crate::types::{Encoding, RString};
+use crate::value::{BoxValue, ReprValue, StackPinned, Value};
+
+/// A Ruby string under construction.
+///
+/// The string is UTF-8 unless [`set_encoding`](Self::set_encoding) says
+/// otherwise. Call [`finish`](Self::finish) or
+/// [`finish_boxed`](Self::finish_boxed) to get the finished `RString`.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::fmt::Write;
+///
+/// use solidus::prelude::*;
+/// use solidus::types::RStringBuilder;
+///
+/// fn render<'ctx>(ctx: &'ctx Context<'ctx>, items: RArray) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+///     let mut out = RStringBuilder::with_capacity(items.len() * 16);
+///     for (i, item) in items.iter().enumerate() {
+///         let item = i64::try_convert(item)?;
+///         writeln!(out, "{}: {}", i, item).map_err(|_| Error::runtime("write failed"))?;
+///     }
+///     out.finish(ctx)
+/// }
+/// ```
+pub struct RStringBuilder {
+    string: BoxValue<RString>,
+}
+
+impl RStringBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create an empty builder with room for at least `capacity` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::types::RStringBuilder;
+    ///
+    /// let builder = RStringBuilder::with_capacity(1024);
+    /// assert!(builder.capacity() >= 1024);
+    /// assert!(builder.is_empty());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        // SAFETY: rb_str_buf_new returns a new, empty string, which is boxed
+        // before anything else can run
+        let string = unsafe {
+            let val = rb_sys::rb_str_buf_new(capacity as _);
+            rb_sys::rb_enc_associate(val, rb_sys::rb_utf8_encoding());
+            RString::from_value_unchecked(Value::from_raw(val))
+        };
+        RStringBuilder {
+            string: BoxValue::new(string),
+        }
+    }
+
+    /// Set the encoding of the string being built.
+    ///
+    /// The bytes written so far are kept as they are and reinterpreted in
+    /// `encoding`, like `String#force_encoding`.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        // SAFETY: the string is valid and only referenced by the builder
+        unsafe {
+            rb_sys::rb_enc_associate(self.string.as_raw(), encoding.as_ptr());
+        }
+    }
+
+    /// The number of bytes written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.string.len()
+    }
+
+    /// Check whether nothing has been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of bytes the string can hold without growing.
+    pub fn capacity(&self) -> usize {
+        // SAFETY: the string is valid
+        unsafe { rb_sys::rb_str_capacity(self.string.as_raw()) }
+    }
+
+    /// Make room for at least `additional` more bytes.
+    ///
+    /// The buffer at least doubles whenever it grows, so appending in small
+    /// pieces takes amortized linear time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if the string would get too big.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), Error> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+        let expand = additional.max(len);
+        if expand > isize::MAX as usize {
+            return Err(Error::argument("string size too big"));
+        }
+        let str_val = self.string.as_raw();
+        // SAFETY: the string is valid and not shared
+        protect(|| unsafe { rb_sys::rb_str_modify_expand(str_val, expand as _) })
+    }
+
+    /// Append bytes, growing the string if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if the string would get too big.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.reserve(bytes.len())?;
+        let str_val = self.string.as_raw();
+        let len = self.len();
+        // SAFETY: reserve made room for bytes after the current contents, and
+        // rb_str_set_len keeps the terminator and code range up to date
+        unsafe {
+            let end = (rb_sys::RSTRING_PTR(str_val) as *mut u8).add(len);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), end, bytes.len());
+            rb_sys::rb_str_set_len(str_val, (len + bytes.len()) as _);
+        }
+        Ok(())
+    }
+
+    /// Append a Rust string slice, growing the string if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if the string would get too big.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) -> Result<(), Error> {
+        self.push_bytes(s.as_bytes())
+    }
+
+    /// Finish building, storing the string in the Context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the Context has no free slots to hold the string.
+    pub fn finish<'ctx, const N: usize>(
+        self,
+        ctx: &'ctx Context<'ctx, N>,
+    ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
+        // Pin the string before the box lets go of it
+        Ok(ctx.pin_value(self.string.inner())?)
+    }
+
+    /// Finish building, boxing the string for heap storage.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Write;
+    ///
+    /// use solidus::types::RStringBuilder;
+    ///
+    /// let mut builder = RStringBuilder::new();
+    /// builder.write_all(b"hello, ")?;
+    /// builder.push_str("world")?;
+    /// assert_eq!(builder.finish_boxed().to_string()?, "hello, world");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn finish_boxed(self) -> BoxValue<RString> {
+        self.string
+    }
+}
+
+impl Default for RStringBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RStringBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RStringBuilder")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl fmt::Write for RStringBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl io::Write for RStringBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_bytes(buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::OutOfMemory, e.message()))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use rb_sys_test_helpers::ruby_test;
+    use std::fmt::Write as _;
+
+    #[ruby_test]
+    fn test_builder_fmt_write() {
+        let mut builder = RStringBuilder::new();
+        assert!(builder.is_empty());
+        write!(builder, "{}-{}", 1, "twö").unwrap();
+        builder.write_char('!').unwrap();
+        assert_eq!(builder.len(), 8);
+
+        let s = builder.finish_boxed();
+        assert_eq!(s.to_string().unwrap(), "1-twö!");
+        assert_eq!(s.encoding().name(), "UTF-8");
+    }
+
+    #[ruby_test]
+    fn test_builder_io_write() {
+        use std::io::Write as _;
+
+        let mut builder = RStringBuilder::default();
+        builder.write_all(b"binary\x00").unwrap();
+        builder.flush().unwrap();
+        builder.push_bytes(b"\xff").unwrap();
+        assert_eq!(builder.finish_boxed().to_bytes(), b"binary\x00\xff");
+    }
+
+    #[ruby_test]
+    fn test_builder_grows() {
+        let mut builder = RStringBuilder::with_capacity(4);
+        let chunk = "0123456789abcdef";
+        for _ in 0..10_000 {
+            builder.push_str(chunk).unwrap();
+        }
+        assert_eq!(builder.len(), chunk.len() * 10_000);
+        assert!(builder.capacity() >= builder.len());
+
+        let s = builder.finish_boxed();
+        let bytes = s.to_bytes();
+        assert!(bytes.chunks(chunk.len()).all(|c| c == chunk.as_bytes()));
+    }
+
+    #[ruby_test]
+    fn test_builder_reserve() {
+        let mut builder = RStringBuilder::new();
+        builder.reserve(1000).unwrap();
+        let capacity = builder.capacity();
+        assert!(capacity >= 1000);
+        builder.push_bytes(&[b'x'; 1000]).unwrap();
+        assert_eq!(builder.capacity(), capacity);
+    }
+
+    #[ruby_test]
+    fn test_builder_encoding() {
+        let mut builder = RStringBuilder::new();
+        builder.set_encoding(Encoding::ascii_8bit());
+        builder.push_bytes(b"\xde\xad").unwrap();
+        let s = builder.finish_boxed();
+        assert_eq!(s.encoding().name(), "ASCII-8BIT");
+        assert_eq!(s.to_bytes(), b"\xde\xad");
+    }
+
+    #[ruby_test]
+    fn test_builder_finish_in_context() {
+        let ctx: Context<'_> = Context::default();
+        let mut builder = RStringBuilder::with_capacity(16);
+        write!(builder, "{:>5}", 42).unwrap();
+        let s = builder.finish(&ctx).unwrap();
+        assert_eq!(s.get().to_string().unwrap(), "   42");
+
+        // The finished string is an ordinary, mutable Ruby string
+        s.get().push_str("!").unwrap();
+        assert_eq!(s.get().to_string().unwrap(), "   42!");
+    }
+}
@@ -0,0 +1,251 @@
+//! Calling Ruby methods on values.
+
+use std::os::raw::c_char;
+use std::pin::Pin;
+
+use super::{BoxValue, StackPinned, Value};
+use crate::context::Context;
+use crate::convert::ArgList;
+use crate::error::{Error, protect};
+
+/// Intern a method name, returning its Ruby ID.
+///
+/// Uses `rb_intern2` so names containing arbitrary bytes (e.g. `"[]="`) are
+/// accepted without building a C string.
+pub(crate) fn intern(name: &str) -> rb_sys::ID {
+    // SAFETY: rb_intern2 reads exactly `len` bytes from the pointer
+    unsafe { rb_sys::rb_intern2(name.as_ptr() as *const c_char, name.len() as _) }
+}
+
+impl Value {
+    /// Call a Ruby method on this value, storing the result in the Context.
+    ///
+    /// The method is looked up like a Ruby call with an explicit receiver
+    /// would, except that private and protected methods are also callable
+    /// (equivalent to `send`). Use [`funcall_public`](Self::funcall_public)
+    /// to respect method visibility.
+    ///
+    /// `args` is any [`ArgList`]: a tuple of up to 8 [`IntoValue`](crate::convert::IntoValue)
+    /// values, or an array. Use `()` for no arguments and `(x,)` for one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises a Ruby exception, or if the Context
+    /// has no free slots to hold the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn describe<'ctx>(
+    ///     ctx: &'ctx Context<'ctx>,
+    ///     value: &Value,
+    /// ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+    ///     value.funcall(ctx, "inspect", ())
+    /// }
+    /// ```
+    pub fn funcall<'ctx, A: ArgList, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        name: &str,
+        args: A,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_method(name, args, false)?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Call a Ruby method on this value, returning a heap-allocated result.
+    ///
+    /// This is the same as [`funcall`](Self::funcall), but the result is
+    /// wrapped in a [`BoxValue`] so it can be stored outside of a method call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises a Ruby exception.
+    pub fn funcall_boxed<A: ArgList>(&self, name: &str, args: A) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_method(name, args, false)?))
+    }
+
+    /// Call a public Ruby method on this value, storing the result in the Context.
+    ///
+    /// Unlike [`funcall`](Self::funcall), calling a private or protected
+    /// method raises `NoMethodError`, just like `receiver.name(args)` in Ruby.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises a Ruby exception, or if the Context
+    /// has no free slots to hold the result.
+    pub fn funcall_public<'ctx, A: ArgList, const N: usize>(
+        &self,
+        ctx: &'ctx Context<'ctx, N>,
+        name: &str,
+        args: A,
+    ) -> Result<Pin<&'ctx StackPinned<Value>>, Error> {
+        let result = self.call_method(name, args, true)?;
+        Ok(ctx.pin_value(result)?)
+    }
+
+    /// Call a public Ruby method on this value, returning a heap-allocated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method raises a Ruby exception.
+    pub fn funcall_public_boxed<A: ArgList>(
+        &self,
+        name: &str,
+        args: A,
+    ) -> Result<BoxValue<Value>, Error> {
+        Ok(BoxValue::new(self.call_method(name, args, true)?))
+    }
+
+    /// Check if this value responds to the given public method.
+    ///
+    /// This honours `respond_to_missing?`, just like `respond_to?` in Ruby.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an overridden `respond_to?` or `respond_to_missing?`
+    /// raises a Ruby exception.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use solidus::prelude::*;
+    ///
+    /// fn to_s_if_possible(value: &Value) -> Result<Option<BoxValue<Value>>, Error> {
+    ///     if value.respond_to("to_s")? {
+    ///         Ok(Some(value.funcall_boxed("to_s", ())?))
+    ///     } else {
+    ///         Ok(None)
+    ///     }
+    /// }
+    /// ```
+    pub fn respond_to(&self, name: &str) -> Result<bool, Error> {
+        let id = intern(name);
+        let recv = self.as_raw();
+        // SAFETY: recv is a valid VALUE and id is a valid interned ID. This
+        // calls respond_to? and respond_to_missing?, which may be overridden.
+        let responds = protect(|| unsafe { rb_sys::rb_respond_to(recv, id) })?;
+        Ok(responds != 0)
+    }
+
+    /// Perform the method call, catching any exception it raises.
+    fn call_method<A: ArgList>(&self, name: &str, args: A, public: bool) -> Result<Value, Error> {
+        let id = intern(name);
+        // The converted arguments live in a stack array for the duration of
+        // the call, so Ruby's GC can see them.
+        let args = args.into_arg_list();
+        let args = args.as_ref();
+        // Value is #[repr(transparent)] over VALUE, so the slice can be passed as argv
+        let argv = args.as_ptr() as *const rb_sys::VALUE;
+        let argc = args.len() as _;
+
+        let recv = self.as_raw();
+
+        // SAFETY: recv and all arguments are valid VALUEs, argv points to argc VALUEs
+        let raw = protect(|| unsafe {
+            if public {
+                rb_sys::rb_funcallv_public(recv, id, argc, argv)
+            } else {
+                rb_sys::rb_funcallv(recv, id, argc, argv)
+            }
+        })?;
+
+        // SAFETY: the method returned a valid VALUE
+        Ok(unsafe { Value::from_raw(raw) })
+    }
+}
+
+#[cfg(all(test, any(feature = "embed", feature = "link-ruby")))]
+mod tests {
+    use super::*;
+    use crate::convert::{IntoValue, TryConvert};
+    use crate::test_util::eval;
+    use crate::types::RString;
+    use crate::value::ReprValue;
+    use rb_sys_test_helpers::ruby_test;
+
+    #[ruby_test]
+    fn test_funcall_no_args() {
+        let ctx: Context<'_> = Context::default();
+        let value = 42i64.into_value();
+        let result = value.funcall(&ctx, "to_s", ()).unwrap();
+        let s = RString::try_convert(result.get().clone()).unwrap();
+        assert_eq!(s.to_string().unwrap(), "42");
+        assert_eq!(ctx.available(), 7);
+    }
+
+    #[ruby_test]
+    fn test_funcall_with_args() {
+        let ctx: Context<'_> = Context::default();
+        let value = 40i64.into_value();
+        let result = value.funcall(&ctx, "+", (2i64,)).unwrap();
+        assert_eq!(i64::try_convert(result.get().clone()).unwrap(), 42);
+    }
+
+    #[ruby_test]
+    fn test_funcall_multiple_args() {
+        let s = RString::new_boxed("hello world");
+        let result = s
+            .as_value()
+            .funcall_boxed("sub", ("world", "solidus"))
+            .unwrap();
+        let result = RString::try_convert(result.inner()).unwrap();
+        assert_eq!(result.to_string().unwrap(), "hello solidus");
+    }
+
+    #[ruby_test]
+    fn test_funcall_array_args() {
+        let value = 10i64.into_value();
+        let result = value.funcall_boxed("clamp", [0i64, 5i64]).unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 5);
+    }
+
+    #[ruby_test]
+    fn test_funcall_exhausted_context() {
+        let ctx: Context<'_, 0> = Context::default();
+        let value = 1i64.into_value();
+        assert!(value.funcall(&ctx, "to_s", ()).is_err());
+    }
+
+    #[ruby_test]
+    fn test_funcall_public() {
+        let value = 42i64.into_value();
+        let result = value.funcall_public_boxed("succ", ()).unwrap();
+        assert_eq!(i64::try_convert(result.inner()).unwrap(), 43);
+    }
+
+    #[ruby_test]
+    fn test_funcall_raises() {
+        let value = 1i64.into_value();
+        let err = value.funcall_boxed("no_such_method", ()).unwrap_err();
+        assert!(err.message().contains("no_such_method"));
+        assert!(err.exception().is_some());
+    }
+
+    #[ruby_test]
+    fn test_funcall_public_rejects_private() {
+        let s = RString::new_boxed("hello");
+        // Kernel#puts is private
+        assert!(s.as_value().funcall_public_boxed("puts", ()).is_err());
+    }
+
+    #[ruby_test]
+    fn test_respond_to() {
+        let s = RString::new_boxed("hello");
+        assert!(s.as_value().respond_to("upcase").unwrap());
+        assert!(!s.as_value().respond_to("no_such_method").unwrap());
+        // Private methods are not reported
+        assert!(!s.as_value().respond_to("puts").unwrap());
+    }
+
+    #[ruby_test]
+    fn test_respond_to_raises() {
+        let value = eval("Class.new { def respond_to_missing?(*) = raise('broken') }.new").unwrap();
+        let err = value.respond_to("anything").unwrap_err();
+        assert!(err.message().contains("broken"));
+        // Methods that exist are found without calling respond_to_missing?
+        assert!(value.respond_to("inspect").unwrap());
+    }
+}
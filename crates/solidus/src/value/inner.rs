@@ -1,6 +1,7 @@
 //! The base Value type wrapping Ruby's VALUE.
 
 use std::fmt;
+use std::marker::PhantomData;
 
 /// Ruby value types.
 ///
@@ -107,9 +108,13 @@ impl ValueType {
 /// `Value` is `!Copy` to prevent accidental heap storage. Values must remain on the
 /// stack to be protected by Ruby's GC, unless explicitly stored in [`BoxValue<T>`](crate::BoxValue).
 /// In method signatures, use `Pin<&StackPinned<T>>` to guarantee stack pinning.
+///
+/// `Value` is also `!Send` and `!Sync`: Ruby objects may only be touched by a
+/// thread holding the GVL, so they can't be moved into code that runs
+/// without it (see [`without_gvl`](crate::without_gvl)).
 #[derive(Clone)]
 #[repr(transparent)]
-pub struct Value(rb_sys::VALUE);
+pub struct Value(rb_sys::VALUE, PhantomData<*mut ()>);
 
 impl Value {
     /// Create a Value from a raw Ruby VALUE.
@@ -120,7 +125,7 @@ impl Value {
     /// an immediate value like nil, true, false, fixnum, or symbol).
     #[inline]
     pub const unsafe fn from_raw(raw: rb_sys::VALUE) -> Self {
-        Value(raw)
+        Value(raw, PhantomData)
     }
 
     /// Get the raw VALUE.
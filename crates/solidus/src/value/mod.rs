@@ -7,8 +7,11 @@
 //! - [`NewValue`] - Guard that enforces pinning at creation time
 //! - [`BoxValue`] - Heap-allocated, GC-registered wrapper
 //! - [`ReprValue`] - Trait for types that represent Ruby values
+//!
+//! [`Value`] also provides `funcall` and friends for calling Ruby methods.
 
 mod boxed;
+mod funcall;
 mod guard;
 mod inner;
 mod pinned;
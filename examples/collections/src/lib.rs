@@ -25,11 +25,11 @@ pub extern "C" fn build_array() -> rb_sys::VALUE {
     let arr = unsafe { RArray::new() };
 
     // Add elements one by one
-    arr.push(10i64);
-    arr.push(20i64);
-    arr.push(30i64);
-    arr.push(40i64);
-    arr.push(50i64);
+    arr.push(10i64).unwrap();
+    arr.push(20i64).unwrap();
+    arr.push(30i64).unwrap();
+    arr.push(40i64).unwrap();
+    arr.push(50i64).unwrap();
 
     assert_eq!(arr.len(), 5);
 
@@ -73,7 +73,7 @@ pub extern "C" fn filter_array_even() -> rb_sys::VALUE {
     arr.each(|val| {
         let n = i64::try_convert(val)?;
         if n % 2 == 0 {
-            evens.push(n);
+            evens.push(n).unwrap();
         }
         Ok(())
     })
@@ -118,11 +118,11 @@ pub extern "C" fn vec_to_array() -> rb_sys::VALUE {
 pub extern "C" fn array_to_vec() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let arr = unsafe { RArray::new() };
-    arr.push(11i64);
-    arr.push(22i64);
-    arr.push(33i64);
-    arr.push(44i64);
-    arr.push(55i64);
+    arr.push(11i64).unwrap();
+    arr.push(22i64).unwrap();
+    arr.push(33i64).unwrap();
+    arr.push(44i64).unwrap();
+    arr.push(55i64).unwrap();
 
     // Convert to Rust Vec<i64>
     let vec: Vec<i64> = arr.to_vec().unwrap();
@@ -152,7 +152,7 @@ pub extern "C" fn map_array_double() -> rb_sys::VALUE {
     let doubled = unsafe { RArray::with_capacity(arr.len()) };
     arr.each(|val| {
         let n = i64::try_convert(val)?;
-        doubled.push(n * 2);
+        doubled.push(n * 2).unwrap();
         Ok(())
     })
     .unwrap();
@@ -178,10 +178,10 @@ pub extern "C" fn build_hash() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Add key-value pairs
-    hash.insert("name", "Alice");
-    hash.insert("language", "Rust");
-    hash.insert("year", 2024i64);
-    hash.insert("active", true);
+    hash.insert("name", "Alice").unwrap();
+    hash.insert("language", "Rust").unwrap();
+    hash.insert("year", 2024i64).unwrap();
+    hash.insert("active", true).unwrap();
 
     assert_eq!(hash.len(), 4);
 
@@ -195,9 +195,9 @@ pub extern "C" fn build_hash() -> rb_sys::VALUE {
 pub extern "C" fn iterate_hash_entries() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let hash = unsafe { RHash::new() };
-    hash.insert("a", 10i64);
-    hash.insert("b", 20i64);
-    hash.insert("c", 30i64);
+    hash.insert("a", 10i64).unwrap();
+    hash.insert("b", 20i64).unwrap();
+    hash.insert("c", 30i64).unwrap();
 
     // Sum all values
     let mut sum = 0i64;
@@ -244,9 +244,9 @@ pub extern "C" fn hashmap_to_rhash() -> rb_sys::VALUE {
     assert_eq!(hash.len(), 3);
 
     // Verify values
-    let width = i64::try_convert(hash.get("width").unwrap()).unwrap();
-    let height = i64::try_convert(hash.get("height").unwrap()).unwrap();
-    let fps = i64::try_convert(hash.get("fps").unwrap()).unwrap();
+    let width = i64::try_convert(hash.get("width").unwrap().unwrap()).unwrap();
+    let height = i64::try_convert(hash.get("height").unwrap().unwrap()).unwrap();
+    let fps = i64::try_convert(hash.get("fps").unwrap().unwrap()).unwrap();
 
     assert_eq!(width, 1920);
     assert_eq!(height, 1080);
@@ -262,9 +262,9 @@ pub extern "C" fn hashmap_to_rhash() -> rb_sys::VALUE {
 pub extern "C" fn rhash_to_hashmap() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let hash = unsafe { RHash::new() };
-    hash.insert("red", 255i64);
-    hash.insert("green", 128i64);
-    hash.insert("blue", 64i64);
+    hash.insert("red", 255i64).unwrap();
+    hash.insert("green", 128i64).unwrap();
+    hash.insert("blue", 64i64).unwrap();
 
     // Convert to Rust HashMap
     let map: HashMap<String, i64> = hash.to_hash_map().unwrap();
@@ -288,10 +288,10 @@ pub extern "C" fn rhash_to_hashmap() -> rb_sys::VALUE {
 pub extern "C" fn filter_hash_by_value() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let hash = unsafe { RHash::new() };
-    hash.insert("small", 5i64);
-    hash.insert("medium", 15i64);
-    hash.insert("large", 25i64);
-    hash.insert("tiny", 1i64);
+    hash.insert("small", 5i64).unwrap();
+    hash.insert("medium", 15i64).unwrap();
+    hash.insert("large", 25i64).unwrap();
+    hash.insert("tiny", 1i64).unwrap();
 
     // Create a new hash with values > 10
     // SAFETY: Value is used immediately and returned to Ruby
@@ -299,17 +299,17 @@ pub extern "C" fn filter_hash_by_value() -> rb_sys::VALUE {
     hash.each(|key, val| {
         let n = i64::try_convert(val.clone())?;
         if n > 10 {
-            filtered.insert(key, val);
+            filtered.insert(key, val).unwrap();
         }
         Ok(())
     })
     .unwrap();
 
     assert_eq!(filtered.len(), 2);
-    assert!(filtered.get("medium").is_some());
-    assert!(filtered.get("large").is_some());
-    assert!(filtered.get("small").is_none());
-    assert!(filtered.get("tiny").is_none());
+    assert!(filtered.get("medium").unwrap().is_some());
+    assert!(filtered.get("large").unwrap().is_some());
+    assert!(filtered.get("small").unwrap().is_none());
+    assert!(filtered.get("tiny").unwrap().is_none());
 
     filtered.into_value().as_raw()
 }
@@ -329,26 +329,26 @@ pub extern "C" fn array_of_hashes() -> rb_sys::VALUE {
     // Create user records as hashes
     // SAFETY: Value is used immediately
     let user1 = unsafe { RHash::new() };
-    user1.insert("name", "Alice");
-    user1.insert("age", 30i64);
+    user1.insert("name", "Alice").unwrap();
+    user1.insert("age", 30i64).unwrap();
 
     let user2 = unsafe { RHash::new() };
-    user2.insert("name", "Bob");
-    user2.insert("age", 25i64);
+    user2.insert("name", "Bob").unwrap();
+    user2.insert("age", 25i64).unwrap();
 
     let user3 = unsafe { RHash::new() };
-    user3.insert("name", "Charlie");
-    user3.insert("age", 35i64);
+    user3.insert("name", "Charlie").unwrap();
+    user3.insert("age", 35i64).unwrap();
 
-    users.push(user1);
-    users.push(user2);
-    users.push(user3);
+    users.push(user1).unwrap();
+    users.push(user2).unwrap();
+    users.push(user3).unwrap();
 
     assert_eq!(users.len(), 3);
 
     // Access nested data
     let first_user = RHash::try_convert(users.entry(0)).unwrap();
-    let name_val = first_user.get("name").unwrap();
+    let name_val = first_user.get("name").unwrap().unwrap();
     let name = RString::try_convert(name_val).unwrap();
     assert_eq!(name.to_string().unwrap(), "Alice");
 
@@ -369,14 +369,14 @@ pub extern "C" fn hash_with_array_values() -> rb_sys::VALUE {
     let vegetables = unsafe { RArray::from_slice(&["carrot", "broccoli", "spinach"]) };
     let grains = unsafe { RArray::from_slice(&["rice", "wheat"]) };
 
-    data.insert("fruits", fruits);
-    data.insert("vegetables", vegetables);
-    data.insert("grains", grains);
+    data.insert("fruits", fruits).unwrap();
+    data.insert("vegetables", vegetables).unwrap();
+    data.insert("grains", grains).unwrap();
 
     assert_eq!(data.len(), 3);
 
     // Access nested array
-    let fruits_val = data.get("fruits").unwrap();
+    let fruits_val = data.get("fruits").unwrap().unwrap();
     let fruits_arr = RArray::try_convert(fruits_val).unwrap();
     assert_eq!(fruits_arr.len(), 3);
 
@@ -405,25 +405,25 @@ pub extern "C" fn group_by_length() -> rb_sys::VALUE {
             let len = word.len() as i64;
 
             // Get or create the array for this length
-            let group = match grouped.get(len) {
+            let group = match grouped.get(len).unwrap() {
                 Some(existing) => RArray::try_convert(existing)?,
                 None => {
                     // SAFETY: Value is immediately inserted into the hash and used
                     let new_group = unsafe { RArray::new() };
-                    grouped.insert(len, new_group.clone());
+                    grouped.insert(len, new_group.clone()).unwrap();
                     new_group
                 }
             };
 
-            group.push(val);
+            group.push(val).unwrap();
             Ok(())
         })
         .unwrap();
 
     // Verify grouping
-    let len_1 = RArray::try_convert(grouped.get(1i64).unwrap()).unwrap();
-    let len_2 = RArray::try_convert(grouped.get(2i64).unwrap()).unwrap();
-    let len_3 = RArray::try_convert(grouped.get(3i64).unwrap()).unwrap();
+    let len_1 = RArray::try_convert(grouped.get(1i64).unwrap().unwrap()).unwrap();
+    let len_2 = RArray::try_convert(grouped.get(2i64).unwrap().unwrap()).unwrap();
+    let len_3 = RArray::try_convert(grouped.get(3i64).unwrap().unwrap()).unwrap();
 
     assert_eq!(len_1.len(), 1); // "a"
     assert_eq!(len_2.len(), 2); // "to", "be"
@@ -439,9 +439,9 @@ pub extern "C" fn group_by_length() -> rb_sys::VALUE {
 pub extern "C" fn flatten_hash_arrays() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let data = unsafe { RHash::new() };
-    data.insert("a", unsafe { RArray::from_slice(&[1i64, 2]) });
-    data.insert("b", unsafe { RArray::from_slice(&[3i64, 4, 5]) });
-    data.insert("c", unsafe { RArray::from_slice(&[6i64]) });
+    data.insert("a", unsafe { RArray::from_slice(&[1i64, 2]) }).unwrap();
+    data.insert("b", unsafe { RArray::from_slice(&[3i64, 4, 5]) }).unwrap();
+    data.insert("c", unsafe { RArray::from_slice(&[6i64]) }).unwrap();
 
     // Flatten all arrays into one
     // SAFETY: Value is used immediately and returned to Ruby
@@ -449,7 +449,7 @@ pub extern "C" fn flatten_hash_arrays() -> rb_sys::VALUE {
     data.each(|_key, val| {
         let arr = RArray::try_convert(val)?;
         arr.each(|item| {
-            flattened.push(item);
+            flattened.push(item).unwrap();
             Ok(())
         })?;
         Ok(())
@@ -20,11 +20,7 @@ fn hello() -> Result<BoxValue<RString>, Error> {
 #[solidus_macros::init]
 fn init(ruby: &Ruby) -> Result<(), Error> {
     // Define a global function that can be called from Ruby
-    ruby.define_global_function(
-        "hello",
-        __solidus_function_hello::wrapper(),
-        __solidus_function_hello::ARITY,
-    )?;
+    ruby.define_global_function("hello", __solidus_function_hello::wrapper())?;
 
     Ok(())
 }
@@ -38,7 +38,7 @@ pub extern "C" fn example_array_with_capacity() -> rb_sys::VALUE {
 
     // Adding elements won't trigger reallocation
     for i in 0..100 {
-        arr.push(i as i64);
+        arr.push(i as i64).unwrap();
     }
 
     assert_eq!(arr.len(), 100);
@@ -55,29 +55,29 @@ pub extern "C" fn example_array_push_pop() -> rb_sys::VALUE {
     let arr = unsafe { RArray::new() };
 
     // Push some elements
-    arr.push(10i64);
-    arr.push(20i64);
-    arr.push(30i64);
+    arr.push(10i64).unwrap();
+    arr.push(20i64).unwrap();
+    arr.push(30i64).unwrap();
 
     assert_eq!(arr.len(), 3);
 
     // Pop the last element
-    let last = arr.pop().unwrap();
+    let last = arr.pop().unwrap().unwrap();
     assert_eq!(i64::try_convert(last).unwrap(), 30);
     assert_eq!(arr.len(), 2);
 
     // Pop another
-    let second = arr.pop().unwrap();
+    let second = arr.pop().unwrap().unwrap();
     assert_eq!(i64::try_convert(second).unwrap(), 20);
     assert_eq!(arr.len(), 1);
 
     // One element remains
-    let first = arr.pop().unwrap();
+    let first = arr.pop().unwrap().unwrap();
     assert_eq!(i64::try_convert(first).unwrap(), 10);
     assert!(arr.is_empty());
 
     // Popping from empty array returns None
-    assert!(arr.pop().is_none());
+    assert!(arr.pop().unwrap().is_none());
 
     arr.into_value().as_raw()
 }
@@ -89,11 +89,11 @@ pub extern "C" fn example_array_push_pop() -> rb_sys::VALUE {
 pub extern "C" fn example_array_entry() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let arr = unsafe { RArray::new() };
-    arr.push(100i64);
-    arr.push(200i64);
-    arr.push(300i64);
-    arr.push(400i64);
-    arr.push(500i64);
+    arr.push(100i64).unwrap();
+    arr.push(200i64).unwrap();
+    arr.push(300i64).unwrap();
+    arr.push(400i64).unwrap();
+    arr.push(500i64).unwrap();
 
     // Positive indices
     let val0 = arr.entry(0);
@@ -131,19 +131,19 @@ pub extern "C" fn example_array_store() -> rb_sys::VALUE {
     let arr = unsafe { RArray::new() };
 
     // Store at index 0
-    arr.store(0, 42i64);
+    arr.store(0, 42i64).unwrap();
     assert_eq!(arr.len(), 1);
 
     let val = arr.entry(0);
     assert_eq!(i64::try_convert(val).unwrap(), 42);
 
     // Replace existing element
-    arr.store(0, 99i64);
+    arr.store(0, 99i64).unwrap();
     let val = arr.entry(0);
     assert_eq!(i64::try_convert(val).unwrap(), 99);
 
     // Store beyond current length extends array with nils
-    arr.store(5, 123i64);
+    arr.store(5, 123i64).unwrap();
     assert_eq!(arr.len(), 6);
 
     // Elements 1-4 are nil
@@ -155,7 +155,7 @@ pub extern "C" fn example_array_store() -> rb_sys::VALUE {
     assert_eq!(i64::try_convert(val5).unwrap(), 123);
 
     // Negative indices work too
-    arr.store(-1, 456i64);
+    arr.store(-1, 456i64).unwrap();
     let val_last = arr.entry(-1);
     assert_eq!(i64::try_convert(val_last).unwrap(), 456);
 
@@ -172,7 +172,7 @@ pub extern "C" fn example_array_each() -> rb_sys::VALUE {
 
     // Add some numbers
     for i in 1..=10 {
-        arr.push(i as i64);
+        arr.push(i as i64).unwrap();
     }
 
     // Sum all elements using each()
@@ -228,11 +228,11 @@ pub extern "C" fn example_array_from_slice() -> rb_sys::VALUE {
 pub extern "C" fn example_array_to_vec() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let arr = unsafe { RArray::new() };
-    arr.push(10i64);
-    arr.push(20i64);
-    arr.push(30i64);
-    arr.push(40i64);
-    arr.push(50i64);
+    arr.push(10i64).unwrap();
+    arr.push(20i64).unwrap();
+    arr.push(30i64).unwrap();
+    arr.push(40i64).unwrap();
+    arr.push(50i64).unwrap();
 
     // Convert to Rust Vec<i64>
     let vec: Vec<i64> = arr.to_vec().unwrap();
@@ -259,11 +259,11 @@ pub extern "C" fn example_array_mixed_types() -> rb_sys::VALUE {
     let arr = unsafe { RArray::new() };
 
     // Ruby arrays can hold different types
-    arr.push(42i64);
+    arr.push(42i64).unwrap();
     // SAFETY: Value is used immediately
-    arr.push(unsafe { RString::new("hello") });
-    arr.push(true);
-    arr.push(2.5f64);
+    arr.push(unsafe { RString::new("hello") }).unwrap();
+    arr.push(true).unwrap();
+    arr.push(2.5f64).unwrap();
 
     assert_eq!(arr.len(), 4);
 
@@ -293,9 +293,9 @@ pub extern "C" fn example_typed_array() -> rb_sys::VALUE {
     // Create an array of strings
     // SAFETY: Value is used immediately and returned to Ruby
     let arr = unsafe { RArray::new() };
-    arr.push(unsafe { RString::new("apple") });
-    arr.push(unsafe { RString::new("banana") });
-    arr.push(unsafe { RString::new("cherry") });
+    arr.push(unsafe { RString::new("apple") }).unwrap();
+    arr.push(unsafe { RString::new("banana") }).unwrap();
+    arr.push(unsafe { RString::new("cherry") }).unwrap();
 
     // Convert to Vec<String> with type checking
     let mut strings = Vec::new();
@@ -323,9 +323,9 @@ pub extern "C" fn example_nested_arrays() -> rb_sys::VALUE {
     let row3 = unsafe { RArray::from_slice(&[7i64, 8, 9]) };
 
     let matrix = unsafe { RArray::new() };
-    matrix.push(row1);
-    matrix.push(row2);
-    matrix.push(row3);
+    matrix.push(row1).unwrap();
+    matrix.push(row2).unwrap();
+    matrix.push(row3).unwrap();
 
     assert_eq!(matrix.len(), 3);
 
@@ -351,10 +351,10 @@ pub extern "C" fn example_nested_arrays() -> rb_sys::VALUE {
 pub extern "C" fn example_array_error_handling() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let arr = unsafe { RArray::new() };
-    arr.push(1i64);
-    arr.push(2i64);
-    arr.push(unsafe { RString::new("not a number") }); // Type mismatch
-    arr.push(4i64);
+    arr.push(1i64).unwrap();
+    arr.push(2i64).unwrap();
+    arr.push(unsafe { RString::new("not a number") }).unwrap(); // Type mismatch
+    arr.push(4i64).unwrap();
 
     // Try to convert to Vec<i64> - will fail at the string
     let result: Result<Vec<i64>, Error> = arr.to_vec();
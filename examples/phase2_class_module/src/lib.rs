@@ -105,7 +105,7 @@ pub extern "C" fn example_iterate_superclasses() -> rb_sys::VALUE {
     while let Some(class) = current_class {
         if let Some(name) = class.name() {
             // SAFETY: Value is used immediately
-            result.push(unsafe { RString::new(&name) });
+            result.push(unsafe { RString::new(&name) }).unwrap();
         }
         current_class = class.superclass();
     }
@@ -375,7 +375,7 @@ pub extern "C" fn example_complex_hierarchy() -> rb_sys::VALUE {
     if let Some(file_stat) = RClass::from_name("File::Stat") {
         if let Some(name) = file_stat.name() {
             // SAFETY: Value is used immediately
-            result.push(unsafe { RString::new(&name) });
+            result.push(unsafe { RString::new(&name) }).unwrap();
         }
 
         // Walk its superclass chain
@@ -383,7 +383,7 @@ pub extern "C" fn example_complex_hierarchy() -> rb_sys::VALUE {
         while let Some(class) = current {
             if let Some(name) = class.name() {
                 // SAFETY: Value is used immediately
-                result.push(unsafe { RString::new(&name) });
+                result.push(unsafe { RString::new(&name) }).unwrap();
             }
             current = class.superclass();
         }
@@ -34,9 +34,9 @@ pub extern "C" fn example_hash_insert() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Insert string key with integer value
-    hash.insert("name", "Alice");
-    hash.insert("age", 30i64);
-    hash.insert("active", true);
+    hash.insert("name", "Alice").unwrap();
+    hash.insert("age", 30i64).unwrap();
+    hash.insert("active", true).unwrap();
 
     // Check the size
     assert_eq!(hash.len(), 3);
@@ -54,22 +54,22 @@ pub extern "C" fn example_hash_get() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Insert some data
-    hash.insert("x", 100i64);
-    hash.insert("y", 200i64);
+    hash.insert("x", 100i64).unwrap();
+    hash.insert("y", 200i64).unwrap();
 
     // Get values by key
-    if let Some(val) = hash.get("x") {
+    if let Some(val) = hash.get("x").unwrap() {
         let x = i64::try_convert(val).unwrap();
         assert_eq!(x, 100);
     }
 
-    if let Some(val) = hash.get("y") {
+    if let Some(val) = hash.get("y").unwrap() {
         let y = i64::try_convert(val).unwrap();
         assert_eq!(y, 200);
     }
 
     // Missing key returns None
-    assert!(hash.get("z").is_none());
+    assert!(hash.get("z").unwrap().is_none());
 
     hash.into_value().as_raw()
 }
@@ -83,15 +83,15 @@ pub extern "C" fn example_hash_update() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Insert initial value
-    hash.insert("counter", 1i64);
+    hash.insert("counter", 1i64).unwrap();
     assert_eq!(hash.len(), 1);
 
     // Update the same key
-    hash.insert("counter", 2i64);
+    hash.insert("counter", 2i64).unwrap();
     assert_eq!(hash.len(), 1); // Still just one key
 
     // Verify the value was updated
-    let val = hash.get("counter").unwrap();
+    let val = hash.get("counter").unwrap().unwrap();
     let counter = i64::try_convert(val).unwrap();
     assert_eq!(counter, 2);
 
@@ -107,23 +107,23 @@ pub extern "C" fn example_hash_delete() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Insert data
-    hash.insert("keep", 1i64);
-    hash.insert("remove", 2i64);
+    hash.insert("keep", 1i64).unwrap();
+    hash.insert("remove", 2i64).unwrap();
     assert_eq!(hash.len(), 2);
 
     // Delete a key and get its value
-    if let Some(val) = hash.delete("remove") {
+    if let Some(val) = hash.delete("remove").unwrap() {
         let removed = i64::try_convert(val).unwrap();
         assert_eq!(removed, 2);
     }
 
     // Hash now has one element
     assert_eq!(hash.len(), 1);
-    assert!(hash.get("remove").is_none());
-    assert!(hash.get("keep").is_some());
+    assert!(hash.get("remove").unwrap().is_none());
+    assert!(hash.get("keep").unwrap().is_some());
 
     // Deleting non-existent key returns None
-    assert!(hash.delete("missing").is_none());
+    assert!(hash.delete("missing").unwrap().is_none());
 
     hash.into_value().as_raw()
 }
@@ -137,9 +137,9 @@ pub extern "C" fn example_hash_iteration() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Insert some data
-    hash.insert("a", 1i64);
-    hash.insert("b", 2i64);
-    hash.insert("c", 3i64);
+    hash.insert("a", 1i64).unwrap();
+    hash.insert("b", 2i64).unwrap();
+    hash.insert("c", 3i64).unwrap();
 
     // Iterate and sum values
     let mut sum = 0i64;
@@ -167,16 +167,16 @@ pub extern "C" fn example_hash_symbol_keys() -> rb_sys::VALUE {
     let name_key = Symbol::new("name");
     let age_key = Symbol::new("age");
 
-    hash.insert(name_key.clone(), "Bob");
-    hash.insert(age_key.clone(), 25i64);
+    hash.insert(name_key.clone(), "Bob").unwrap();
+    hash.insert(age_key.clone(), 25i64).unwrap();
 
     // Retrieve using symbols
-    if let Some(val) = hash.get(name_key) {
+    if let Some(val) = hash.get(name_key).unwrap() {
         let name = RString::try_convert(val).unwrap();
         assert_eq!(name.to_string().unwrap(), "Bob");
     }
 
-    if let Some(val) = hash.get(age_key) {
+    if let Some(val) = hash.get(age_key).unwrap() {
         let age = i64::try_convert(val).unwrap();
         assert_eq!(age, 25);
     }
@@ -193,14 +193,14 @@ pub extern "C" fn example_hash_integer_keys() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Use integers as keys
-    hash.insert(1i64, "first");
-    hash.insert(2i64, "second");
-    hash.insert(3i64, "third");
+    hash.insert(1i64, "first").unwrap();
+    hash.insert(2i64, "second").unwrap();
+    hash.insert(3i64, "third").unwrap();
 
     assert_eq!(hash.len(), 3);
 
     // Retrieve by integer key
-    if let Some(val) = hash.get(2i64) {
+    if let Some(val) = hash.get(2i64).unwrap() {
         let s = RString::try_convert(val).unwrap();
         assert_eq!(s.to_string().unwrap(), "second");
     }
@@ -217,16 +217,16 @@ pub extern "C" fn example_hash_mixed_keys() -> rb_sys::VALUE {
     let hash = unsafe { RHash::new() };
 
     // Mix different key types
-    hash.insert("string_key", 1i64);
-    hash.insert(Symbol::new("symbol_key"), 2i64);
-    hash.insert(100i64, 3i64);
+    hash.insert("string_key", 1i64).unwrap();
+    hash.insert(Symbol::new("symbol_key"), 2i64).unwrap();
+    hash.insert(100i64, 3i64).unwrap();
 
     assert_eq!(hash.len(), 3);
 
     // Each key type is distinct
-    assert!(hash.get("string_key").is_some());
-    assert!(hash.get(Symbol::new("symbol_key")).is_some());
-    assert!(hash.get(100i64).is_some());
+    assert!(hash.get("string_key").unwrap().is_some());
+    assert!(hash.get(Symbol::new("symbol_key")).unwrap().is_some());
+    assert!(hash.get(100i64).unwrap().is_some());
 
     hash.into_value().as_raw()
 }
@@ -249,7 +249,7 @@ pub extern "C" fn example_hash_from_hashmap() -> rb_sys::VALUE {
     assert_eq!(hash.len(), 3);
 
     // Verify the values
-    let red = i64::try_convert(hash.get("red").unwrap()).unwrap();
+    let red = i64::try_convert(hash.get("red").unwrap().unwrap()).unwrap();
     assert_eq!(red, 255);
 
     hash.into_value().as_raw()
@@ -263,8 +263,8 @@ pub extern "C" fn example_hash_to_hashmap() -> rb_sys::VALUE {
     // Create Ruby hash
     // SAFETY: Value is used immediately and returned to Ruby
     let hash = unsafe { RHash::new() };
-    hash.insert("width", 1920i64);
-    hash.insert("height", 1080i64);
+    hash.insert("width", 1920i64).unwrap();
+    hash.insert("height", 1080i64).unwrap();
 
     // Convert to Rust HashMap
     let map: HashMap<String, i64> = hash.to_hash_map().unwrap();
@@ -284,23 +284,23 @@ pub extern "C" fn example_hash_nested() -> rb_sys::VALUE {
     // Create inner hash
     // SAFETY: Value is used immediately
     let inner = unsafe { RHash::new() };
-    inner.insert("city", "Portland");
-    inner.insert("state", "Oregon");
+    inner.insert("city", "Portland").unwrap();
+    inner.insert("state", "Oregon").unwrap();
 
     // Create outer hash
     // SAFETY: Value is used immediately and returned to Ruby
     let outer = unsafe { RHash::new() };
-    outer.insert("name", "Alice");
-    outer.insert("location", inner);
+    outer.insert("name", "Alice").unwrap();
+    outer.insert("location", inner).unwrap();
 
     assert_eq!(outer.len(), 2);
 
     // Retrieve nested hash
-    if let Some(val) = outer.get("location") {
+    if let Some(val) = outer.get("location").unwrap() {
         let location = RHash::try_convert(val).unwrap();
         assert_eq!(location.len(), 2);
 
-        if let Some(city_val) = location.get("city") {
+        if let Some(city_val) = location.get("city").unwrap() {
             let city = RString::try_convert(city_val).unwrap();
             assert_eq!(city.to_string().unwrap(), "Portland");
         }
@@ -315,9 +315,9 @@ pub extern "C" fn example_hash_nested() -> rb_sys::VALUE {
 fn build_user_hash(name: &str, age: i64, active: bool) -> Result<NewValue<RHash>, Error> {
     // SAFETY: Value is returned as part of a Result, caller handles it
     let hash = unsafe { RHash::new() };
-    hash.insert("name", name);
-    hash.insert("age", age);
-    hash.insert("active", active);
+    hash.insert("name", name)?;
+    hash.insert("age", age)?;
+    hash.insert("active", active)?;
     Ok(NewValue::new(hash))
 }
 
@@ -328,13 +328,13 @@ pub extern "C" fn example_hash_type_safe() -> rb_sys::VALUE {
             assert_eq!(hash.len(), 3);
 
             // Verify types
-            let name = RString::try_convert(hash.get("name").unwrap()).unwrap();
+            let name = RString::try_convert(hash.get("name").unwrap().unwrap()).unwrap();
             assert_eq!(name.to_string().unwrap(), "Charlie");
 
-            let age = i64::try_convert(hash.get("age").unwrap()).unwrap();
+            let age = i64::try_convert(hash.get("age").unwrap().unwrap()).unwrap();
             assert_eq!(age, 35);
 
-            let active = bool::try_convert(hash.get("active").unwrap()).unwrap();
+            let active = bool::try_convert(hash.get("active").unwrap().unwrap()).unwrap();
             assert!(active);
 
             hash.into_value().as_raw()
@@ -377,9 +377,9 @@ pub extern "C" fn example_hash_roundtrip() -> rb_sys::VALUE {
 pub extern "C" fn example_hash_collect_keys() -> rb_sys::VALUE {
     // SAFETY: Value is used immediately and returned to Ruby
     let hash = unsafe { RHash::new() };
-    hash.insert("alpha", 1i64);
-    hash.insert("beta", 2i64);
-    hash.insert("gamma", 3i64);
+    hash.insert("alpha", 1i64).unwrap();
+    hash.insert("beta", 2i64).unwrap();
+    hash.insert("gamma", 3i64).unwrap();
 
     // Collect all keys
     let mut keys = Vec::new();
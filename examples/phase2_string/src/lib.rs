@@ -123,8 +123,8 @@ pub extern "C" fn example_string_encoding() -> rb_sys::VALUE {
     let enc = s.encoding();
     let _enc_name = enc.name();
 
-    // Default encoding is usually UTF-8
-    // (depends on Ruby version and environment)
+    // Strings created from a Rust &str are UTF-8
+    assert_eq!(enc, Encoding::utf8());
 
     // We can also get standard encodings
     let utf8_enc = Encoding::utf8();
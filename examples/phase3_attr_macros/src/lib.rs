@@ -90,7 +90,10 @@ fn greet(name: Pin<&StackPinned<RString>>) -> Result<NewValue<RString>, Error> {
 
 /// Global function with two arguments, both automatically pinned.
 #[solidus_macros::function]
-fn join_strings(first: Pin<&StackPinned<RString>>, second: Pin<&StackPinned<RString>>) -> Result<NewValue<RString>, Error> {
+fn join_strings(
+    first: Pin<&StackPinned<RString>>,
+    second: Pin<&StackPinned<RString>>,
+) -> Result<NewValue<RString>, Error> {
     let a = first.get().to_string()?;
     let b = second.get().to_string()?;
     // SAFETY: Value is immediately returned to Ruby
@@ -116,7 +119,11 @@ fn concat(rb_self: RString, other: Pin<&StackPinned<RString>>) -> Result<NewValu
 
 /// Instance method with two automatically pinned arguments.
 #[solidus_macros::method]
-fn surround(rb_self: RString, prefix: Pin<&StackPinned<RString>>, suffix: Pin<&StackPinned<RString>>) -> Result<NewValue<RString>, Error> {
+fn surround(
+    rb_self: RString,
+    prefix: Pin<&StackPinned<RString>>,
+    suffix: Pin<&StackPinned<RString>>,
+) -> Result<NewValue<RString>, Error> {
     let p = prefix.get().to_string()?;
     let s = rb_self.to_string()?;
     let x = suffix.get().to_string()?;
@@ -199,7 +206,10 @@ fn reverse(s: Pin<&StackPinned<RString>>) -> Result<NewValue<RString>, Error> {
 
 /// Module function with two args.
 #[solidus_macros::function]
-fn repeat_join(text: Pin<&StackPinned<RString>>, separator: Pin<&StackPinned<RString>>) -> Result<NewValue<RString>, Error> {
+fn repeat_join(
+    text: Pin<&StackPinned<RString>>,
+    separator: Pin<&StackPinned<RString>>,
+) -> Result<NewValue<RString>, Error> {
     let t = text.get().to_string()?;
     let sep = separator.get().to_string()?;
     // SAFETY: Value is immediately returned to Ruby
@@ -219,27 +229,19 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     ruby.define_global_function(
         "attr_get_greeting",
         __solidus_function_get_greeting::wrapper(),
-        __solidus_function_get_greeting::ARITY,
-    )?;
-    ruby.define_global_function(
-        "attr_greet",
-        __solidus_function_greet::wrapper(),
-        __solidus_function_greet::ARITY,
     )?;
+    ruby.define_global_function("attr_greet", __solidus_function_greet::wrapper())?;
     ruby.define_global_function(
         "attr_join_strings",
         __solidus_function_join_strings::wrapper(),
-        __solidus_function_join_strings::ARITY,
     )?;
     ruby.define_global_function(
         "attr_uppercase_explicit",
         __solidus_function_uppercase_explicit::wrapper(),
-        __solidus_function_uppercase_explicit::ARITY,
     )?;
     ruby.define_global_function(
         "attr_format_mixed",
         __solidus_function_format_mixed::wrapper(),
-        __solidus_function_format_mixed::ARITY,
     )?;
 
     // ========================================================================
@@ -250,30 +252,22 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     let attr_string_rclass = RClass::try_convert(attr_string_class)?;
 
     // Register instance methods using the generated modules
-    attr_string_rclass.clone().define_method(
-        "attr_length",
-        __solidus_method_length::wrapper(),
-        __solidus_method_length::ARITY,
-    )?;
-    attr_string_rclass.clone().define_method(
-        "attr_concat",
-        __solidus_method_concat::wrapper(),
-        __solidus_method_concat::ARITY,
-    )?;
-    attr_string_rclass.clone().define_method(
-        "attr_surround",
-        __solidus_method_surround::wrapper(),
-        __solidus_method_surround::ARITY,
-    )?;
+    attr_string_rclass
+        .clone()
+        .define_method("attr_length", __solidus_method_length::wrapper())?;
+    attr_string_rclass
+        .clone()
+        .define_method("attr_concat", __solidus_method_concat::wrapper())?;
+    attr_string_rclass
+        .clone()
+        .define_method("attr_surround", __solidus_method_surround::wrapper())?;
     attr_string_rclass.clone().define_method(
         "attr_concat_explicit",
         __solidus_method_concat_explicit::wrapper(),
-        __solidus_method_concat_explicit::ARITY,
     )?;
     attr_string_rclass.clone().define_method(
         "attr_combine_mixed",
         __solidus_method_combine_mixed::wrapper(),
-        __solidus_method_combine_mixed::ARITY,
     )?;
 
     // ========================================================================
@@ -283,21 +277,15 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     let string_utils_module = ruby.define_module("AttrStringUtils");
     let string_utils_rmodule = RModule::try_convert(string_utils_module)?;
 
-    string_utils_rmodule.clone().define_module_function(
-        "to_upper",
-        __solidus_function_to_upper::wrapper(),
-        __solidus_function_to_upper::ARITY,
-    )?;
-    string_utils_rmodule.clone().define_module_function(
-        "reverse",
-        __solidus_function_reverse::wrapper(),
-        __solidus_function_reverse::ARITY,
-    )?;
-    string_utils_rmodule.clone().define_module_function(
-        "repeat_join",
-        __solidus_function_repeat_join::wrapper(),
-        __solidus_function_repeat_join::ARITY,
-    )?;
+    string_utils_rmodule
+        .clone()
+        .define_module_function("to_upper", __solidus_function_to_upper::wrapper())?;
+    string_utils_rmodule
+        .clone()
+        .define_module_function("reverse", __solidus_function_reverse::wrapper())?;
+    string_utils_rmodule
+        .clone()
+        .define_module_function("repeat_join", __solidus_function_repeat_join::wrapper())?;
 
     Ok(())
 }
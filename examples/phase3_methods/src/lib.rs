@@ -32,11 +32,7 @@ fn greet<'ctx>(
 
 /// Instance method with arity 1 - self + one argument
 /// Adds a number to another number (passed as string, converted to int)
-fn add(
-    _ctx: &Context,
-    rb_self: RString,
-    other: Pin<&StackPinned<RString>>,
-) -> Result<i64, Error> {
+fn add(_ctx: &Context, rb_self: RString, other: Pin<&StackPinned<RString>>) -> Result<i64, Error> {
     let a = rb_self
         .to_string()?
         .parse::<i64>()
@@ -261,28 +257,23 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     // Instance methods using method! macro
     calc_rclass
         .clone()
-        .define_method("greet", solidus::method!(greet, 0), 0)?;
+        .define_method("greet", solidus::method!(greet, 0))?;
     calc_rclass
         .clone()
-        .define_method("add", solidus::method!(add, 1), 1)?;
+        .define_method("add", solidus::method!(add, 1))?;
     calc_rclass
         .clone()
-        .define_method("multiply_three", solidus::method!(multiply_three, 2), 2)?;
+        .define_method("multiply_three", solidus::method!(multiply_three, 2))?;
     calc_rclass
         .clone()
-        .define_method("always_fails", solidus::method!(always_fails, 0), 0)?;
+        .define_method("always_fails", solidus::method!(always_fails, 0))?;
 
     // Class methods using function! macro and define_singleton_method
-    calc_rclass.clone().define_singleton_method(
-        "create_default",
-        solidus::function!(create_default, 0),
-        0,
-    )?;
-    calc_rclass.define_singleton_method(
-        "create_with_name",
-        solidus::function!(create_with_name, 1),
-        1,
-    )?;
+    calc_rclass
+        .clone()
+        .define_singleton_method("create_default", solidus::function!(create_default, 0))?;
+    calc_rclass
+        .define_singleton_method("create_with_name", solidus::function!(create_with_name, 1))?;
 
     // ========================================================================
     // Define StringUtils module and its module functions
@@ -293,21 +284,13 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
 
     // Module functions using function! macro and define_module_function
     // These can be called as StringUtils.method_name or via include
-    string_utils_rmodule.clone().define_module_function(
-        "get_version",
-        solidus::function!(get_version, 0),
-        0,
-    )?;
-    string_utils_rmodule.clone().define_module_function(
-        "to_upper",
-        solidus::function!(to_upper, 1),
-        1,
-    )?;
-    string_utils_rmodule.define_module_function(
-        "join_with",
-        solidus::function!(join_with, 2),
-        2,
-    )?;
+    string_utils_rmodule
+        .clone()
+        .define_module_function("get_version", solidus::function!(get_version, 0))?;
+    string_utils_rmodule
+        .clone()
+        .define_module_function("to_upper", solidus::function!(to_upper, 1))?;
+    string_utils_rmodule.define_module_function("join_with", solidus::function!(join_with, 2))?;
 
     // ========================================================================
     // Define Math module with class methods
@@ -319,20 +302,20 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     // Singleton methods on the module (class methods)
     math_rmodule
         .clone()
-        .define_singleton_method("pi", solidus::function!(pi, 0), 0)?;
+        .define_singleton_method("pi", solidus::function!(pi, 0))?;
     math_rmodule
         .clone()
-        .define_singleton_method("double", solidus::function!(double, 1), 1)?;
-    math_rmodule.define_singleton_method("power", solidus::function!(power, 2), 2)?;
+        .define_singleton_method("double", solidus::function!(double, 1))?;
+    math_rmodule.define_singleton_method("power", solidus::function!(power, 2))?;
 
     // ========================================================================
     // Define global functions
     // ========================================================================
 
-    ruby.define_global_function("hello", solidus::function!(hello, 0), 0)?;
-    ruby.define_global_function("repeat_string", solidus::function!(repeat_string, 1), 1)?;
-    ruby.define_global_function("add_numbers", solidus::function!(add_numbers, 2), 2)?;
-    ruby.define_global_function("average_three", solidus::function!(average_three, 3), 3)?;
+    ruby.define_global_function("hello", solidus::function!(hello, 0))?;
+    ruby.define_global_function("repeat_string", solidus::function!(repeat_string, 1))?;
+    ruby.define_global_function("add_numbers", solidus::function!(add_numbers, 2))?;
+    ruby.define_global_function("average_three", solidus::function!(average_three, 3))?;
 
     Ok(())
 }
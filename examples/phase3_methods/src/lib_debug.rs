@@ -8,7 +8,7 @@ fn debug_func(s: Pin<&StackPinned<RString>>) -> Result<RString, Error> {
 
 fn init_debug(ruby: &Ruby) -> Result<(), Error> {
     // Try to register with explicit type checking
-    ruby.define_global_function("debug_func", solidus::function!(debug_func, 1), 1)?;
+    ruby.define_global_function("debug_func", solidus::function!(debug_func, 1))?;
     Ok(())
 }
 
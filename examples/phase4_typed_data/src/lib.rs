@@ -1,5 +1,5 @@
 use solidus::prelude::*;
-use solidus::typed_data::{get, get_mut, wrap, DataTypeFunctions, Marker};
+use solidus::typed_data::{DataTypeFunctions, Marker, get, get_mut, wrap};
 use std::cell::RefCell;
 use std::pin::Pin;
 use std::sync::OnceLock;
@@ -48,23 +48,24 @@ fn point_new(x: f64, y: f64) -> Result<Value, Error> {
     wrap(ruby, class, point)
 }
 
+// A `&Point` receiver is unwrapped by the macro, raising TypeError if self
+// isn't a Point
 #[solidus_macros::method]
-fn point_x(rb_self: Pin<&StackPinned<Value>>) -> Result<f64, Error> {
-    let point: &Point = get(rb_self.get())?;
+fn point_x(point: &Point) -> Result<f64, Error> {
     Ok(point.x())
 }
 
 #[solidus_macros::method]
-fn point_y(rb_self: Pin<&StackPinned<Value>>) -> Result<f64, Error> {
-    let point: &Point = get(rb_self.get())?;
+fn point_y(point: &Point) -> Result<f64, Error> {
     Ok(point.y())
 }
 
+// Only the receiver can be a reference; other wrapped arguments are unwrapped
+// with get()
 #[solidus_macros::method]
-fn point_distance(rb_self: Pin<&StackPinned<Value>>, other: Pin<&StackPinned<Value>>) -> Result<f64, Error> {
-    let point: &Point = get(rb_self.get())?;
-    let other_point: &Point = get(other.get())?;
-    Ok(point.distance(other_point))
+fn point_distance(point: &Point, other: Pin<&StackPinned<Value>>) -> Result<f64, Error> {
+    let other_point = get::<Point>(other.get())?;
+    Ok(point.distance(&other_point))
 }
 
 // ============================================================================
@@ -106,14 +107,12 @@ fn counter_new(initial: i64) -> Result<Value, Error> {
 }
 
 #[solidus_macros::method]
-fn counter_get(rb_self: Pin<&StackPinned<Value>>) -> Result<i64, Error> {
-    let counter: &Counter = get(rb_self.get())?;
+fn counter_get(counter: &Counter) -> Result<i64, Error> {
     Ok(counter.get())
 }
 
 #[solidus_macros::method]
-fn counter_increment(rb_self: Pin<&StackPinned<Value>>) -> Result<i64, Error> {
-    let counter: &Counter = get(rb_self.get())?;
+fn counter_increment(counter: &Counter) -> Result<i64, Error> {
     Ok(counter.increment())
 }
 
@@ -163,23 +162,26 @@ fn container_new() -> Result<Value, Error> {
     wrap(ruby, class, container)
 }
 
+// push returns self, so it takes the receiver as a Value and borrows the
+// Container with get_mut()
 #[solidus_macros::method]
-fn container_push(rb_self: Pin<&StackPinned<Value>>, value: Pin<&StackPinned<Value>>) -> Result<Value, Error> {
-    let container: &mut Container = get_mut(rb_self.get())?;
+fn container_push(
+    rb_self: Pin<&StackPinned<Value>>,
+    value: Pin<&StackPinned<Value>>,
+) -> Result<Value, Error> {
+    let mut container = get_mut::<Container>(rb_self.get())?;
     let boxed = BoxValue::new(value.get().as_value());
     container.push(boxed);
     Ok(rb_self.get().as_value())
 }
 
 #[solidus_macros::method]
-fn container_len(rb_self: Pin<&StackPinned<Value>>) -> Result<usize, Error> {
-    let container: &Container = get(rb_self.get())?;
+fn container_len(container: &Container) -> Result<usize, Error> {
     Ok(container.len())
 }
 
 #[solidus_macros::method]
-fn container_get(rb_self: Pin<&StackPinned<Value>>, index: i64) -> Result<Value, Error> {
-    let container: &Container = get(rb_self.get())?;
+fn container_get(container: &Container, index: i64) -> Result<Value, Error> {
     if index < 0 {
         return Err(Error::runtime("Index cannot be negative"));
     }
@@ -199,26 +201,18 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     let point_class = RClass::try_convert(point_class_val)?;
 
     // Define all methods on the class using attribute macro generated modules
-    point_class.clone().define_singleton_method(
-        "new",
-        __solidus_function_point_new::wrapper(),
-        __solidus_function_point_new::ARITY,
-    )?;
-    point_class.clone().define_method(
-        "x",
-        __solidus_method_point_x::wrapper(),
-        __solidus_method_point_x::ARITY,
-    )?;
-    point_class.clone().define_method(
-        "y",
-        __solidus_method_point_y::wrapper(),
-        __solidus_method_point_y::ARITY,
-    )?;
-    point_class.clone().define_method(
-        "distance",
-        __solidus_method_point_distance::wrapper(),
-        __solidus_method_point_distance::ARITY,
-    )?;
+    point_class
+        .clone()
+        .define_singleton_method("new", __solidus_function_point_new::wrapper())?;
+    point_class
+        .clone()
+        .define_method("x", __solidus_method_point_x::wrapper())?;
+    point_class
+        .clone()
+        .define_method("y", __solidus_method_point_y::wrapper())?;
+    point_class
+        .clone()
+        .define_method("distance", __solidus_method_point_distance::wrapper())?;
 
     // Then store in OnceLock
     POINT_CLASS
@@ -230,21 +224,15 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     let counter_class = RClass::try_convert(counter_class_val)?;
 
     // Define all methods on the class using attribute macro generated modules
-    counter_class.clone().define_singleton_method(
-        "new",
-        __solidus_function_counter_new::wrapper(),
-        __solidus_function_counter_new::ARITY,
-    )?;
-    counter_class.clone().define_method(
-        "get",
-        __solidus_method_counter_get::wrapper(),
-        __solidus_method_counter_get::ARITY,
-    )?;
-    counter_class.clone().define_method(
-        "increment",
-        __solidus_method_counter_increment::wrapper(),
-        __solidus_method_counter_increment::ARITY,
-    )?;
+    counter_class
+        .clone()
+        .define_singleton_method("new", __solidus_function_counter_new::wrapper())?;
+    counter_class
+        .clone()
+        .define_method("get", __solidus_method_counter_get::wrapper())?;
+    counter_class
+        .clone()
+        .define_method("increment", __solidus_method_counter_increment::wrapper())?;
 
     // Then store in OnceLock
     COUNTER_CLASS
@@ -256,26 +244,18 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     let container_class = RClass::try_convert(container_class_val)?;
 
     // Define all methods on the class using attribute macro generated modules
-    container_class.clone().define_singleton_method(
-        "new",
-        __solidus_function_container_new::wrapper(),
-        __solidus_function_container_new::ARITY,
-    )?;
-    container_class.clone().define_method(
-        "push",
-        __solidus_method_container_push::wrapper(),
-        __solidus_method_container_push::ARITY,
-    )?;
-    container_class.clone().define_method(
-        "len",
-        __solidus_method_container_len::wrapper(),
-        __solidus_method_container_len::ARITY,
-    )?;
-    container_class.clone().define_method(
-        "get",
-        __solidus_method_container_get::wrapper(),
-        __solidus_method_container_get::ARITY,
-    )?;
+    container_class
+        .clone()
+        .define_singleton_method("new", __solidus_function_container_new::wrapper())?;
+    container_class
+        .clone()
+        .define_method("push", __solidus_method_container_push::wrapper())?;
+    container_class
+        .clone()
+        .define_method("len", __solidus_method_container_len::wrapper())?;
+    container_class
+        .clone()
+        .define_method("get", __solidus_method_container_get::wrapper())?;
 
     // Then store in OnceLock
     CONTAINER_CLASS
@@ -52,8 +52,7 @@ fn concatenate_pinned<'ctx>(
 ) -> Result<Pin<&'ctx StackPinned<RString>>, Error> {
     let s1 = first.get().to_string()?;
     let s2 = second.get().to_string()?;
-    ctx.new_string(&format!("{}{}", s1, s2))
-        .map_err(Into::into)
+    ctx.new_string(&format!("{}{}", s1, s2)).map_err(Into::into)
 }
 
 /// Instance method example - uses `self` as first argument.
@@ -135,7 +134,7 @@ impl StringCollector {
         for s in &self.strings {
             // Get the RString from BoxValue (via Deref) and push a clone to the array
             // RString implements IntoValue, so we can push it directly
-            array.get().push((**s).clone());
+            array.get().push((**s).clone())?;
         }
         Ok(array)
     }
@@ -195,10 +194,7 @@ fn ruby_box_string<'ctx>(
 }
 
 /// Global function: add a string to the collector
-fn ruby_collect_string(
-    _ctx: &Context,
-    s: Pin<&StackPinned<RString>>,
-) -> Result<i64, Error> {
+fn ruby_collect_string(_ctx: &Context, s: Pin<&StackPinned<RString>>) -> Result<i64, Error> {
     let collector = get_collector();
     collector.add(s);
     Ok(collector.len() as i64)
@@ -281,7 +277,7 @@ fn ruby_demo_heap_boxing<'ctx>(
     for boxed in &boxed_values {
         // Get the RString from BoxValue (via Deref) and push a clone to the array
         // RString implements IntoValue, so we can push it directly
-        array.get().push((**boxed).clone());
+        array.get().push((**boxed).clone())?;
     }
 
     // BoxValues are automatically unregistered when dropped
@@ -298,57 +294,36 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     // ========================================================================
 
     // Stack pinning demos
-    ruby.define_global_function(
-        "process_string",
-        solidus::function!(ruby_process_string, 1),
-        1,
-    )?;
-    ruby.define_global_function(
-        "concat_strings",
-        solidus::function!(ruby_concat_strings, 2),
-        2,
-    )?;
+    ruby.define_global_function("process_string", solidus::function!(ruby_process_string, 1))?;
+    ruby.define_global_function("concat_strings", solidus::function!(ruby_concat_strings, 2))?;
 
     // Boxing demo
-    ruby.define_global_function("box_string", solidus::function!(ruby_box_string, 1), 1)?;
+    ruby.define_global_function("box_string", solidus::function!(ruby_box_string, 1))?;
 
     // String collector (demonstrates Vec<BoxValue<T>>)
-    ruby.define_global_function(
-        "collect_string",
-        solidus::function!(ruby_collect_string, 1),
-        1,
-    )?;
+    ruby.define_global_function("collect_string", solidus::function!(ruby_collect_string, 1))?;
     ruby.define_global_function(
         "collector_count",
         solidus::function!(ruby_collector_count, 0),
-        0,
-    )?;
-    ruby.define_global_function(
-        "collector_join",
-        solidus::function!(ruby_collector_join, 1),
-        1,
     )?;
+    ruby.define_global_function("collector_join", solidus::function!(ruby_collector_join, 1))?;
     ruby.define_global_function(
         "collector_to_array",
         solidus::function!(ruby_collector_to_array, 0),
-        0,
     )?;
     ruby.define_global_function(
         "collector_clear",
         solidus::function!(ruby_collector_clear, 0),
-        0,
     )?;
 
     // Demonstration functions
     ruby.define_global_function(
         "demo_stack_pinning",
         solidus::function!(ruby_demo_stack_pinning, 0),
-        0,
     )?;
     ruby.define_global_function(
         "demo_heap_boxing",
         solidus::function!(ruby_demo_heap_boxing, 0),
-        0,
     )?;
 
     // ========================================================================
@@ -356,7 +331,7 @@ fn init_solidus(ruby: &Ruby) -> Result<(), Error> {
     // ========================================================================
 
     let string_class = RClass::try_convert(ruby.class_string())?;
-    string_class.define_method("append_solidus", solidus::method!(append_to_self, 1), 1)?;
+    string_class.define_method("append_solidus", solidus::method!(append_to_self, 1))?;
 
     Ok(())
 }